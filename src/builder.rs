@@ -1,8 +1,13 @@
 //! contains the QuickJsRuntimeBuilder which may be used to instantiate a new QuickjsRuntimeFacade
 
 use crate::facades::QuickJsRuntimeFacade;
+#[cfg(feature = "console")]
+use crate::features::console::{ConsoleConfig, ConsoleLevel};
+use crate::quickjs_utils::allocators::{BoxedAllocator, QuickJsAllocator};
+use crate::quickjs_utils::promises::RejectionAction;
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
 
 use crate::jsutils::modules::{CompiledModuleLoader, NativeModuleLoader, ScriptModuleLoader};
 use crate::jsutils::{JsError, ScriptPreProcessor};
@@ -28,10 +33,24 @@ pub struct QuickJsRuntimeBuilder {
     pub(crate) opt_gc_threshold: Option<u64>,
     pub(crate) opt_max_stack_size: Option<u64>,
     pub(crate) opt_gc_interval: Option<Duration>,
+    pub(crate) opt_alloc_budget_per_eval: Option<u64>,
+    pub(crate) opt_max_pending_promises: Option<usize>,
+    pub(crate) custom_allocator: Option<BoxedAllocator>,
     pub(crate) runtime_init_hooks: EsRuntimeInitHooks,
     pub(crate) script_pre_processors: Vec<Box<dyn ScriptPreProcessor + Send>>,
     #[allow(clippy::type_complexity)]
     pub(crate) interrupt_handler: Option<Box<dyn Fn(&QuickJsRuntimeAdapter) -> bool + Send>>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) unhandled_rejection_handler:
+        Option<Box<dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> RejectionAction + Send>>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) atom_count_threshold: Option<(i64, Box<dyn Fn(&QuickJsRuntimeAdapter, i64) + Send>)>,
+    pub(crate) opt_atom_count_check_interval: Option<Duration>,
+    pub(crate) proxy_method_stats: bool,
+    #[cfg(feature = "web")]
+    pub(crate) web_features: bool,
+    #[cfg(feature = "console")]
+    pub(crate) console_config: ConsoleConfig,
 }
 
 impl QuickJsRuntimeBuilder {
@@ -50,9 +69,20 @@ impl QuickJsRuntimeBuilder {
             opt_gc_threshold: None,
             opt_max_stack_size: None,
             opt_gc_interval: None,
+            opt_alloc_budget_per_eval: None,
+            opt_max_pending_promises: None,
+            custom_allocator: None,
             runtime_init_hooks: vec![],
             script_pre_processors: vec![],
             interrupt_handler: None,
+            unhandled_rejection_handler: None,
+            atom_count_threshold: None,
+            opt_atom_count_check_interval: None,
+            proxy_method_stats: false,
+            #[cfg(feature = "web")]
+            web_features: false,
+            #[cfg(feature = "console")]
+            console_config: ConsoleConfig::default(),
         }
     }
 
@@ -180,6 +210,20 @@ impl QuickJsRuntimeBuilder {
         self
     }
 
+    /// set the max number of bytes a single eval may allocate in total, unlike memory_limit this also counts memory which was allocated and freed again
+    /// when the budget is exceeded allocations will fail and the eval will fail with an out of memory error
+    pub fn alloc_budget_per_eval(mut self, bytes: u64) -> Self {
+        self.opt_alloc_budget_per_eval = Some(bytes);
+        self
+    }
+
+    /// use a custom allocator for all allocations of the runtime, e.g. to account for or limit the memory used by QuickJS
+    /// see [QuickJsAllocator](crate::quickjs_utils::allocators::QuickJsAllocator) for an example
+    pub fn custom_allocator<A: QuickJsAllocator + Send + 'static>(mut self, allocator: A) -> Self {
+        self.custom_allocator = Some(Box::new(allocator));
+        self
+    }
+
     /// add an interrupt handler, this will be called several times during script execution and may be used to cancel a running script
     pub fn set_interrupt_handler<I: Fn(&QuickJsRuntimeAdapter) -> bool + Send + 'static>(
         mut self,
@@ -188,6 +232,143 @@ impl QuickJsRuntimeBuilder {
         self.interrupt_handler = Some(Box::new(interrupt_handler));
         self
     }
+
+    /// set a handler which decides what to do with promises which were rejected while no rejection handler was added to them
+    /// by default these rejections are logged as errors
+    /// when the handler returns RejectionAction::Throw the reason is passed as error to the callback of QuickJsRuntimeAdapter::run_pending_jobs_with
+    pub fn on_unhandled_rejection<H>(mut self, handler: H) -> Self
+    where
+        H: Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> RejectionAction + Send + 'static,
+    {
+        self.unhandled_rejection_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// set a callback which is invoked when the number of atoms (interned strings like property names) in the runtime exceeds a threshold
+    /// this may be used to detect workloads which create a lot of unique property names, the callback receives the current atom count
+    /// the atom count is checked at most once per second, see atom_count_check_interval
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// let rt = QuickJsRuntimeBuilder::new()
+    ///     .atom_count_threshold(100000, |_q_js_rt, atom_count| {
+    ///         log::warn!("runtime has {} atoms", atom_count);
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn atom_count_threshold<C>(mut self, threshold: i64, callback: C) -> Self
+    where
+        C: Fn(&QuickJsRuntimeAdapter, i64) + Send + 'static,
+    {
+        self.atom_count_threshold = Some((threshold, Box::new(callback)));
+        self
+    }
+
+    /// set the minimal time between two checks of the atom_count_threshold, defaults to 1 second
+    /// computing the atom count walks the whole heap so a short interval slows down runtimes with a large heap
+    pub fn atom_count_check_interval(mut self, interval: Duration) -> Self {
+        self.opt_atom_count_check_interval = Some(interval);
+        self
+    }
+
+    /// limit the number of promises which are waiting for a native producer (e.g. created with QuickJsRealmAdapter::create_resolving_promise)
+    /// when the runtime already has max pending promises a new promise is rejected immediately with a RangeError and its producer is not run
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// let rt = QuickJsRuntimeBuilder::new().max_pending_promises(10000).build();
+    /// ```
+    pub fn max_pending_promises(mut self, max: usize) -> Self {
+        self.opt_max_pending_promises = Some(max);
+        self
+    }
+
+    /// record the call count and total duration of Proxy methods, the stats can be retrieved with reflection::method_stats() from the worker thread of the runtime
+    /// this is disabled by default to avoid the overhead of timing every call
+    pub fn proxy_method_stats(mut self, enabled: bool) -> Self {
+        self.proxy_method_stats = enabled;
+        self
+    }
+
+    /// install structuredClone, atob, btoa and performance in every realm, see [web](crate::features::web)
+    /// the other web APIs of this crate (like console, setTimeout and EventTarget) are separate cargo features which are not affected by this
+    #[cfg(feature = "web")]
+    pub fn with_web_features(mut self) -> Self {
+        self.web_features = true;
+        self
+    }
+
+    /// group the digits of numbers formatted with %d or %i by console methods, e.g. with ',' 1000000 is logged as 1,000,000
+    #[cfg(feature = "console")]
+    pub fn console_grouping_separator(mut self, separator: char) -> Self {
+        self.console_config.grouping_separator = Some(separator);
+        self
+    }
+
+    /// when enabled a plain object passed as last argument to a console method is rendered as logfmt style key=value pairs
+    /// e.g. console.info('done', {ms: 5, ok: true}) is logged as "done ms=5 ok=true"
+    #[cfg(feature = "console")]
+    pub fn console_logfmt(mut self, enabled: bool) -> Self {
+        self.console_config.logfmt = enabled;
+        self
+    }
+
+    /// log the output of a console level under a specific log target, e.g. to log console.error under "js.error"
+    /// levels which are not mapped are logged under the target of the console module
+    #[cfg(feature = "console")]
+    pub fn console_log_target(mut self, level: ConsoleLevel, target: &str) -> Self {
+        self.console_config
+            .log_targets
+            .insert(level, target.to_string());
+        self
+    }
+
+    /// set the max number of bytes the console methods may output during a single eval
+    /// once exceeded further output of that eval is dropped after a single "[console output truncated]" notice
+    #[cfg(feature = "console")]
+    pub fn console_byte_budget_per_eval(mut self, bytes: usize) -> Self {
+        self.console_config.byte_budget_per_eval = Some(bytes);
+        self
+    }
+
+    /// when enabled callbacks scheduled with setTimeout, setInterval or setImmediate log at the console.group() level
+    /// which was active when they were scheduled instead of the level which is active when they run
+    #[cfg(feature = "console")]
+    pub fn console_async_group_context(mut self, enabled: bool) -> Self {
+        self.console_config.async_group_context = enabled;
+        self
+    }
+
+    /// translate the CSS of %c directives in console messages to ANSI escape codes for terminal output
+    /// by default %c directives and their style arguments are stripped from the output
+    #[cfg(feature = "console")]
+    pub fn console_ansi_styles(mut self, enabled: bool) -> Self {
+        self.console_config.ansi_styles = enabled;
+        self
+    }
+
+    /// set the max number of values (nodes) which are visited when serializing a single object argument of a console method
+    /// this guards against huge object graphs, output which exceeds the limit ends with "… (output limited)"
+    #[cfg(feature = "console")]
+    pub fn console_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.console_config.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// set the max time formatting a single argument of a console method may take (default is 1 second)
+    /// this guards against toString or toJSON methods which never return, arguments which take longer are logged as an abort marker
+    #[cfg(feature = "console")]
+    pub fn console_format_timeout(mut self, timeout: Duration) -> Self {
+        self.console_config.format_timeout = Some(timeout);
+        self
+    }
+
+    /// use another decimal separator for numbers formatted with %f by console methods, e.g. with ',' 1.5 is logged as 1,5
+    #[cfg(feature = "console")]
+    pub fn console_decimal_separator(mut self, separator: char) -> Self {
+        self.console_config.decimal_separator = Some(separator);
+        self
+    }
 }
 
 impl Default for QuickJsRuntimeBuilder {
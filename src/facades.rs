@@ -2,8 +2,8 @@
 
 use crate::builder::QuickJsRuntimeBuilder;
 use crate::jsutils::{JsError, Script};
-use crate::quickjs_utils::{functions, objects};
-use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjs_utils::{allocators, errors, functions, objects, promises};
+use crate::quickjsrealmadapter::{EvalOptions, QuickJsRealmAdapter};
 use crate::quickjsruntimeadapter::{
     CompiledModuleLoaderAdapter, MemoryUsage, NativeModuleLoaderAdapter, QuickJsRuntimeAdapter,
     ScriptModuleLoaderAdapter, QJS_RT,
@@ -17,7 +17,9 @@ use libquickjs_sys as q;
 use std::future::Future;
 use std::pin::Pin;
 use std::rc::Rc;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 use tokio::task::JoinError;
 
 lazy_static! {
@@ -174,9 +176,18 @@ impl QuickJsRuntimeFacade {
             }),
         };
 
-        ret.exe_task_in_event_loop(|| {
-            let rt_ptr = unsafe { q::JS_NewRuntime() };
-            let rt = QuickJsRuntimeAdapter::new(rt_ptr);
+        let opt_alloc_budget = builder.opt_alloc_budget_per_eval;
+        let opt_custom_allocator = builder.custom_allocator.take().map(Box::new);
+
+        ret.exe_task_in_event_loop(move || {
+            let rt_ptr = if opt_alloc_budget.is_some() || opt_custom_allocator.is_some() {
+                allocators::set_eval_budget(opt_alloc_budget.map(|budget| budget as usize));
+                unsafe { allocators::new_runtime(opt_custom_allocator.as_deref()) }
+            } else {
+                unsafe { q::JS_NewRuntime() }
+            };
+            let mut rt = QuickJsRuntimeAdapter::new(rt_ptr);
+            rt.custom_allocator = opt_custom_allocator;
             QuickJsRuntimeAdapter::init_rt_for_current_thread(rt);
             functions::init_statics();
             reflection::init_statics();
@@ -198,7 +209,12 @@ impl QuickJsRuntimeFacade {
             feature = "settimeout",
             feature = "setinterval",
             feature = "console",
-            feature = "setimmediate"
+            feature = "setimmediate",
+            feature = "eventtarget",
+            feature = "require",
+            feature = "hostlog",
+            feature = "queuemicrotask",
+            feature = "textencoding"
         ))]
         {
             let res = crate::features::init(&ret);
@@ -207,6 +223,16 @@ impl QuickJsRuntimeFacade {
             }
         }
 
+        #[cfg(feature = "web")]
+        {
+            if builder.web_features {
+                let res = ret.exe_rt_task_in_event_loop(crate::features::web::init);
+                if res.is_err() {
+                    panic!("could not init web features: {}", res.err().unwrap());
+                }
+            }
+        }
+
         if let Some(interval) = builder.opt_gc_interval {
             let rti_ref: Weak<QuickjsRuntimeFacadeInner> = Arc::downgrade(&ret.inner);
             std::thread::spawn(move || loop {
@@ -266,6 +292,19 @@ impl QuickJsRuntimeFacade {
                 if let Some(interrupt_handler) = builder.interrupt_handler {
                     q_js_rt.set_interrupt_handler(interrupt_handler);
                 }
+                if let Some(handler) = builder.unhandled_rejection_handler {
+                    q_js_rt.set_unhandled_rejection_handler(handler);
+                }
+                if let Some((threshold, callback)) = builder.atom_count_threshold {
+                    q_js_rt.set_atom_count_threshold(threshold, callback);
+                }
+                if let Some(interval) = builder.opt_atom_count_check_interval {
+                    q_js_rt.set_atom_count_check_interval(interval);
+                }
+                q_js_rt.set_max_pending_promises(builder.opt_max_pending_promises);
+                crate::reflection::set_method_stats_enabled(builder.proxy_method_stats);
+                #[cfg(feature = "console")]
+                crate::features::console::set_config(builder.console_config);
             })
         });
 
@@ -714,6 +753,73 @@ impl QuickJsRuntimeFacade {
         })
     }
 
+    /// Evaluate a script with EvalOptions
+    #[allow(clippy::type_complexity)]
+    pub fn eval_with_options(
+        &self,
+        realm_name: Option<&str>,
+        script: Script,
+        options: EvalOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<JsValueFacade, JsError>>>> {
+        self.loop_realm(realm_name, move |_rt, realm| {
+            let res = realm.eval_with_options(script, &options)?;
+            realm.to_js_value_facade(&res)
+        })
+    }
+
+    /// Evaluate a script with EvalOptions and return the result synchronously
+    /// # example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::quickjsrealmadapter::EvalOptions;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// let options = EvalOptions::new().max_result_bytes(64);
+    /// let res = rt.eval_sync_with_options(None, Script::new("my_file.js", "({a: 1});"), options.clone()).expect("script failed");
+    /// assert!(res.is_js_object());
+    /// let err = rt.eval_sync_with_options(None, Script::new("my_file.js", "'x'.repeat(100);"), options);
+    /// assert!(err.is_err());
+    /// ```
+    pub fn eval_sync_with_options(
+        &self,
+        realm_name: Option<&str>,
+        script: Script,
+        options: EvalOptions,
+    ) -> Result<JsValueFacade, JsError> {
+        self.loop_realm_sync(realm_name, move |_rt, realm| {
+            let res = realm.eval_with_options(script, &options)?;
+            realm.to_js_value_facade(&res)
+        })
+    }
+
+    /// Evaluate a script and return the result synchronously, every console line output during the eval is prefixed with the trace id
+    /// e.g. "[req-123] ", callbacks which are scheduled with setTimeout, setInterval or setImmediate during the eval and
+    /// promise jobs which are queued during the eval log with the same trace id
+    /// # example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// let script = Script::new("my_file.js", "console.log('handling request'); setTimeout(() => {console.log('done');}, 10);");
+    /// rt.eval_with_trace_id_sync(None, script, "req-123").expect("script failed");
+    /// ```
+    #[cfg(feature = "console")]
+    pub fn eval_with_trace_id_sync(
+        &self,
+        realm_name: Option<&str>,
+        script: Script,
+        trace_id: &str,
+    ) -> Result<JsValueFacade, JsError> {
+        let trace_id = trace_id.to_string();
+        self.loop_realm_sync(realm_name, move |rt, realm| {
+            let _trace_id = crate::features::console::TraceIdGuard::set(Some(trace_id));
+            let res = realm.eval(script)?;
+            // run the jobs queued by the script while the trace id is still active
+            rt.run_pending_jobs_if_any();
+            realm.to_js_value_facade(&res)
+        })
+    }
+
     /// evaluate a module, you need this if you want to compile a script that contains static imports
     /// e.g.
     /// ```javascript
@@ -802,6 +908,75 @@ impl QuickJsRuntimeFacade {
         })
     }
 
+    /// evaluate a module synchronously and return its namespace object, this may be used to read the exports of the module
+    /// # example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// let script = Script::new("/opt/files/my_consts.mjs", "export const answer = 42;");
+    /// let ns = rt.eval_module_namespace_sync(None, script).ok().expect("module failed");
+    /// if let quickjs_runtime::values::JsValueFacade::JsObject { cached_object } = ns {
+    ///     let exports = cached_object.get_object_sync().expect("could not get exports");
+    ///     assert_eq!(exports.get("answer").unwrap().get_i32(), 42);
+    /// } else {
+    ///     panic!("namespace was not an object");
+    /// }
+    /// ```
+    pub fn eval_module_namespace_sync(
+        &self,
+        realm_name: Option<&str>,
+        script: Script,
+    ) -> Result<JsValueFacade, JsError> {
+        self.loop_realm_sync(realm_name, |_rt, realm| {
+            let namespace = realm.eval_module_namespace(script)?;
+            realm.to_js_value_facade(&namespace)
+        })
+    }
+
+    /// evaluate a script and then invoke its global `main` function with the passed args as an Array of strings
+    /// if main returns a Promise this will wait for it to settle and return its result
+    /// # example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// let script = Script::new("my_cli.js", "function main(args){return args.join('-');}");
+    /// let res = rt.eval_main_sync(None, script, vec!["a".to_string(), "b".to_string()]).ok().expect("script failed");
+    /// assert_eq!(res.get_str(), "a-b");
+    /// ```
+    pub fn eval_main_sync(
+        &self,
+        realm_name: Option<&str>,
+        script: Script,
+        args: Vec<String>,
+    ) -> Result<JsValueFacade, JsError> {
+        let res = self.loop_realm_sync(realm_name, move |_rt, realm| {
+            realm.eval(script)?;
+            let args_array = realm.create_array()?;
+            for (index, arg) in args.iter().enumerate() {
+                let arg_ref = realm.create_string(arg.as_str())?;
+                realm.set_array_element(&args_array, index as u32, &arg_ref)?;
+            }
+            let main_res = realm.invoke_function_by_name(&[], "main", &[args_array])?;
+            realm.to_js_value_facade(&main_res)
+        })?;
+
+        match res {
+            JsValueFacade::JsPromise { cached_promise } => {
+                match cached_promise.get_promise_result_sync()? {
+                    Ok(resolution) => Ok(resolution),
+                    Err(JsValueFacade::JsError { val }) => Err(val),
+                    Err(rejection) => Err(JsError::new_string(format!(
+                        "main was rejected: {}",
+                        rejection.stringify()
+                    ))),
+                }
+            }
+            _ => Ok(res),
+        }
+    }
+
     /// invoke a function in the engine and get the result synchronously
     /// # example
     /// ```rust
@@ -896,6 +1071,104 @@ impl QuickJsRuntimeFacade {
         })
     }
 
+    /// invoke a function and, if it returns a Promise, wait until that Promise settles
+    /// the event loop keeps running while waiting so timers and other jobs the function depends on are run
+    /// this is the common case for async handlers
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::values::JsValueConvertable;
+    /// use std::time::Duration;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// let script = Script::new("my_file.es", "this.handler = async function(a) {return a * 2;};");
+    /// rt.eval_sync(None, script).ok().expect("script failed");
+    /// let res = rt.call_and_await(None, &[], "handler", vec![21.to_js_value_facade()], Duration::from_secs(1)).ok().expect("func failed");
+    /// assert_eq!(res.get_i32(), 42);
+    /// ```
+    pub fn call_and_await(
+        &self,
+        realm_name: Option<&str>,
+        namespace: &[&str],
+        method_name: &str,
+        args: Vec<JsValueFacade>,
+        timeout: Duration,
+    ) -> Result<JsValueFacade, JsError> {
+        let movable_namespace: Vec<String> = namespace.iter().map(|s| s.to_string()).collect();
+        let movable_method_name = method_name.to_string();
+        let (tx, rx) = mpsc::channel();
+
+        self.loop_realm_sync(realm_name, move |_rt, realm| {
+            let res = || {
+                let args_adapters = args
+                    .into_iter()
+                    .map(|jsvf| realm.from_js_value_facade(jsvf))
+                    .collect::<Result<Vec<QuickJsValueAdapter>, JsError>>()?;
+
+                let namespace = movable_namespace
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<&str>>();
+
+                let res = realm.invoke_function_by_name(
+                    namespace.as_slice(),
+                    movable_method_name.as_str(),
+                    args_adapters.as_slice(),
+                )?;
+
+                if !promises::is_promise_q(realm, &res) {
+                    let _ = tx.send(realm.to_js_value_facade(&res));
+                    return Ok(());
+                }
+
+                let then_tx = tx.clone();
+                let then_func = realm.create_function(
+                    "then",
+                    move |realm, _this, args| {
+                        let _ = then_tx.send(realm.to_js_value_facade(&args[0]));
+                        realm.create_undefined()
+                    },
+                    1,
+                )?;
+                let catch_tx = tx.clone();
+                let catch_func = realm.create_function(
+                    "catch",
+                    move |realm, _this, args| {
+                        let reason = &args[0];
+                        let err = if errors::is_error_q(realm, reason) {
+                            unsafe { errors::error_to_js_error(realm.context, reason) }
+                        } else {
+                            JsError::new_string(functions::call_to_string_q(realm, reason)?)
+                        };
+                        let _ = catch_tx.send(Err(err));
+                        realm.create_undefined()
+                    },
+                    1,
+                )?;
+                promises::add_promise_reactions_q(
+                    realm,
+                    &res,
+                    Some(then_func),
+                    Some(catch_func),
+                    None,
+                )
+            };
+            if let Err(err) = res() {
+                let _ = tx.send(Err(err));
+            }
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(res) => res,
+            Err(RecvTimeoutError::Timeout) => Err(JsError::new_string(format!(
+                "promise returned by {method_name} did not settle within {timeout:?}"
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(JsError::new_string(format!(
+                "promise returned by {method_name} was dropped before it settled"
+            ))),
+        }
+    }
+
     pub fn invoke_function_void(
         &self,
         realm_name: Option<&str>,
@@ -951,7 +1224,7 @@ pub mod tests {
     use crate::jsutils::JsError;
     use crate::jsutils::Script;
     use crate::quickjs_utils::{primitives, promises};
-    use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+    use crate::quickjsrealmadapter::{EvalOptions, QuickJsRealmAdapter};
     use crate::quickjsvalueadapter::QuickJsValueAdapter;
     use crate::values::{JsValueConvertable, JsValueFacade};
     use backtrace::Backtrace;
@@ -1136,6 +1409,111 @@ pub mod tests {
         assert_eq!(res.get_i32(), 14);
     }
 
+    #[test]
+    fn test_eval_max_result_bytes() {
+        let rt = init_test_rt();
+        let options = EvalOptions::new().max_result_bytes(1024);
+        let err = rt
+            .eval_sync_with_options(
+                None,
+                Script::new(
+                    "test_eval_max_result_bytes.es",
+                    "Array.from({length: 100000}, (_, i) => ({index: i}));",
+                ),
+                options.clone(),
+            )
+            .expect_err("huge result was not rejected");
+        assert_eq!(err.get_name(), "RangeError");
+        assert!(err
+            .get_message()
+            .contains("exceeds the maximum size of 1024 bytes"));
+
+        let res = rt
+            .eval_sync_with_options(
+                None,
+                Script::new("test_eval_max_result_bytes.es", "[1, 2, {a: 'b'}];"),
+                options,
+            )
+            .expect("small result was rejected");
+        assert!(res.is_js_array());
+    }
+
+    #[test]
+    fn test_eval_module_namespace_sync() {
+        let rt = init_test_rt();
+        let namespace = rt
+            .eval_module_namespace_sync(
+                None,
+                Script::new(
+                    "/test/test_eval_module_namespace.mjs",
+                    "export const a = 1; export default function(x) {return x * 3;}",
+                ),
+            )
+            .expect("module failed");
+        let exports = match namespace {
+            JsValueFacade::JsObject { cached_object } => cached_object
+                .get_object_sync()
+                .expect("could not get exports"),
+            _ => panic!("namespace was not an object"),
+        };
+        assert_eq!(exports.get("a").expect("no a").get_i32(), 1);
+        match exports.get("default").expect("no default") {
+            JsValueFacade::JsFunction { cached_function } => {
+                let res = cached_function
+                    .invoke_function_sync(vec![4.to_js_value_facade()])
+                    .expect("default function failed");
+                assert_eq!(res.get_i32(), 12);
+            }
+            _ => panic!("default export was not a function"),
+        }
+
+        // the same path in another realm is a separate module with its own namespace
+        rt.create_context("test_eval_module_namespace_realm")
+            .expect("could not create realm");
+        let namespace = rt
+            .eval_module_namespace_sync(
+                Some("test_eval_module_namespace_realm"),
+                Script::new(
+                    "/test/test_eval_module_namespace.mjs",
+                    "export const a = 2;",
+                ),
+            )
+            .expect("module failed");
+        let exports = match namespace {
+            JsValueFacade::JsObject { cached_object } => cached_object
+                .get_object_sync()
+                .expect("could not get exports"),
+            _ => panic!("namespace was not an object"),
+        };
+        assert_eq!(exports.get("a").expect("no a").get_i32(), 2);
+        assert!(exports.get("default").is_none());
+    }
+
+    #[test]
+    fn test_eval_main_sync() {
+        let rt = init_test_rt();
+        let res = rt
+            .eval_main_sync(
+                None,
+                Script::new("test_main.es", "function main(args){return args.length;}"),
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            )
+            .expect("main failed");
+        assert_eq!(res.get_i32(), 3);
+
+        let res = rt
+            .eval_main_sync(
+                None,
+                Script::new(
+                    "test_main_async.es",
+                    "globalThis.main = async function(args){return args[1];}",
+                ),
+                vec!["a".to_string(), "b".to_string()],
+            )
+            .expect("async main failed");
+        assert_eq!(res.get_str(), "b");
+    }
+
     #[test]
     fn t1234() {
         // test stack overflow
@@ -1219,6 +1597,65 @@ pub mod tests {
         std::thread::sleep(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_call_and_await() {
+        let rt = init_test_rt();
+        rt.eval_sync(
+            None,
+            Script::new(
+                "test_call_and_await.es",
+                r#"
+            this.call_and_await_test = {
+                delayed: async function(a, b) {
+                    await new Promise((resolve) => setTimeout(resolve, 50));
+                    return a + b;
+                },
+                failing: async function() {
+                    await new Promise((resolve) => setTimeout(resolve, 10));
+                    throw new TypeError('handler failed');
+                },
+                never: function() {
+                    return new Promise(() => {});
+                },
+                sync: function() {
+                    return 'no promise';
+                }
+            };
+        "#,
+            ),
+        )
+        .expect("script failed");
+
+        let ns = &["call_and_await_test"];
+        let timeout = Duration::from_secs(2);
+        let res = rt
+            .call_and_await(
+                None,
+                ns,
+                "delayed",
+                vec![2.to_js_value_facade(), 3.to_js_value_facade()],
+                timeout,
+            )
+            .expect("call failed");
+        assert_eq!(res.get_i32(), 5);
+
+        let err = rt
+            .call_and_await(None, ns, "failing", vec![], timeout)
+            .expect_err("call should have failed");
+        assert_eq!(err.get_name(), "TypeError");
+        assert_eq!(err.get_message(), "handler failed");
+
+        let res = rt
+            .call_and_await(None, ns, "sync", vec![], timeout)
+            .expect("call failed");
+        assert_eq!(res.get_str(), "no promise");
+
+        let err = rt
+            .call_and_await(None, ns, "never", vec![], Duration::from_millis(100))
+            .expect_err("call should have timed out");
+        assert!(err.get_message().contains("did not settle"));
+    }
+
     #[test]
     fn test_module_sync() {
         log::info!("> test_module_sync");
@@ -1317,6 +1754,36 @@ pub mod abstraction_tests {
         }
     }
 
+    #[test]
+    fn test_loop_realm_from_other_thread() {
+        let rt = std::sync::Arc::new(QuickJsRuntimeBuilder::new().build());
+        let rt2 = rt.clone();
+        let (caller_thread, js_thread) = std::thread::spawn(move || {
+            let caller_thread = std::thread::current().id();
+            let js_thread = block_on(rt2.loop_realm(None, |_rt_adapter, realm_adapter| {
+                let global = realm_adapter.get_global().expect("could not get global");
+                let value = realm_adapter
+                    .create_string("set from another thread")
+                    .expect("could not create string");
+                realm_adapter
+                    .set_object_property(&global, "spawned", &value)
+                    .expect("could not set spawned");
+                std::thread::current().id()
+            }));
+            (caller_thread, js_thread)
+        })
+        .join()
+        .expect("thread panicked");
+        assert_ne!(caller_thread, js_thread);
+        let val = rt
+            .eval_sync(
+                None,
+                Script::new("test_loop_realm_from_other_thread.js", "spawned;"),
+            )
+            .expect("script failed");
+        assert_eq!(val.get_str(), "set from another thread");
+    }
+
     #[tokio::test]
     async fn test_serde() {
         let json = r#"
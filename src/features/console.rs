@@ -5,7 +5,13 @@
 //! * console.info()
 //! * console.error()
 //! * console.warning()
-//! * console.trace()
+//! * console.trace() (the message is followed by the JS call stack at the call site)
+//! * console.assert()
+//! * console.dir()
+//! * console.dirxml()
+//! * console.count() / console.countReset()
+//! * console.time() / console.timeLog() / console.timeEnd()
+//! * console.group() / console.groupCollapsed() / console.groupEnd()
 //!
 //! The methods use rust's log crate to output messages. e.g. console.info() uses the log::info!() macro
 //! so the console messages should appear in the log you initialized from rust
@@ -20,9 +26,39 @@
 //!
 //! The string substitution you can use are
 //! * %o or %O Outputs a JavaScript object (serialized)
-//! * %d or %i Outputs an integer. Number formatting is supported, for example  console.log("Foo %.2d", 1.1) will output the number as two significant figures with a leading 0: Foo 01
+//! * %d or %i Outputs an integer. Number formatting is supported, for example  console.log("Foo %.2d", 1.1) will output the number as two significant figures with a leading 0: Foo 01, a width pads with spaces: %5d right aligns and %-5d left aligns the number in 5 characters
 //! * %s Outputs a string (will attempt to call .toString() on objects, use %o to output a serialized JSON string)
 //! * %f Outputs a floating-point value. Formatting is supported, for example  console.log("Foo %.2f", 1.1) will output the number to 2 decimal places: Foo 1.10
+//! * %c Applies CSS styling to the text which follows, by default the directive and its style argument are stripped,
+//!   QuickJsRuntimeBuilder::console_ansi_styles translates the CSS to ANSI escape codes for terminal output
+//!
+//! Only the first argument is scanned for substitutions, substituted values are output verbatim and are never scanned again
+//! e.g. console.log('%s and %d', '%d', 1) will output '%d and 1'
+//!
+//! The formatting of the console methods is available to native functions as [format_args]
+//!
+//! By default all messages are logged under the target of this module, QuickJsRuntimeBuilder::console_log_target can be used to log a console level under another target
+//!
+//! console.group() indents the output of the console methods until the matching console.groupEnd()
+//! When QuickJsRuntimeBuilder::console_async_group_context is enabled callbacks scheduled with setTimeout, setInterval or setImmediate
+//! log at the group level which was active when they were scheduled instead of the group level which is active when they run
+//!
+//! QuickJsRuntimeFacade::eval_with_trace_id_sync prefixes every console line of an eval with a trace id, e.g. "[req-123] "
+//! callbacks scheduled with setTimeout, setInterval or setImmediate during that eval log with the same trace id
+//!
+//! console.dir(obj, {depth: 2}) outputs a serialized object where objects and arrays nested deeper than depth (default 2)
+//! are output as "[Object]" or "[Array]", pass {depth: null} to output the whole object
+//!
+//! console.dirxml(obj) outputs the whole object as a tree with every member on its own line at info level,
+//! values which are not objects are output like console.log() would output them
+//!
+//! QuickJsRuntimeBuilder::console_max_nodes limits the number of values which are visited when serializing an object argument
+//!
+//! With the optional print feature a print() global is added which, like print() in the standalone qjs interpreter,
+//! joins its arguments with spaces and logs them as a single line at info level, e.g. print('a', 1, true) outputs 'a 1 true'
+//!
+//! When QuickJsRuntimeBuilder::console_logfmt is enabled a plain object passed as last argument is rendered as key=value pairs
+//! e.g. console.info('done', {ms: 5, ok: true}) will output 'done ms=5 ok=true'
 //! # Example
 //! ```rust
 //! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
@@ -44,15 +80,315 @@
 use crate::jsutils::{JsError, JsValueType};
 use crate::quickjs_utils;
 use crate::quickjs_utils::functions::call_to_string;
-use crate::quickjs_utils::json::stringify;
-use crate::quickjs_utils::{functions, json, parse_args, primitives};
+use crate::quickjs_utils::interrupthandler::DeadlineGuard;
+use crate::quickjs_utils::json::{
+    safe_stringify, safe_stringify_limited, safe_stringify_nodes, safe_stringify_tree,
+};
+use crate::quickjs_utils::{functions, objects, parse_args};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use crate::reflection::Proxy;
 use libquickjs_sys as q;
-use log::LevelFilter;
+use log::{Level, LevelFilter, Log, Record};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// options for the console feature, these are set from the QuickJsRuntimeBuilder
+#[derive(Clone)]
+pub(crate) struct ConsoleConfig {
+    /// separator used to group the digits of %d and %i values per thousand
+    pub(crate) grouping_separator: Option<char>,
+    /// separator used instead of '.' for %f values
+    pub(crate) decimal_separator: Option<char>,
+    /// max time formatting a single argument may take, this guards against toString or toJSON methods which never return
+    pub(crate) format_timeout: Option<Duration>,
+    /// render a plain object passed as last argument as logfmt style key=value pairs
+    pub(crate) logfmt: bool,
+    /// max number of bytes a single eval may output to the console
+    pub(crate) byte_budget_per_eval: Option<usize>,
+    /// log targets per console level, levels which are not mapped log under the target of this module
+    pub(crate) log_targets: HashMap<ConsoleLevel, String>,
+    /// capture the group level when a callback is scheduled and log at that level when it runs
+    pub(crate) async_group_context: bool,
+    /// max number of values visited when serializing a single argument
+    pub(crate) max_nodes: Option<usize>,
+    /// translate the css of %c directives to ansi escape codes instead of stripping them
+    pub(crate) ansi_styles: bool,
+}
+
+impl Default for ConsoleConfig {
+    fn default() -> Self {
+        Self {
+            grouping_separator: None,
+            decimal_separator: None,
+            format_timeout: Some(Duration::from_secs(1)),
+            logfmt: false,
+            byte_budget_per_eval: None,
+            log_targets: HashMap::new(),
+            async_group_context: false,
+            max_nodes: None,
+            ansi_styles: false,
+        }
+    }
+}
+
+/// the console methods, used to map the output of a console method to a log target
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ConsoleLevel {
+    Log,
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleLevel {
+    fn log_level(&self) -> Level {
+        match self {
+            ConsoleLevel::Log => Level::Info,
+            ConsoleLevel::Trace => Level::Trace,
+            ConsoleLevel::Debug => Level::Debug,
+            ConsoleLevel::Info => Level::Info,
+            ConsoleLevel::Warn => Level::Warn,
+            ConsoleLevel::Error => Level::Error,
+        }
+    }
+}
+
+thread_local! {
+    static CONSOLE_CONFIG: RefCell<ConsoleConfig> = RefCell::new(ConsoleConfig::default());
+}
+
+pub(crate) fn set_config(config: ConsoleConfig) {
+    CONSOLE_CONFIG.with(|rc| *rc.borrow_mut() = config);
+}
+
+fn with_config<R, C: FnOnce(&ConsoleConfig) -> R>(consumer: C) -> R {
+    CONSOLE_CONFIG.with(|rc| consumer(&rc.borrow()))
+}
+
+/// console state which is kept per realm, this is dropped together with the realm
+#[derive(Default)]
+pub(crate) struct ConsoleRealmState {
+    counters: HashMap<String, u64>,
+    timers: HashMap<String, Instant>,
+    // number of console.group() calls which were not yet ended
+    group_depth: usize,
+}
+
+impl ConsoleRealmState {
+    pub(crate) fn counters(&self) -> HashMap<String, u64> {
+        self.counters.clone()
+    }
+    pub(crate) fn timers(&self) -> Vec<(String, Duration)> {
+        let mut timers: Vec<(String, Duration)> = self
+            .timers
+            .iter()
+            .map(|(label, start)| (label.clone(), start.elapsed()))
+            .collect();
+        timers.sort_by(|a, b| a.0.cmp(&b.0));
+        timers
+    }
+}
+
+thread_local! {
+    // bytes output by the current eval, None if no eval is running
+    static EVAL_OUTPUT_BYTES: Cell<Option<usize>> = Cell::new(None);
+    static EVAL_OUTPUT_TRUNCATED: Cell<bool> = Cell::new(false);
+}
+
+const GROUP_INDENT: &str = "  ";
+
+fn group_depth(realm: &QuickJsRealmAdapter) -> usize {
+    realm.console_state.borrow().group_depth
+}
+
+/// set the group depth of a realm and return the previous depth
+fn set_group_depth(realm: &QuickJsRealmAdapter, depth: usize) -> usize {
+    std::mem::replace(&mut realm.console_state.borrow_mut().group_depth, depth)
+}
+
+/// set the group depth of a realm by its id, this does nothing when the realm was dropped in the mean time
+fn set_group_depth_by_id(realm_id: &str, depth: usize) -> Option<usize> {
+    QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+        q_js_rt
+            .opt_context(realm_id)
+            .map(|realm| set_group_depth(realm, depth))
+    })
+}
+
+thread_local! {
+    // trace id of the eval which is running or which scheduled the callback which is running, see QuickJsRuntimeFacade::eval_with_trace_id_sync
+    static TRACE_ID: RefCell<Option<String>> = RefCell::new(None);
+}
+
+fn trace_id() -> Option<String> {
+    TRACE_ID.with(|rc| rc.borrow().clone())
+}
+
+/// guard which makes a trace id the active one until it is dropped, the previous trace id is restored on drop
+pub(crate) struct TraceIdGuard {
+    previous: Option<String>,
+}
+
+impl TraceIdGuard {
+    pub(crate) fn set(trace_id: Option<String>) -> Self {
+        let previous = TRACE_ID.with(|rc| rc.replace(trace_id));
+        Self { previous }
+    }
+}
+
+impl Drop for TraceIdGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        TRACE_ID.with(|rc| *rc.borrow_mut() = previous);
+    }
+}
+
+/// the group level of a realm and the trace id which were active when a callback was scheduled
+/// the group level is only captured when QuickJsRuntimeBuilder::console_async_group_context is enabled
+pub(crate) struct GroupContext {
+    realm_id: String,
+    depth: Option<usize>,
+    trace_id: Option<String>,
+}
+
+impl GroupContext {
+    pub(crate) fn capture(realm: &QuickJsRealmAdapter) -> Self {
+        let depth = if with_config(|config| config.async_group_context) {
+            Some(group_depth(realm))
+        } else {
+            None
+        };
+        Self {
+            realm_id: realm.id.clone(),
+            depth,
+            trace_id: trace_id(),
+        }
+    }
+
+    /// make the captured group level and trace id the active ones until the returned guard is dropped
+    pub(crate) fn enter(&self) -> GroupContextGuard<'_> {
+        let previous = self
+            .depth
+            .and_then(|depth| set_group_depth_by_id(self.realm_id.as_str(), depth));
+        GroupContextGuard {
+            realm_id: self.realm_id.as_str(),
+            previous,
+            _trace_id: TraceIdGuard::set(self.trace_id.clone()),
+        }
+    }
+}
+
+pub(crate) struct GroupContextGuard<'a> {
+    realm_id: &'a str,
+    previous: Option<usize>,
+    _trace_id: TraceIdGuard,
+}
+
+impl Drop for GroupContextGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            set_group_depth_by_id(self.realm_id, previous);
+        }
+    }
+}
+
+const TRUNCATED_NOTICE: &str = "[console output truncated]";
+
+/// guard which counts the console output from creation until it is dropped
+/// nested guards (e.g. an eval from a native function called from script) are counted as part of the outermost eval
+pub(crate) struct EvalOutputGuard {
+    outermost: bool,
+}
+
+impl EvalOutputGuard {
+    pub(crate) fn start() -> Self {
+        let outermost = EVAL_OUTPUT_BYTES.with(|rc| rc.get().is_none());
+        if outermost {
+            EVAL_OUTPUT_BYTES.with(|rc| rc.set(Some(0)));
+            EVAL_OUTPUT_TRUNCATED.with(|rc| rc.set(false));
+        }
+        Self { outermost }
+    }
+}
+
+impl Drop for EvalOutputGuard {
+    fn drop(&mut self) {
+        if self.outermost {
+            EVAL_OUTPUT_BYTES.with(|rc| rc.set(None));
+        }
+    }
+}
+
+/// output a line to a logger under the target which is configured for the console level
+fn log_line(logger: &dyn Log, level: ConsoleLevel, line: &str) {
+    with_config(|config| {
+        let target = config
+            .log_targets
+            .get(&level)
+            .map(|target| target.as_str())
+            .unwrap_or(module_path!());
+        logger.log(
+            &Record::builder()
+                .args(format_args!("{line}"))
+                .level(level.log_level())
+                .target(target)
+                .module_path(Some(module_path!()))
+                .file(Some(file!()))
+                .line(Some(line!()))
+                .build(),
+        );
+    });
+}
+
+/// check a line against the output budget of the current eval
+/// returns None if the line should be dropped or the truncation notice if this line exceeds the budget
+fn budgeted_line(line: String) -> Option<String> {
+    let budget = match with_config(|config| config.byte_budget_per_eval) {
+        Some(budget) => budget,
+        None => return Some(line),
+    };
+    let written = match EVAL_OUTPUT_BYTES.with(|rc| rc.get()) {
+        Some(written) => written,
+        None => return Some(line),
+    };
+    if EVAL_OUTPUT_TRUNCATED.with(|rc| rc.get()) {
+        return None;
+    }
+    let total = written + line.len();
+    if total > budget {
+        EVAL_OUTPUT_TRUNCATED.with(|rc| rc.set(true));
+        Some(TRUNCATED_NOTICE.to_string())
+    } else {
+        EVAL_OUTPUT_BYTES.with(|rc| rc.set(Some(total)));
+        Some(line)
+    }
+}
+
+/// group the digits of an integer string, e.g. 1000000 becomes 1,000,000
+fn group_digits(i_val: &str, separator: char) -> String {
+    let (sign, digits) = match i_val.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", i_val),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        // NaN, Infinity etc
+        return i_val.to_string();
+    }
+    let mut grouped = String::from(sign);
+    for (idx, chr) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(chr);
+    }
+    grouped
+}
 
 pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
     q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_ctx(q_ctx))
@@ -66,10 +402,28 @@ pub(crate) fn init_ctx(q_ctx: &QuickJsRealmAdapter) -> Result<(), JsError> {
         .static_native_method("info", Some(console_info))
         .static_native_method("warn", Some(console_warn))
         .static_native_method("error", Some(console_error))
-        //.static_native_method("assert", Some(console_assert)) // todo
+        .static_native_method("assert", Some(console_assert))
+        .static_native_method("dir", Some(console_dir))
+        .static_native_method("dirxml", Some(console_dirxml))
         .static_native_method("debug", Some(console_debug))
-        .install(q_ctx, true)
-        .map(|_| {})
+        .static_native_method("group", Some(console_group))
+        .static_native_method("groupCollapsed", Some(console_group))
+        .static_native_method("groupEnd", Some(console_group_end))
+        .static_native_method("count", Some(console_count))
+        .static_native_method("countReset", Some(console_count_reset))
+        .static_native_method("time", Some(console_time))
+        .static_native_method("timeLog", Some(console_time_log))
+        .static_native_method("timeEnd", Some(console_time_end))
+        .install(q_ctx, true)?;
+
+    #[cfg(feature = "print")]
+    {
+        let print_func = functions::new_native_function_q(q_ctx, "print", Some(print), 1, false)?;
+        let global = quickjs_utils::get_global_q(q_ctx);
+        objects::set_property2_q(q_ctx, &global, "print", &print_func, 0)?;
+    }
+
+    Ok(())
 }
 
 #[allow(clippy::or_fun_call)]
@@ -80,6 +434,7 @@ unsafe fn parse_field_value(
 ) -> String {
     // format ints
     // only support ,2 / .3 to declare the number of digits to display, e.g. $.3i turns 3 to 003
+    // a width pads with spaces, e.g. %5d turns 42 to '   42', %-5d to '42   ' and %05d to '00042'
 
     // format floats
     // only support ,2 / .3 to declare the number of decimals to display, e.g. $.3f turns 3.1 to 3.100
@@ -115,7 +470,11 @@ unsafe fn parse_field_value(
             }
         }
 
-        return i_val;
+        if let Some(separator) = with_config(|config| config.grouping_separator) {
+            i_val = group_digits(i_val.as_str(), separator);
+        }
+
+        return pad_to_width(i_val, field);
     } else if field.ends_with('f') {
         let mut f_val: String = call_to_string(ctx, value).unwrap_or(String::new());
 
@@ -147,47 +506,244 @@ unsafe fn parse_field_value(
                     }
                 }
             }
-            return f_val;
+            return replace_decimal_separator(f_val);
         } else if field.ends_with('o') || field.ends_with('O') {
-            let json_str_res = json::stringify(ctx, value, None);
-            let json = match json_str_res {
-                Ok(json_str) => primitives::to_string(ctx, &json_str).unwrap_or(String::new()),
-                Err(_e) => "".to_string(),
-            };
-            return json;
+            return stringify_log_obj(ctx, value);
+        }
+        return replace_decimal_separator(f_val);
+    }
+    call_to_string(ctx, value).unwrap_or_else(unserializable)
+}
+
+/// pad a formatted int to the width of a field like %5d, flags may precede the width
+/// '-' aligns the value to the left and '0' pads with zeros after the sign instead of with spaces
+fn pad_to_width(val: String, field: &str) -> String {
+    let spec = field.trim_start_matches('%');
+    let spec = &spec[..spec
+        .find(|c: char| c == '.' || c.is_ascii_alphabetic())
+        .unwrap_or(spec.len())];
+    let flags_end = spec
+        .find(|c: char| c != '-' && c != '0')
+        .unwrap_or(spec.len());
+    let (flags, width_str) = spec.split_at(flags_end);
+    let width = match usize::from_str(width_str) {
+        Ok(width) => width,
+        Err(_) => return val,
+    };
+    let len = val.chars().count();
+    if len >= width {
+        return val;
+    }
+    let padding = width - len;
+    if flags.contains('-') {
+        format!("{val}{}", " ".repeat(padding))
+    } else if flags.contains('0') {
+        let digits_start = if val.starts_with('-') { 1 } else { 0 };
+        let (sign, digits) = val.split_at(digits_start);
+        format!("{sign}{}{digits}", "0".repeat(padding))
+    } else {
+        format!("{}{val}", " ".repeat(padding))
+    }
+}
+
+fn replace_decimal_separator(f_val: String) -> String {
+    match with_config(|config| config.decimal_separator) {
+        Some(separator) => f_val.replacen('.', separator.to_string().as_str(), 1),
+        None => f_val,
+    }
+}
+
+/// run a formatting step which may call script (toString or toJSON) with the configured timeout so it can not hang logging
+fn guarded_format<F: FnOnce() -> String>(formatter: F) -> String {
+    match with_config(|config| config.format_timeout) {
+        Some(timeout) => {
+            let guard = DeadlineGuard::start(timeout);
+            let formatted = formatter();
+            if guard.was_interrupted() {
+                format!("[Aborted: formatting took longer than {timeout:?}]")
+            } else {
+                formatted
+            }
         }
+        None => formatter(),
     }
-    call_to_string(ctx, value).unwrap_or(String::new())
 }
 
-unsafe fn stringify_log_obj(ctx: *mut q::JSContext, arg: &QuickJsValueAdapter) -> String {
-    match stringify(ctx, arg, None) {
-        Ok(r) => match primitives::to_string(ctx, &r) {
-            Ok(s) => s,
-            Err(e) => format!("Error: {e}"),
+/// render a value whose toString or toJSON threw an error
+fn unserializable(err: JsError) -> String {
+    format!("[Unserializable: {}]", err.get_message())
+}
+
+/// check if a value is an object literal (or an object without prototype) and not an instance of some class
+unsafe fn is_plain_object(ctx: *mut q::JSContext, arg: &QuickJsValueAdapter) -> bool {
+    if arg.get_js_type() != JsValueType::Object {
+        return false;
+    }
+    match objects::get_prototype_of(ctx, arg) {
+        Ok(proto) if proto.is_null() => true,
+        Ok(proto) => match objects::get_prototype_of(ctx, &proto) {
+            Ok(proto_proto) => proto_proto.is_null(),
+            Err(_) => false,
         },
-        Err(e) => format!("Error: {e}"),
+        Err(_) => false,
     }
 }
 
-#[allow(clippy::or_fun_call)]
-unsafe fn parse_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+/// quote a logfmt key or value if it contains characters which would make the line ambiguous
+fn logfmt_quote(val: String) -> String {
+    if val.is_empty() || val.contains(|c: char| c.is_whitespace() || c == '=' || c == '"') {
+        serde_json::Value::String(val).to_string()
+    } else {
+        val
+    }
+}
+
+/// render the properties of an object as logfmt style key=value pairs, e.g. ` ms=5 ok=true`
+unsafe fn format_logfmt_fields(ctx: *mut q::JSContext, fields: &QuickJsValueAdapter) -> String {
+    let entries = match objects::get_entries(ctx, fields) {
+        Ok(entries) => entries,
+        Err(e) => return format!(" {}", unserializable(e)),
+    };
+    let mut output = String::new();
+    for (key, value) in entries {
+        let value_str = match value.get_js_type() {
+            JsValueType::Object | JsValueType::Function | JsValueType::Array => {
+                stringify_log_obj(ctx, &value)
+            }
+            _ => call_to_string(ctx, &value).unwrap_or_else(unserializable),
+        };
+        output.push(' ');
+        output.push_str(logfmt_quote(key).as_str());
+        output.push('=');
+        output.push_str(logfmt_quote(value_str).as_str());
+    }
+    output
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// translate the css of a %c directive to ansi escape codes, unsupported properties are ignored
+/// supported are color and background(-color) with named colors, font-weight: bold, font-style: italic and text-decoration: underline
+fn css_to_ansi(css: &str) -> String {
+    fn color_code(color: &str) -> Option<u8> {
+        match color {
+            "black" => Some(0),
+            "red" => Some(1),
+            "green" => Some(2),
+            "yellow" | "orange" => Some(3),
+            "blue" => Some(4),
+            "magenta" | "purple" => Some(5),
+            "cyan" => Some(6),
+            "white" => Some(7),
+            "gray" | "grey" => Some(60),
+            _ => None,
+        }
+    }
+
+    let mut codes = vec![];
+    for declaration in css.split(';') {
+        let (property, value) = match declaration.split_once(':') {
+            Some((property, value)) => (
+                property.trim().to_ascii_lowercase(),
+                value.trim().to_ascii_lowercase(),
+            ),
+            None => continue,
+        };
+        let code = match (property.as_str(), value.as_str()) {
+            ("color", color) => color_code(color).map(|c| 30 + c),
+            ("background" | "background-color", color) => color_code(color).map(|c| 40 + c),
+            ("font-weight", "bold" | "bolder" | "700" | "800" | "900") => Some(1),
+            ("font-style", "italic") => Some(3),
+            ("text-decoration", "underline") => Some(4),
+            _ => None,
+        };
+        if let Some(code) = code {
+            codes.push(code.to_string());
+        }
+    }
+    if codes.is_empty() {
+        String::new()
+    } else {
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// serialize an object argument, this stops with a "… (output limited)" placeholder when the configured max number of nodes is exceeded
+unsafe fn stringify_log_obj(ctx: *mut q::JSContext, value: &QuickJsValueAdapter) -> String {
+    match with_config(|config| config.max_nodes) {
+        Some(max_nodes) => safe_stringify_limited(ctx, value, max_nodes),
+        None => safe_stringify(ctx, value),
+    }
+}
+
+/// the realm id, trace id and group indentation every console line starts with
+unsafe fn line_prefix(ctx: *mut q::JSContext) -> String {
     let mut output = String::new();
 
     output.push_str("JS_REALM:[");
-    QuickJsRealmAdapter::with_context(ctx, |realm| output.push_str(realm.id.as_str()));
+    let depth = QuickJsRealmAdapter::with_context(ctx, |realm| {
+        output.push_str(realm.id.as_str());
+        group_depth(realm)
+    });
     output.push_str("]: ");
+    if let Some(trace_id) = trace_id() {
+        output.push('[');
+        output.push_str(trace_id.as_str());
+        output.push_str("] ");
+    }
+    for _ in 0..depth {
+        output.push_str(GROUP_INDENT);
+    }
+    output
+}
+
+/// format values like the console methods do, the first value may contain substitutions like %s, %d or %o
+/// and the values which were not substituted are appended separated by spaces
+/// unlike the output of the console methods the result is not prefixed with the realm id and group indentation
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::features::console::format_args;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let realm = q_js_rt.get_main_realm();
+///     let args = [realm.create_string("%s has %d items").unwrap(), realm.create_string("cart").unwrap(), realm.create_i32(3).unwrap(), realm.create_boolean(true).unwrap()];
+///     assert_eq!(format_args(realm, &args), "cart has 3 items true");
+/// });
+/// ```
+pub fn format_args(realm: &QuickJsRealmAdapter, args: &[QuickJsValueAdapter]) -> String {
+    unsafe { parse_message(realm.context, args.to_vec()) }
+}
+
+unsafe fn parse_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+    let mut output = line_prefix(ctx);
+    output.push_str(parse_message(ctx, args).as_str());
+    output
+}
+
+#[allow(clippy::or_fun_call)]
+unsafe fn parse_message(ctx: *mut q::JSContext, mut args: Vec<QuickJsValueAdapter>) -> String {
+    let mut output = String::new();
 
     if args.is_empty() {
         return output;
     }
 
-    let message = match &args[0].get_js_type() {
+    let logfmt_fields = if with_config(|config| config.logfmt)
+        && args.len() > 1
+        && is_plain_object(ctx, &args[args.len() - 1])
+    {
+        args.pop()
+    } else {
+        None
+    };
+
+    let message = guarded_format(|| match &args[0].get_js_type() {
         JsValueType::Object => stringify_log_obj(ctx, &args[0]),
         JsValueType::Function => stringify_log_obj(ctx, &args[0]),
         JsValueType::Array => stringify_log_obj(ctx, &args[0]),
-        _ => functions::call_to_string(ctx, &args[0]).unwrap_or(String::new()),
-    };
+        _ => functions::call_to_string(ctx, &args[0]).unwrap_or_else(unserializable),
+    });
 
     let mut field_code = String::new();
     let mut in_field = false;
@@ -196,16 +752,49 @@ unsafe fn parse_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) ->
 
     let mut filled = 1;
 
+    let ansi_styles = with_config(|config| config.ansi_styles);
+    let mut styled = false;
+
     if args[0].is_string() {
         for chr in message.chars() {
-            if in_field {
+            if in_field && field_code.is_empty() && chr.eq(&'%') {
+                // %% is a literal %
+                output.push('%');
+                in_field = false;
+            } else if in_field {
                 field_code.push(chr);
-                if chr.eq(&'s') || chr.eq(&'d') || chr.eq(&'f') || chr.eq(&'o') || chr.eq(&'i') {
+                if chr.eq(&'c') {
+                    // css styling, the style arg is consumed and only output when translated to ansi escape codes
+                    if x < args.len() {
+                        if ansi_styles {
+                            let css = call_to_string(ctx, &args[x]).unwrap_or_default();
+                            if styled {
+                                output.push_str(ANSI_RESET);
+                            }
+                            let codes = css_to_ansi(css.as_str());
+                            styled = !codes.is_empty();
+                            output.push_str(codes.as_str());
+                        }
+                        x += 1;
+                        filled += 1;
+                    }
+
+                    in_field = false;
+                    field_code = String::new();
+                } else if chr.eq(&'s')
+                    || chr.eq(&'d')
+                    || chr.eq(&'f')
+                    || chr.eq(&'o')
+                    || chr.eq(&'i')
+                {
                     // end field
 
                     if x < args.len() {
                         output.push_str(
-                            parse_field_value(ctx, field_code.as_str(), &args[x]).as_str(),
+                            guarded_format(|| {
+                                parse_field_value(ctx, field_code.as_str(), &args[x])
+                            })
+                            .as_str(),
                         );
                         x += 1;
                         filled += 1;
@@ -220,6 +809,9 @@ unsafe fn parse_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) ->
                 output.push(chr);
             }
         }
+        if styled {
+            output.push_str(ANSI_RESET);
+        }
     } else {
         output.push_str(message.as_str());
     }
@@ -227,15 +819,19 @@ unsafe fn parse_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) ->
     for arg in args.iter().skip(filled) {
         // add args which we're not filled in str
         output.push(' ');
-        let tail_arg = match arg.get_js_type() {
+        let tail_arg = guarded_format(|| match arg.get_js_type() {
             JsValueType::Object => stringify_log_obj(ctx, arg),
             JsValueType::Function => stringify_log_obj(ctx, arg),
             JsValueType::Array => stringify_log_obj(ctx, arg),
-            _ => call_to_string(ctx, arg).unwrap_or(String::new()),
-        };
+            _ => call_to_string(ctx, arg).unwrap_or_else(unserializable),
+        });
         output.push_str(tail_arg.as_str());
     }
 
+    if let Some(fields) = logfmt_fields {
+        output.push_str(guarded_format(|| format_logfmt_fields(ctx, &fields)).as_str());
+    }
+
     output
 }
 
@@ -247,7 +843,9 @@ unsafe extern "C" fn console_log(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Info {
         let args = parse_args(ctx, argc, argv);
-        log::info!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Log, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
@@ -260,11 +858,41 @@ unsafe extern "C" fn console_trace(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Trace {
         let args = parse_args(ctx, argc, argv);
-        log::trace!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(trace_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Trace, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
 
+/// format the line for console.trace(), the message followed by the stack of the call site
+unsafe fn trace_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+    let mut output = parse_line(ctx, args);
+    let stack = call_site_stack(ctx);
+    if !stack.is_empty() {
+        output.push('\n');
+        output.push_str(stack.as_str());
+    }
+    output
+}
+
+/// capture the current JS stack by creating an Error and reading its stack
+/// the native frames on top of the stack (e.g. console.trace itself) are skipped
+unsafe fn call_site_stack(ctx: *mut q::JSContext) -> String {
+    let stack_res = quickjs_utils::get_constructor(ctx, "Error")
+        .and_then(|constructor| functions::call_constructor(ctx, &constructor, &[]))
+        .and_then(|error| objects::get_property(ctx, &error, "stack"));
+    match stack_res {
+        Ok(stack) if stack.is_string() => call_to_string(ctx, &stack)
+            .unwrap_or_default()
+            .lines()
+            .skip_while(|frame| frame.ends_with("(native)"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
 unsafe extern "C" fn console_debug(
     ctx: *mut q::JSContext,
     _this_val: q::JSValue,
@@ -273,7 +901,9 @@ unsafe extern "C" fn console_debug(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Debug {
         let args = parse_args(ctx, argc, argv);
-        log::debug!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Debug, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
@@ -286,7 +916,9 @@ unsafe extern "C" fn console_info(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Info {
         let args = parse_args(ctx, argc, argv);
-        log::info!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Info, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
@@ -299,7 +931,9 @@ unsafe extern "C" fn console_warn(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Warn {
         let args = parse_args(ctx, argc, argv);
-        log::warn!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Warn, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
@@ -312,17 +946,1145 @@ unsafe extern "C" fn console_error(
 ) -> q::JSValue {
     if log::max_level() >= LevelFilter::Error {
         let args = parse_args(ctx, argc, argv);
-        log::error!("{}", parse_line(ctx, args));
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Error, line.as_str());
+        }
+    }
+    quickjs_utils::new_null()
+}
+
+unsafe extern "C" fn console_assert(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    if log::max_level() >= LevelFilter::Error {
+        let mut args = parse_args(ctx, argc, argv);
+        let assertion_ok = !args.is_empty() && args.remove(0).is_truthy();
+        if !assertion_ok {
+            if let Some(line) = budgeted_line(assertion_line(ctx, args)) {
+                log_line(log::logger(), ConsoleLevel::Error, line.as_str());
+            }
+        }
+    }
+    quickjs_utils::new_null()
+}
+
+/// format the line for a failed console.assert, the message args are formatted like the args of the other console methods
+unsafe fn assertion_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+    let mut output = line_prefix(ctx);
+    output.push_str("Assertion failed:");
+    if !args.is_empty() {
+        output.push(' ');
+        output.push_str(parse_message(ctx, args).as_str());
+    }
+    output
+}
+
+/// the default depth of console.dir()
+const DEFAULT_DIR_DEPTH: usize = 2;
+
+unsafe extern "C" fn console_dir(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    if log::max_level() >= LevelFilter::Info {
+        let args = parse_args(ctx, argc, argv);
+        if let Some(line) = budgeted_line(dir_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Log, line.as_str());
+        }
     }
     quickjs_utils::new_null()
 }
 
+/// get the depth option passed to console.dir(), None means no limit
+unsafe fn dir_depth(
+    ctx: *mut q::JSContext,
+    options: Option<&QuickJsValueAdapter>,
+) -> Option<usize> {
+    let depth = match options {
+        Some(options) if options.is_object() => objects::get_property(ctx, options, "depth")
+            .ok()
+            .filter(|depth| !depth.is_exception()),
+        _ => None,
+    };
+    match depth {
+        Some(depth) if depth.is_null() => None,
+        Some(depth) if depth.is_i32() => Some(depth.to_i32().max(0) as usize),
+        Some(depth) if depth.is_f64() => {
+            let depth = depth.to_f64();
+            if depth.is_finite() {
+                Some(depth.max(0.0) as usize)
+            } else {
+                None
+            }
+        }
+        _ => Some(DEFAULT_DIR_DEPTH),
+    }
+}
+
+/// format the line for console.dir(), the object is serialized up to the depth passed in the options
+unsafe fn dir_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+    let mut output = line_prefix(ctx);
+    if let Some(value) = args.first() {
+        let max_depth = dir_depth(ctx, args.get(1));
+        let max_nodes = with_config(|config| config.max_nodes);
+        output.push_str(
+            guarded_format(|| safe_stringify_nodes(ctx, value, max_nodes, max_depth)).as_str(),
+        );
+    } else {
+        output.push_str("undefined");
+    }
+    output
+}
+
+unsafe extern "C" fn console_dirxml(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    if log::max_level() >= LevelFilter::Info {
+        let args = parse_args(ctx, argc, argv);
+        if let Some(line) = budgeted_line(dirxml_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Info, line.as_str());
+        }
+    }
+    quickjs_utils::new_null()
+}
+
+/// format the line for console.dirxml(), an object is output as a tree, other values are formatted like console.log() does
+unsafe fn dirxml_line(ctx: *mut q::JSContext, args: Vec<QuickJsValueAdapter>) -> String {
+    match args.first() {
+        Some(value) if value.is_object() && !value.is_function() => {
+            let mut output = line_prefix(ctx);
+            let max_nodes = with_config(|config| config.max_nodes);
+            output.push_str(guarded_format(|| safe_stringify_tree(ctx, value, max_nodes)).as_str());
+            output
+        }
+        _ => parse_line(ctx, args),
+    }
+}
+
+/// get the label passed as first argument, "default" if no label was passed
+unsafe fn label_arg(ctx: *mut q::JSContext, args: &[QuickJsValueAdapter]) -> String {
+    match args.first() {
+        Some(label) if !label.is_undefined() => {
+            call_to_string(ctx, label).unwrap_or_else(unserializable)
+        }
+        _ => "default".to_string(),
+    }
+}
+
+unsafe extern "C" fn console_count(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    let args = parse_args(ctx, argc, argv);
+    let label = label_arg(ctx, &args);
+    let count = QuickJsRealmAdapter::with_context(ctx, |realm| {
+        let state = &mut *realm.console_state.borrow_mut();
+        let count = state.counters.entry(label.clone()).or_insert(0);
+        *count += 1;
+        *count
+    });
+    if log::max_level() >= LevelFilter::Info {
+        let line = format!("{}{label}: {count}", line_prefix(ctx));
+        if let Some(line) = budgeted_line(line) {
+            log_line(log::logger(), ConsoleLevel::Info, line.as_str());
+        }
+    }
+    quickjs_utils::new_null()
+}
+
+unsafe extern "C" fn console_count_reset(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    let args = parse_args(ctx, argc, argv);
+    let label = label_arg(ctx, &args);
+    let existed = QuickJsRealmAdapter::with_context(ctx, |realm| {
+        let state = &mut *realm.console_state.borrow_mut();
+        match state.counters.get_mut(&label) {
+            Some(count) => {
+                *count = 0;
+                true
+            }
+            None => false,
+        }
+    });
+    if !existed {
+        log_warning(ctx, format!("Count for '{label}' does not exist").as_str());
+    }
+    quickjs_utils::new_null()
+}
+
+/// log a warning line, e.g. for an unknown counter or timer
+unsafe fn log_warning(ctx: *mut q::JSContext, message: &str) {
+    if log::max_level() >= LevelFilter::Warn {
+        let line = format!("{}{message}", line_prefix(ctx));
+        if let Some(line) = budgeted_line(line) {
+            log_line(log::logger(), ConsoleLevel::Warn, line.as_str());
+        }
+    }
+}
+
+/// format an elapsed time like browsers do, e.g. "12.345ms"
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:.3}ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+unsafe extern "C" fn console_time(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    let args = parse_args(ctx, argc, argv);
+    let label = label_arg(ctx, &args);
+    let started = QuickJsRealmAdapter::with_context(ctx, |realm| {
+        let state = &mut *realm.console_state.borrow_mut();
+        if state.timers.contains_key(&label) {
+            false
+        } else {
+            state.timers.insert(label.clone(), Instant::now());
+            true
+        }
+    });
+    if !started {
+        log_warning(ctx, format!("Timer '{label}' already exists").as_str());
+    }
+    quickjs_utils::new_null()
+}
+
+/// log the elapsed time of a timer, the timer is removed if remove is true
+unsafe fn log_timer(
+    ctx: *mut q::JSContext,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+    remove: bool,
+) {
+    let mut args = parse_args(ctx, argc, argv);
+    let label = label_arg(ctx, &args);
+    let elapsed = QuickJsRealmAdapter::with_context(ctx, |realm| {
+        let state = &mut *realm.console_state.borrow_mut();
+        let started = if remove {
+            state.timers.remove(&label)
+        } else {
+            state.timers.get(&label).copied()
+        };
+        started.map(|started| started.elapsed())
+    });
+    match elapsed {
+        Some(elapsed) => {
+            if log::max_level() >= LevelFilter::Info {
+                let mut line = format!("{}{label}: {}", line_prefix(ctx), format_elapsed(elapsed));
+                if args.len() > 1 {
+                    line.push(' ');
+                    line.push_str(parse_message(ctx, args.split_off(1)).as_str());
+                }
+                if let Some(line) = budgeted_line(line) {
+                    log_line(log::logger(), ConsoleLevel::Info, line.as_str());
+                }
+            }
+        }
+        None => log_warning(ctx, format!("Timer '{label}' does not exist").as_str()),
+    }
+}
+
+unsafe extern "C" fn console_time_log(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    log_timer(ctx, argc, argv, false);
+    quickjs_utils::new_null()
+}
+
+unsafe extern "C" fn console_time_end(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    log_timer(ctx, argc, argv, true);
+    quickjs_utils::new_null()
+}
+
+/// the line output by print(), the arguments converted to string and joined with spaces
+#[cfg(feature = "print")]
+unsafe fn print_line(ctx: *mut q::JSContext, args: &[QuickJsValueAdapter]) -> String {
+    args.iter()
+        .map(|arg| call_to_string(ctx, arg).unwrap_or_else(unserializable))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(feature = "print")]
+unsafe extern "C" fn print(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    if log::max_level() >= LevelFilter::Info {
+        let args = parse_args(ctx, argc, argv);
+        if let Some(line) = budgeted_line(print_line(ctx, &args)) {
+            log_line(log::logger(), ConsoleLevel::Log, line.as_str());
+        }
+    }
+    quickjs_utils::new_null()
+}
+
+unsafe extern "C" fn console_group(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    if argc > 0 && log::max_level() >= LevelFilter::Info {
+        let args = parse_args(ctx, argc, argv);
+        if let Some(line) = budgeted_line(parse_line(ctx, args)) {
+            log_line(log::logger(), ConsoleLevel::Log, line.as_str());
+        }
+    }
+    QuickJsRealmAdapter::with_context(ctx, |realm| {
+        set_group_depth(realm, group_depth(realm) + 1);
+    });
+    quickjs_utils::new_null()
+}
+
+unsafe extern "C" fn console_group_end(
+    ctx: *mut q::JSContext,
+    _this_val: q::JSValue,
+    _argc: ::std::os::raw::c_int,
+    _argv: *mut q::JSValue,
+) -> q::JSValue {
+    QuickJsRealmAdapter::with_context(ctx, |realm| {
+        set_group_depth(realm, group_depth(realm).saturating_sub(1));
+    });
+    quickjs_utils::new_null()
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::builder::QuickJsRuntimeBuilder;
+    use crate::features::console::{
+        assertion_line, budgeted_line, css_to_ansi, dir_line, dirxml_line, format_args,
+        format_elapsed, log_line, pad_to_width, parse_line, trace_line, ConsoleLevel,
+        EvalOutputGuard,
+    };
     use crate::jsutils::Script;
+    use log::{Log, Metadata, Record};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     //use log::LevelFilter;
 
+    #[test]
+    pub fn test_format_timeout() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_format_timeout(Duration::from_millis(100))
+            .build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let endless = realm
+                .eval(Script::new(
+                    "test_format_timeout.js",
+                    "({toJSON: function() {while (true) {}}});",
+                ))
+                .expect("script failed");
+            let args = vec![
+                realm.create_string("before").unwrap(),
+                endless,
+                realm.create_string("after").unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(
+                line,
+                "JS_REALM:[__main__]: before [Aborted: formatting took longer than 100ms] after"
+            );
+        });
+    }
+
+    struct RecordingLogger {
+        records: Mutex<Vec<(String, String)>>,
+    }
+
+    impl Log for RecordingLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &Record) {
+            self.records
+                .lock()
+                .unwrap()
+                .push((record.target().to_string(), record.args().to_string()));
+        }
+        fn flush(&self) {}
+    }
+
+    #[test]
+    pub fn test_log_targets() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_log_target(ConsoleLevel::Error, "js.error")
+            .console_log_target(ConsoleLevel::Info, "js.info")
+            .build();
+        let records = rt.exe_rt_task_in_event_loop(|_q_js_rt| {
+            let logger = RecordingLogger {
+                records: Mutex::new(vec![]),
+            };
+            log_line(&logger, ConsoleLevel::Error, "an error");
+            log_line(&logger, ConsoleLevel::Info, "some info");
+            log_line(&logger, ConsoleLevel::Warn, "a warning");
+            logger.records.into_inner().unwrap()
+        });
+        assert_eq!(
+            records,
+            vec![
+                ("js.error".to_string(), "an error".to_string()),
+                ("js.info".to_string(), "some info".to_string()),
+                (
+                    "quickjs_runtime::features::console".to_string(),
+                    "a warning".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_byte_budget_per_eval() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_byte_budget_per_eval(100)
+            .build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            // outside of an eval output is not limited
+            assert!(budgeted_line("x".repeat(200)).is_some());
+
+            let guard = EvalOutputGuard::start();
+            assert_eq!(budgeted_line("a".repeat(60)), Some("a".repeat(60)));
+            assert_eq!(
+                budgeted_line("b".repeat(60)),
+                Some("[console output truncated]".to_string())
+            );
+            assert_eq!(budgeted_line("c".to_string()), None);
+            drop(guard);
+
+            // the budget is reset for the next eval
+            let _guard = EvalOutputGuard::start();
+            assert_eq!(budgeted_line("d".repeat(60)), Some("d".repeat(60)));
+
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .eval(Script::new(
+                    "test_byte_budget_per_eval.js",
+                    "for (let x = 0; x < 100; x++) {console.log('log bomb %i', x);}",
+                ))
+                .expect("script failed");
+        });
+    }
+
+    #[test]
+    pub fn test_group_per_realm() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.create_context("group_other")
+            .expect("could not create realm");
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let other = q_js_rt.get_context("group_other");
+            realm
+                .eval(Script::new(
+                    "test_group_per_realm.js",
+                    "console.group('a'); console.groupCollapsed('b'); console.group(); console.groupEnd();",
+                ))
+                .expect("script failed");
+            let args = vec![realm.create_string("nested").unwrap()];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]:     nested");
+
+            let args = vec![other.create_string("flat").unwrap()];
+            let line = unsafe { parse_line(other.context, args) };
+            assert_eq!(line, "JS_REALM:[group_other]: flat");
+
+            realm
+                .eval(Script::new(
+                    "test_group_per_realm2.js",
+                    "console.groupEnd(); console.groupEnd(); console.groupEnd();",
+                ))
+                .expect("script failed");
+            let args = vec![realm.create_string("done").unwrap()];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: done");
+
+            other
+                .eval(Script::new(
+                    "test_group_per_realm3.js",
+                    "console.group('left open');",
+                ))
+                .expect("script failed");
+        });
+
+        // a new realm with the same id does not inherit the groups of the dropped realm
+        rt.drop_context("group_other");
+        rt.create_context("group_other")
+            .expect("could not create realm");
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let other = q_js_rt.get_context("group_other");
+            let args = vec![other.create_string("flat").unwrap()];
+            let line = unsafe { parse_line(other.context, args) };
+            assert_eq!(line, "JS_REALM:[group_other]: flat");
+        });
+    }
+
+    #[test]
+    pub fn test_count() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.create_context("count_other")
+            .expect("could not create realm");
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .eval(Script::new(
+                    "test_count.js",
+                    r#"
+                console.count('foo');
+                console.count('foo');
+                console.count();
+                console.count(undefined);
+                console.countReset('foo');
+                console.count('foo');
+                console.countReset('bar');
+            "#,
+                ))
+                .expect("script failed");
+            let state = realm.console_state.borrow();
+            assert_eq!(state.counters.get("foo"), Some(&1));
+            assert_eq!(state.counters.get("default"), Some(&2));
+            assert!(!state.counters.contains_key("bar"));
+
+            let other = q_js_rt.get_context("count_other");
+            other
+                .eval(Script::new("test_count2.js", "console.count('foo');"))
+                .expect("script failed");
+            assert_eq!(other.console_state.borrow().counters.get("foo"), Some(&1));
+        });
+    }
+
+    #[test]
+    pub fn test_timers() {
+        assert_eq!(format_elapsed(Duration::from_micros(12345)), "12.345ms");
+        assert_eq!(format_elapsed(Duration::from_secs(2)), "2000.000ms");
+
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .eval(Script::new(
+                    "test_timers.js",
+                    "console.time('load'); console.time(); console.timeLog('load', 'step', 1); console.time('load');",
+                ))
+                .expect("script failed");
+            {
+                let state = realm.console_state.borrow();
+                assert!(state.timers.contains_key("load"));
+                assert!(state.timers.contains_key("default"));
+            }
+            realm
+                .eval(Script::new(
+                    "test_timers2.js",
+                    "console.timeEnd('load'); console.timeEnd('load'); console.timeLog('unknown');",
+                ))
+                .expect("script failed");
+            let state = realm.console_state.borrow();
+            assert!(!state.timers.contains_key("load"));
+            assert!(state.timers.contains_key("default"));
+        });
+    }
+
+    #[test]
+    pub fn test_console_counters_and_timers() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .eval(Script::new(
+                    "test_console_counters_and_timers.js",
+                    "console.count('requests'); console.count('requests'); console.count('requests'); console.count(); console.time('load'); console.time('boot');",
+                ))
+                .expect("script failed");
+            let counters = realm.console_counters();
+            assert_eq!(counters.len(), 2);
+            assert_eq!(counters.get("requests"), Some(&3));
+            assert_eq!(counters.get("default"), Some(&1));
+
+            std::thread::sleep(Duration::from_millis(10));
+            let timers = realm.console_timers();
+            let labels: Vec<&str> = timers.iter().map(|(label, _)| label.as_str()).collect();
+            assert_eq!(labels, vec!["boot", "load"]);
+            assert!(timers[1].1 >= Duration::from_millis(10));
+
+            realm
+                .eval(Script::new(
+                    "test_console_counters_and_timers2.js",
+                    "console.timeEnd('load');",
+                ))
+                .expect("script failed");
+            assert_eq!(realm.console_timers().len(), 1);
+        });
+    }
+
+    #[test]
+    pub fn test_async_group_context() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_async_group_context(true)
+            .build();
+        let lines = Arc::new(Mutex::new(vec![]));
+        let lines2 = lines.clone();
+        rt.exe_rt_task_in_event_loop(move |q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let capture = realm
+                .create_function(
+                    "captureLine",
+                    move |realm, _this, args| {
+                        let line = unsafe { parse_line(realm.context, args.to_vec()) };
+                        lines2.lock().unwrap().push(line);
+                        realm.create_null()
+                    },
+                    1,
+                )
+                .expect("could not create function");
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "captureLine", &capture)
+                .expect("could not set captureLine");
+            realm
+                .eval(Script::new(
+                    "test_async_group_context.js",
+                    r#"
+                console.group('outer');
+                setTimeout(() => {captureLine('scheduled');}, 10);
+                console.groupEnd();
+                console.group('other');
+                console.group('inner');
+                captureLine('now');
+            "#,
+                ))
+                .expect("script failed");
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        let lines = lines.lock().unwrap();
+        assert_eq!(
+            *lines,
+            vec![
+                "JS_REALM:[__main__]:     now".to_string(),
+                "JS_REALM:[__main__]:   scheduled".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_trace_id() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let lines = Arc::new(Mutex::new(vec![]));
+        let lines2 = lines.clone();
+        rt.exe_rt_task_in_event_loop(move |q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let capture = realm
+                .create_function(
+                    "captureLine",
+                    move |realm, _this, args| {
+                        let line = unsafe { parse_line(realm.context, args.to_vec()) };
+                        lines2.lock().unwrap().push(line);
+                        realm.create_null()
+                    },
+                    1,
+                )
+                .expect("could not create function");
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "captureLine", &capture)
+                .expect("could not set captureLine");
+        });
+        rt.eval_with_trace_id_sync(
+            None,
+            Script::new(
+                "test_trace_id.js",
+                r#"
+                setTimeout(() => {captureLine('later');}, 50);
+                Promise.resolve().then(() => {captureLine('job');});
+                captureLine('now');
+            "#,
+            ),
+            "req-123",
+        )
+        .expect("script failed");
+        rt.eval_sync(
+            None,
+            Script::new("test_trace_id2.js", "captureLine('untraced');"),
+        )
+        .expect("script failed");
+        std::thread::sleep(Duration::from_millis(200));
+        let lines = lines.lock().unwrap();
+        assert_eq!(
+            *lines,
+            vec![
+                "JS_REALM:[__main__]: [req-123] now".to_string(),
+                "JS_REALM:[__main__]: [req-123] job".to_string(),
+                "JS_REALM:[__main__]: untraced".to_string(),
+                "JS_REALM:[__main__]: [req-123] later".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_assert() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let falsy = realm
+                .eval(Script::new(
+                    "test_assert.js",
+                    "[0, '', null, undefined, NaN, false, 0n];",
+                ))
+                .expect("script failed");
+            for index in 0..7 {
+                let value = realm.get_array_element(&falsy, index).unwrap();
+                assert!(!value.is_truthy(), "value at {index} should be falsy");
+            }
+            let truthy = realm
+                .eval(Script::new(
+                    "test_assert2.js",
+                    "[1, 'a', {}, [], -1.5, true];",
+                ))
+                .expect("script failed");
+            for index in 0..6 {
+                let value = realm.get_array_element(&truthy, index).unwrap();
+                assert!(value.is_truthy(), "value at {index} should be truthy");
+            }
+
+            let args = vec![
+                realm.create_string("expected %i items").unwrap(),
+                realm.create_i32(3).unwrap(),
+            ];
+            let line = unsafe { assertion_line(realm.context, args) };
+            assert_eq!(
+                line,
+                "JS_REALM:[__main__]: Assertion failed: expected 3 items"
+            );
+            let line = unsafe { assertion_line(realm.context, vec![]) };
+            assert_eq!(line, "JS_REALM:[__main__]: Assertion failed:");
+
+            realm
+                .eval(Script::new(
+                    "test_assert3.js",
+                    "console.assert(true, 'not logged'); console.assert(false);",
+                ))
+                .expect("script failed");
+        });
+    }
+
+    #[test]
+    pub fn test_trace() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let capture = realm
+                .create_function(
+                    "captureTrace",
+                    |realm, _this, args| {
+                        let line = unsafe { trace_line(realm.context, args.to_vec()) };
+                        realm.create_string(line.as_str())
+                    },
+                    1,
+                )
+                .expect("could not create function");
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "captureTrace", &capture)
+                .expect("could not set captureTrace");
+            realm
+                .eval(Script::new(
+                    "test_trace.js",
+                    r#"
+                function inner() {
+                    return captureTrace('tracing %s', 'here');
+                }
+                function outer() {
+                    return inner();
+                }
+                outer();
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        let lines: Vec<&str> = res.lines().collect();
+        assert_eq!(lines[0], "JS_REALM:[__main__]: tracing here");
+        assert!(lines[1].contains("at inner (test_trace.js"), "{res}");
+        assert!(lines[2].contains("at outer (test_trace.js"), "{res}");
+        assert!(!res.contains("captureTrace"));
+    }
+
+    #[test]
+    pub fn test_dir() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let obj = realm
+                .eval(Script::new(
+                    "test_dir.js",
+                    "const o = {a: {b: {c: {d: 1}}}, n: 1}; o.self = o; o;",
+                ))
+                .expect("script failed");
+            let line = unsafe { dir_line(realm.context, vec![obj.clone()]) };
+            assert_eq!(
+                line,
+                r#"JS_REALM:[__main__]: {"a":{"b":{"c":"[Object]"}},"n":1,"self":"[Circular]"}"#
+            );
+            let options = realm
+                .eval(Script::new("test_dir2.js", "({depth: 0});"))
+                .expect("script failed");
+            let line = unsafe { dir_line(realm.context, vec![obj.clone(), options]) };
+            assert_eq!(
+                line,
+                r#"JS_REALM:[__main__]: {"a":"[Object]","n":1,"self":"[Circular]"}"#
+            );
+            let options = realm
+                .eval(Script::new("test_dir3.js", "({depth: null});"))
+                .expect("script failed");
+            let line = unsafe { dir_line(realm.context, vec![obj, options]) };
+            assert_eq!(
+                line,
+                r#"JS_REALM:[__main__]: {"a":{"b":{"c":{"d":1}}},"n":1,"self":"[Circular]"}"#
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_dirxml() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let obj = realm
+                .eval(Script::new(
+                    "test_dirxml.js",
+                    "const x = {name: 'root', children: [{name: 'leaf', tags: []}]}; x.self = x; x;",
+                ))
+                .expect("script failed");
+            let line = unsafe { dirxml_line(realm.context, vec![obj]) };
+            assert_eq!(
+                line,
+                r#"JS_REALM:[__main__]: {
+  "name": "root",
+  "children": [
+    {
+      "name": "leaf",
+      "tags": []
+    }
+  ],
+  "self": "[Circular]"
+}"#
+            );
+            let args = vec![
+                realm.create_string("%d items").unwrap(),
+                realm.create_i32(3).unwrap(),
+            ];
+            let line = unsafe { dirxml_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: 3 items");
+            realm
+                .eval(Script::new(
+                    "test_dirxml2.js",
+                    "console.dirxml(1); console.dirxml(); console.dirxml(null);",
+                ))
+                .expect("dirxml threw");
+        });
+    }
+
+    #[test]
+    pub fn test_max_nodes() {
+        let rt = QuickJsRuntimeBuilder::new().console_max_nodes(100).build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let huge = realm
+                .eval(Script::new(
+                    "test_max_nodes.js",
+                    r#"
+                function build(depth) {
+                    const node = {};
+                    for (let x = 0; x < 10; x++) {
+                        node['k' + x] = depth > 0 ? build(depth - 1) : x;
+                    }
+                    return node;
+                }
+                build(4);
+            "#,
+                ))
+                .expect("script failed");
+            let args = vec![realm.create_string("huge").unwrap(), huge];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert!(line.starts_with("JS_REALM:[__main__]: huge {\"k0\":{"));
+            assert_eq!(line.matches("… (output limited)").count(), 1);
+            assert!(line.len() < 2000);
+        });
+    }
+
+    #[cfg(feature = "print")]
+    #[test]
+    pub fn test_print() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = realm
+                .eval(Script::new("test_print.js", "['a', 1, true];"))
+                .expect("script failed");
+            let args: Vec<_> = (0..3)
+                .map(|index| realm.get_array_element(&args, index).unwrap())
+                .collect();
+            let line = unsafe { super::print_line(realm.context, &args) };
+            assert_eq!(line, "a 1 true");
+
+            let res = realm
+                .eval(Script::new("test_print2.js", "typeof print;"))
+                .expect("script failed");
+            assert_eq!(res.to_string().unwrap(), "function");
+            realm
+                .eval(Script::new("test_print3.js", "print('a', 1, true);"))
+                .expect("script failed");
+        });
+    }
+
+    #[test]
+    pub fn test_css_directives() {
+        assert_eq!(css_to_ansi("color: red"), "\x1b[31m");
+        assert_eq!(
+            css_to_ansi("font-weight: bold; background-color: Blue; margin: 2px"),
+            "\x1b[1;44m"
+        );
+        assert_eq!(css_to_ansi("padding: 4px"), "");
+
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("%cred%c and %cplain %s").unwrap(),
+                realm.create_string("color: red").unwrap(),
+                realm.create_string("").unwrap(),
+                realm.create_string("font-weight: bold").unwrap(),
+                realm.create_string("text").unwrap(),
+                realm.create_i32(1).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: red and plain text 1");
+        });
+
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_ansi_styles(true)
+            .build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("%cred%c plain").unwrap(),
+                realm.create_string("color: red").unwrap(),
+                realm.create_string("").unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: \x1b[31mred\x1b[0m plain");
+        });
+    }
+
+    #[test]
+    pub fn test_percent_escape() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("value is 50%% of %i").unwrap(),
+                realm.create_i32(200).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: value is 50% of 200");
+
+            let args = vec![realm.create_string("100%% done").unwrap()];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: 100% done");
+        });
+    }
+
+    #[test]
+    pub fn test_logfmt() {
+        let rt = QuickJsRuntimeBuilder::new().console_logfmt(true).build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let fields = realm
+                .eval(Script::new(
+                    "test_logfmt.js",
+                    "({ms: 5, ok: true, msg: 'all good', nested: {a: 1}});",
+                ))
+                .expect("script failed");
+            let args = vec![realm.create_string("done").unwrap(), fields];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(
+                line,
+                r#"JS_REALM:[__main__]: done ms=5 ok=true msg="all good" nested="{\"a\":1}""#
+            );
+
+            // class instances and lone objects are not rendered as fields
+            let instance = realm
+                .eval(Script::new(
+                    "test_logfmt2.js",
+                    "class LogFmtTest {constructor() {this.a = 1;}}; new LogFmtTest();",
+                ))
+                .expect("script failed");
+            let args = vec![realm.create_string("instance").unwrap(), instance];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, r#"JS_REALM:[__main__]: instance {"a":1}"#);
+        });
+    }
+
+    #[test]
+    pub fn test_unserializable() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let bad_json = realm
+                .eval(Script::new(
+                    "test_unserializable.js",
+                    "({toJSON: function() {throw Error('no json for you');}});",
+                ))
+                .expect("script failed");
+            let bad_str = realm
+                .eval(Script::new(
+                    "test_unserializable2.js",
+                    "({toString: function() {throw Error('no str for you');}});",
+                ))
+                .expect("script failed");
+            let args = vec![
+                realm.create_string("hello %s").unwrap(),
+                bad_str,
+                bad_json,
+                realm.create_string("world").unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(
+                line,
+                "JS_REALM:[__main__]: hello [Unserializable: no str for you] [Unserializable: no json for you] world"
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_format_args() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let format = realm
+                .create_function(
+                    "format",
+                    |realm, _this, args| realm.create_string(format_args(realm, args).as_str()),
+                    1,
+                )
+                .expect("could not create function");
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "format", &format)
+                .expect("could not set format");
+            realm
+                .eval(Script::new(
+                    "test_format_args.js",
+                    "console.group(); const res = format('%s=%i%%', 'done', 99.5, {a: 1}); console.groupEnd(); res;",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, r#"done=99% {"a":1}"#);
+    }
+
+    #[test]
+    pub fn test_substitutions_not_rescanned() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("%s and %d").unwrap(),
+                realm.create_string("%d").unwrap(),
+                realm.create_i32(1).unwrap(),
+                realm.create_string("%s%%").unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: %d and 1 %s%%");
+        });
+    }
+
+    #[test]
+    pub fn test_int_width() {
+        assert_eq!(pad_to_width("42".to_string(), "5d"), "   42");
+        assert_eq!(pad_to_width("42".to_string(), "-5d"), "42   ");
+        assert_eq!(pad_to_width("-42".to_string(), "05i"), "-0042");
+        assert_eq!(pad_to_width("123456".to_string(), "3d"), "123456");
+        assert_eq!(pad_to_width("003".to_string(), ".3i"), "003");
+
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("[%5d] [%-5d] [%.3i] [%6.3i]").unwrap(),
+                realm.create_i32(42).unwrap(),
+                realm.create_i32(42).unwrap(),
+                realm.create_i32(3).unwrap(),
+                realm.create_i32(3).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: [   42] [42   ] [003] [   003]");
+        });
+    }
+
+    #[test]
+    pub fn test_number_format() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .console_grouping_separator(',')
+            .console_decimal_separator(',')
+            .build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("int %d neg %i small %d").unwrap(),
+                realm.create_i32(1000000).unwrap(),
+                realm.create_i32(-1234567).unwrap(),
+                realm.create_i32(123).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(
+                line,
+                "JS_REALM:[__main__]: int 1,000,000 neg -1,234,567 small 123"
+            );
+
+            let args = vec![
+                realm.create_string("float %.2f").unwrap(),
+                realm.create_f64(1.5).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: float 1,50");
+        });
+
+        // plain output by default
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let args = vec![
+                realm.create_string("%d %.2f").unwrap(),
+                realm.create_i32(1000000).unwrap(),
+                realm.create_f64(1.5).unwrap(),
+            ];
+            let line = unsafe { parse_line(realm.context, args) };
+            assert_eq!(line, "JS_REALM:[__main__]: 1000000 1.50");
+        });
+    }
+
     #[test]
     pub fn test_console() {
         //simple_logging::log_to_stderr(LevelFilter::Info);
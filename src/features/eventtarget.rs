@@ -0,0 +1,183 @@
+//! provides the EventTarget and Event classes for the runtime
+//!
+//! EventTarget is a [Proxy](crate::reflection::Proxy) class which implements [event_target](crate::reflection::Proxy::event_target)
+//! script classes may extend it to implement DOM-like event patterns and Proxy classes may extend it with `Proxy::extends("EventTarget")`
+//!
+//! an Event has a type and an optional detail, dispatchEvent returns false when a listener called preventDefault() on a cancelable Event
+//! listeners are called with the Event as only argument and are not called in a guaranteed order
+//!
+//! this feature is not enabled by default, add `features = ["eventtarget"]` to the quickjs_runtime dependency to use it
+//! # Example
+//! ```rust
+//! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+//! use quickjs_runtime::jsutils::Script;
+//! let rt = QuickJsRuntimeBuilder::new().build();
+//! let res = rt.eval_sync(None, Script::new("test_eventtarget.js", r#"
+//!     class Door extends EventTarget {
+//!         open() {
+//!             this.dispatchEvent(new Event('open', {detail: 'front'}));
+//!         }
+//!     }
+//!     let door = new Door();
+//!     let opened = null;
+//!     door.addEventListener('open', (evt) => {opened = evt.detail;});
+//!     door.open();
+//!     opened;
+//! "#)).expect("script failed");
+//! assert_eq!(res.get_str(), "front");
+//! ```
+
+use crate::jsutils::JsError;
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use crate::reflection::Proxy;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+struct EventData {
+    event_type: String,
+    detail: Option<QuickJsValueAdapter>,
+    cancelable: bool,
+    default_prevented: bool,
+}
+
+thread_local! {
+    static EVENTS: RefCell<HashMap<usize, EventData>> = RefCell::new(HashMap::new());
+}
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("eventtarget::init");
+
+    q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_realm(q_ctx))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    Proxy::new()
+        .name("EventTarget")
+        .constructor(|_rt, _realm, _id, _args| Ok(()))
+        .event_target()
+        .install(realm, true)?;
+
+    Proxy::new()
+        .name("Event")
+        .constructor(|_rt, realm, id, args| {
+            let event_type = match args.first() {
+                Some(arg) => arg.to_string()?,
+                None => {
+                    return Err(JsError::new(
+                        "TypeError".to_string(),
+                        "Event constructor requires a type".to_string(),
+                        "".to_string(),
+                    ))
+                }
+            };
+            let (detail, cancelable) = match args.get(1) {
+                Some(options) if options.is_object() => {
+                    let detail = realm.get_object_property(options, "detail")?;
+                    let cancelable = realm.get_object_property(options, "cancelable")?;
+                    (
+                        Some(detail).filter(|detail| !detail.is_undefined()),
+                        cancelable.is_truthy(),
+                    )
+                }
+                _ => (None, false),
+            };
+            EVENTS.with(|rc| {
+                rc.borrow_mut().insert(
+                    id,
+                    EventData {
+                        event_type,
+                        detail,
+                        cancelable,
+                        default_prevented: false,
+                    },
+                )
+            });
+            Ok(())
+        })
+        .read_only_getter("type", |_rt, realm, id| {
+            let event_type = with_event(id, |event| event.event_type.clone())?;
+            realm.create_string(event_type.as_str())
+        })
+        .read_only_getter("detail", |_rt, realm, id| {
+            match with_event(id, |event| event.detail.clone())? {
+                Some(detail) => Ok(detail),
+                None => realm.create_null(),
+            }
+        })
+        .read_only_getter("cancelable", |_rt, realm, id| {
+            realm.create_boolean(with_event(id, |event| event.cancelable)?)
+        })
+        .read_only_getter("defaultPrevented", |_rt, realm, id| {
+            realm.create_boolean(with_event(id, |event| event.default_prevented)?)
+        })
+        .method("preventDefault", |_rt, realm, id, _args| {
+            EVENTS.with(|rc| {
+                if let Some(event) = rc.borrow_mut().get_mut(id) {
+                    if event.cancelable {
+                        event.default_prevented = true;
+                    }
+                }
+            });
+            realm.create_undefined()
+        })
+        .finalizer(|_rt, _realm, id| {
+            EVENTS.with(|rc| rc.borrow_mut().remove(&id));
+        })
+        .install(realm, true)?;
+    Ok(())
+}
+
+fn with_event<C, R>(id: &usize, consumer: C) -> Result<R, JsError>
+where
+    C: FnOnce(&EventData) -> R,
+{
+    EVENTS.with(|rc| match rc.borrow().get(id) {
+        Some(event) => Ok(consumer(event)),
+        None => Err(JsError::new_str("no such Event")),
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+
+    #[test]
+    fn test_extend_event_target() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_extend_event_target.js",
+                    r#"
+            class Clock extends EventTarget {
+                constructor() {
+                    super();
+                    this.started = true;
+                }
+                tick() {
+                    return this.dispatchEvent(new Event('tick', {detail: 42, cancelable: true}));
+                }
+            }
+            globalThis.clock = new Clock();
+            globalThis.received = [];
+            let listener = (evt) => {
+                received.push(evt.type + ':' + evt.detail + ':' + (evt instanceof Event) + ':' + clock.started);
+                evt.preventDefault();
+            };
+            clock.addEventListener('tick', listener);
+            let notPrevented = clock.tick();
+            clock.removeEventListener('tick', listener);
+            clock.tick();
+            received.join(',') + '|' + notPrevented;
+        "#,
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(res.get_str(), "tick:42:true:true|false");
+    }
+}
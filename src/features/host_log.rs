@@ -0,0 +1,251 @@
+//! the hostlog feature adds a hostLog global which lets scripts subscribe to the log records of the rust side of the application
+//!
+//! records are passed to the runtimes with [forward_record], or by installing a [HostLogger] which wraps the logger of the application
+//! the records are dispatched to the subscribed handlers in the worker thread of each runtime
+//!
+//! records which are logged while the handlers run (e.g. by a handler calling console.log) are not forwarded again
+//! # Example
+//! ```javascript
+//! const unsubscribe = hostLog.subscribe('warn', (record) => {
+//!     // record is {level: 'WARN', target: 'my_app::db', message: 'pool exhausted'}
+//!     dashboard.add(record.level, record.message);
+//! });
+//! ```
+//! ```rust
+//! use log::LevelFilter;
+//! use quickjs_runtime::features::host_log::HostLogger;
+//! let logger = HostLogger::new(simple_logging_compatible_logger(), LevelFilter::Warn);
+//! // logger.init().expect("could not install logger");
+//! # fn simple_logging_compatible_logger() -> impl log::Log {
+//! #     struct NoLogger {}
+//! #     impl log::Log for NoLogger {
+//! #         fn enabled(&self, _metadata: &log::Metadata) -> bool { false }
+//! #         fn log(&self, _record: &log::Record) {}
+//! #         fn flush(&self) {}
+//! #     }
+//! #     NoLogger {}
+//! # }
+//! ```
+
+use crate::facades::QuickjsRuntimeFacadeInner;
+use crate::jsutils::{JsError, Script};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::cell::Cell;
+use std::sync::{Arc, Mutex, Weak};
+
+lazy_static! {
+    /// the runtimes records are forwarded to
+    static ref RUNTIMES: Mutex<Vec<Weak<QuickjsRuntimeFacadeInner>>> = Mutex::new(vec![]);
+}
+
+thread_local! {
+    // set while records are dispatched to script so records logged by the handlers are not forwarded again
+    static DISPATCHING: Cell<bool> = Cell::new(false);
+}
+
+const HOST_LOG_SOURCE: &str = r#"
+(function() {
+    const levels = ['ERROR', 'WARN', 'INFO', 'DEBUG', 'TRACE'];
+    const subscribers = [];
+    const hostLog = {
+        subscribe(level, handler) {
+            const maxLevel = levels.indexOf(String(level).toUpperCase());
+            if (maxLevel < 0) {
+                throw new TypeError('unknown log level: ' + level);
+            }
+            if (typeof handler !== 'function') {
+                throw new TypeError('handler is not a function');
+            }
+            const subscriber = {maxLevel, handler};
+            subscribers.push(subscriber);
+            return function unsubscribe() {
+                const index = subscribers.indexOf(subscriber);
+                if (index >= 0) {
+                    subscribers.splice(index, 1);
+                }
+            };
+        }
+    };
+    Object.defineProperty(hostLog, 'dispatch', {
+        value: function(level, target, message) {
+            const levelIndex = levels.indexOf(level);
+            let failure;
+            for (const subscriber of subscribers.slice()) {
+                if (levelIndex <= subscriber.maxLevel) {
+                    try {
+                        subscriber.handler({level, target, message});
+                    } catch (err) {
+                        // one failing handler should not keep the record from the other handlers
+                        failure = failure || err;
+                    }
+                }
+            }
+            if (failure) {
+                throw failure;
+            }
+        }
+    });
+    globalThis.hostLog = hostLog;
+})();
+"#;
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("host_log::init");
+
+    if let Some(rti) = q_js_rt.get_rti_ref() {
+        let runtimes = &mut *RUNTIMES.lock().unwrap();
+        runtimes.push(Arc::downgrade(&rti));
+    }
+    q_js_rt.add_context_init_hook(|_q_js_rt, realm| init_realm(realm))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    realm.eval(Script::new("host_log.js", HOST_LOG_SOURCE))?;
+    Ok(())
+}
+
+/// pass a log record to the hostLog subscribers of all runtimes
+/// the record is dispatched asynchronously in the worker thread of each runtime
+pub fn forward_record(level: Level, target: &str, message: &str) {
+    if DISPATCHING.with(|rc| rc.get()) {
+        return;
+    }
+    // upgrade outside of the lock so a record which is logged while adding the tasks can not deadlock
+    let runtimes: Vec<Arc<QuickjsRuntimeFacadeInner>> = {
+        let runtimes = &mut *RUNTIMES.lock().unwrap();
+        runtimes.retain(|rti| rti.strong_count() > 0);
+        runtimes.iter().filter_map(|rti| rti.upgrade()).collect()
+    };
+    for rti in runtimes {
+        let target = target.to_string();
+        let message = message.to_string();
+        rti.add_rt_task_to_event_loop_void(move |q_js_rt| {
+            dispatch(q_js_rt, level, target.as_str(), message.as_str());
+        });
+    }
+}
+
+fn dispatch(q_js_rt: &QuickJsRuntimeAdapter, level: Level, target: &str, message: &str) {
+    DISPATCHING.with(|rc| rc.set(true));
+    for realm in q_js_rt.contexts.values() {
+        if let Err(e) = dispatch_to_realm(realm, level, target, message) {
+            log::error!("hostLog handler failed in realm {}: {}", realm.id, e);
+        }
+    }
+    DISPATCHING.with(|rc| rc.set(false));
+}
+
+fn dispatch_to_realm(
+    realm: &QuickJsRealmAdapter,
+    level: Level,
+    target: &str,
+    message: &str,
+) -> Result<(), JsError> {
+    let host_log = realm.get_object_property(&realm.get_global()?, "hostLog")?;
+    if !host_log.is_object() {
+        return Ok(());
+    }
+    let args = [
+        realm.create_string(level.as_str())?,
+        realm.create_string(target)?,
+        realm.create_string(message)?,
+    ];
+    realm.invoke_function_on_object_by_name(&host_log, "dispatch", &args)?;
+    Ok(())
+}
+
+/// a logger which passes records to the logger of the application and forwards the records up to a level to the hostLog subscribers
+pub struct HostLogger {
+    inner: Box<dyn Log>,
+    level: LevelFilter,
+}
+
+impl HostLogger {
+    /// wrap a logger, records up to level are also forwarded to scripts
+    pub fn new<L: Log + 'static>(inner: L, level: LevelFilter) -> Self {
+        Self {
+            inner: Box::new(inner),
+            level,
+        }
+    }
+    /// install this as the global logger of the log crate
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        log::set_max_level(std::cmp::max(self.level, log::max_level()));
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl Log for HostLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+        if record.level() <= self.level {
+            forward_record(
+                record.level(),
+                record.target(),
+                record.args().to_string().as_str(),
+            );
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::features::host_log::forward_record;
+    use crate::jsutils::Script;
+    use log::Level;
+
+    #[test]
+    fn test_host_log() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.eval_sync(
+            None,
+            Script::new(
+                "test_host_log.js",
+                r#"
+            globalThis.received = [];
+            const unsubscribe = hostLog.subscribe('warn', (record) => {
+                received.push(record.level + ':' + record.target + ':' + record.message);
+                console.log('logged by a handler');
+            });
+            hostLog.subscribe('error', () => {
+                throw Error('failing handler');
+            });
+            globalThis.stop = unsubscribe;
+        "#,
+            ),
+        )
+        .expect("script failed");
+
+        forward_record(Level::Warn, "my_app::db", "pool exhausted");
+        forward_record(Level::Info, "my_app::db", "query done");
+        forward_record(Level::Error, "my_app", "disk full");
+        rt.eval_sync(None, Script::new("test_host_log2.js", "stop();"))
+            .expect("script failed");
+        forward_record(Level::Error, "my_app", "after unsubscribe");
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new("test_host_log3.js", "received.join('|');"),
+            )
+            .expect("script failed");
+        assert_eq!(
+            res.get_str(),
+            "WARN:my_app::db:pool exhausted|ERROR:my_app:disk full"
+        );
+    }
+}
@@ -1,19 +1,37 @@
-//! contains engine features like console, setTimeout, setInterval and setImmediate
+//! contains engine features like console, setTimeout, setInterval, setImmediate, queueMicrotask, EventTarget, TextEncoder, TextDecoder, require and hostLog
+//! and the web globals which are installed with QuickJsRuntimeBuilder::with_web_features (web feature)
 
 use crate::facades::QuickJsRuntimeFacade;
 use crate::jsutils::JsError;
 #[cfg(feature = "console")]
 pub mod console;
+#[cfg(feature = "eventtarget")]
+pub mod eventtarget;
+#[cfg(feature = "hostlog")]
+pub mod host_log;
+#[cfg(feature = "queuemicrotask")]
+pub mod queue_microtask;
+#[cfg(feature = "require")]
+pub mod require;
 #[cfg(any(feature = "settimeout", feature = "setinterval"))]
 pub mod set_timeout;
 #[cfg(feature = "setimmediate")]
 pub mod setimmediate;
+#[cfg(feature = "textencoding")]
+pub mod text_encoding;
+#[cfg(feature = "web")]
+pub mod web;
 
 #[cfg(any(
     feature = "settimeout",
     feature = "setinterval",
     feature = "console",
-    feature = "setimmediate"
+    feature = "setimmediate",
+    feature = "eventtarget",
+    feature = "require",
+    feature = "hostlog",
+    feature = "queuemicrotask",
+    feature = "textencoding"
 ))]
 pub fn init(es_rt: &QuickJsRuntimeFacade) -> Result<(), JsError> {
     log::trace!("features::init");
@@ -23,6 +41,16 @@ pub fn init(es_rt: &QuickJsRuntimeFacade) -> Result<(), JsError> {
         console::init(q_js_rt)?;
         #[cfg(feature = "setimmediate")]
         setimmediate::init(q_js_rt)?;
+        #[cfg(feature = "eventtarget")]
+        eventtarget::init(q_js_rt)?;
+        #[cfg(feature = "require")]
+        require::init(q_js_rt)?;
+        #[cfg(feature = "hostlog")]
+        host_log::init(q_js_rt)?;
+        #[cfg(feature = "queuemicrotask")]
+        queue_microtask::init(q_js_rt)?;
+        #[cfg(feature = "textencoding")]
+        text_encoding::init(q_js_rt)?;
 
         #[cfg(any(feature = "settimeout", feature = "setinterval"))]
         set_timeout::init(q_js_rt)?;
@@ -0,0 +1,123 @@
+//! provides the queueMicrotask method for the runtime, this requires the optional "queuemicrotask" feature
+//!
+//! the callback is added to the job queue of QuickJS, so like a Promise reaction it runs after the current task but before
+//! the next macrotask (e.g. a setTimeout or setImmediate callback)
+//! # Example
+//! ```rust
+//! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+//! use quickjs_runtime::jsutils::Script;
+//! let rt = QuickJsRuntimeBuilder::new().build();
+//! rt.eval_sync(None, Script::new("queue_microtask.js", r#"
+//!     globalThis.order = [];
+//!     queueMicrotask(() => order.push('microtask'));
+//!     order.push('sync');
+//! "#)).expect("script failed");
+//! let res = rt.eval_sync(None, Script::new("queue_microtask2.js", "order.join(',');")).expect("script failed");
+//! assert_eq!(res.get_str(), "sync,microtask");
+//! ```
+
+use crate::jsutils::JsError;
+use crate::quickjs_utils;
+use crate::quickjs_utils::functions;
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use libquickjs_sys as q;
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("queue_microtask::init");
+
+    q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_realm(q_ctx))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    let queue_microtask_func = realm.create_function(
+        "queueMicrotask",
+        |realm, _this, args| {
+            if args.is_empty() || !functions::is_function_q(realm, &args[0]) {
+                return Err(JsError::new(
+                    "TypeError".to_string(),
+                    "queueMicrotask requires a function as first arg".to_string(),
+                    "".to_string(),
+                ));
+            }
+            let mut callback = *args[0].borrow_value();
+            // JS_EnqueueJob duplicates the args so the callback stays alive until the job has run
+            let res =
+                unsafe { q::JS_EnqueueJob(realm.context, Some(microtask_job), 1, &mut callback) };
+            if res < 0 {
+                return Err(JsError::new_str("could not enqueue microtask"));
+            }
+            realm.create_undefined()
+        },
+        1,
+    )?;
+    realm.set_object_property(
+        &realm.get_global()?,
+        "queueMicrotask",
+        &queue_microtask_func,
+    )?;
+    Ok(())
+}
+
+unsafe extern "C" fn microtask_job(
+    context: *mut q::JSContext,
+    _argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    log::trace!("> microtask_job");
+    // an exception is returned as is so it is reported like a failing Promise reaction
+    q::JS_Call(
+        context,
+        *argv,
+        quickjs_utils::new_undefined(),
+        0,
+        std::ptr::null_mut(),
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::facades::tests::init_test_rt;
+    use crate::jsutils::Script;
+    use crate::values::JsValueFacade;
+
+    #[test]
+    fn test_queue_microtask() {
+        let rt = init_test_rt();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_queue_microtask.js",
+                    r#"
+            (() => {
+                const order = [];
+                queueMicrotask(() => order.push('microtask'));
+                const timeout = new Promise((resolve) => setTimeout(() => {
+                    order.push('timeout');
+                    resolve();
+                }, 0));
+                Promise.resolve().then(() => order.push('promise'));
+                try {
+                    queueMicrotask('not a function');
+                } catch (err) {
+                    order.push(err instanceof TypeError);
+                }
+                order.push('sync');
+                return timeout.then(() => order.join(','));
+            })()
+        "#,
+                ),
+            )
+            .expect("script failed");
+        let res = match res {
+            JsValueFacade::JsPromise { cached_promise } => cached_promise
+                .get_promise_result_sync()
+                .expect("promise timed out")
+                .expect("promise was rejected"),
+            _ => panic!("script did not return a promise"),
+        };
+        assert_eq!(res.get_str(), "true,sync,microtask,promise,timeout");
+    }
+}
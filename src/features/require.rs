@@ -0,0 +1,198 @@
+//! provides a Node-style require() function for CommonJS scripts
+//!
+//! modules are loaded with the ScriptModuleLoaders of the runtime and evaluated in a wrapper function which provides
+//! `exports`, `require`, `module` and `__filename`, the value of `module.exports` is returned and cached per normalized path
+//!
+//! when modules require each other in a cycle the second require returns the partially populated exports of the module which is still loading
+//! # Example
+//! ```rust
+//! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+//! use quickjs_runtime::jsutils::modules::ScriptModuleLoader;
+//! use quickjs_runtime::jsutils::Script;
+//! use quickjs_runtime::quickjsrealmadapter::QuickJsRealmAdapter;
+//!
+//! struct GreeterLoader {}
+//! impl ScriptModuleLoader for GreeterLoader {
+//!     fn normalize_path(&self, _realm: &QuickJsRealmAdapter, _ref_path: &str, path: &str) -> Option<String> {
+//!         Some(path.to_string())
+//!     }
+//!     fn load_module(&self, _realm: &QuickJsRealmAdapter, _absolute_path: &str) -> String {
+//!         "module.exports = function(name) {return 'hello ' + name;};".to_string()
+//!     }
+//! }
+//!
+//! let rt = QuickJsRuntimeBuilder::new().script_module_loader(GreeterLoader{}).build();
+//! let res = rt.eval_sync(None, Script::new("require.js", "require('greeter.js')('world');")).expect("script failed");
+//! assert_eq!(res.get_str(), "hello world");
+//! ```
+
+use crate::jsutils::{JsError, Script};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+
+const REQUIRE_SOURCE: &str = r#"
+(function(loadModule, compileModule) {
+    const cache = new Map();
+    const resolved = new Map();
+
+    function createRequire(refPath) {
+        return function require(id) {
+            id = String(id);
+            const key = refPath + '\n' + id;
+            let path = resolved.get(key);
+            let code;
+            if (path === undefined) {
+                const loaded = loadModule(refPath, id);
+                path = loaded.path;
+                code = loaded.code;
+                resolved.set(key, path);
+            }
+            let module = cache.get(path);
+            if (module) {
+                // this may be a module which is still loading (a circular require), in that case its exports are partially populated
+                return module.exports;
+            }
+            if (code === undefined) {
+                code = loadModule(refPath, id).code;
+            }
+            module = {id: path, exports: {}, loaded: false};
+            cache.set(path, module);
+            try {
+                compileModule(path, code).call(module.exports, module.exports, createRequire(path), module, path);
+            } catch (err) {
+                cache.delete(path);
+                throw err;
+            }
+            module.loaded = true;
+            return module.exports;
+        };
+    }
+
+    globalThis.require = createRequire('');
+})
+"#;
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("require::init");
+
+    q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_realm(q_ctx))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    let load_module_func = realm.create_function(
+        "loadModule",
+        |realm, _this, args| {
+            if args.len() != 2 || !args[0].is_string() || !args[1].is_string() {
+                return Err(JsError::new_str(
+                    "loadModule requires a ref_path and id string",
+                ));
+            }
+            let ref_path = args[0].to_string()?;
+            let id = args[1].to_string()?;
+            let script_opt = QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+                q_js_rt.load_module_script_opt(ref_path.as_str(), id.as_str())
+            });
+            let script = script_opt
+                .ok_or_else(|| JsError::new_string(format!("Cannot find module '{id}'")))?;
+            let loaded = realm.create_object()?;
+            realm.set_object_property(&loaded, "path", &realm.create_string(script.get_path())?)?;
+            realm.set_object_property(&loaded, "code", &realm.create_string(script.get_code())?)?;
+            Ok(loaded)
+        },
+        2,
+    )?;
+    let compile_module_func = realm.create_function(
+        "compileModule",
+        |realm, _this, args| {
+            if args.len() != 2 || !args[0].is_string() || !args[1].is_string() {
+                return Err(JsError::new_str(
+                    "compileModule requires a path and code string",
+                ));
+            }
+            let path = args[0].to_string()?;
+            let code = args[1].to_string()?;
+            // the wrapper starts on the first line of the module so line numbers in stack traces are unchanged
+            let wrapped = format!("(function (exports, require, module, __filename) {{{code}\n}})");
+            realm.eval(Script::new(path.as_str(), wrapped.as_str()))
+        },
+        2,
+    )?;
+
+    let installer = realm.eval(Script::new("require.js", REQUIRE_SOURCE))?;
+    realm.invoke_function(None, &installer, &[&load_module_func, &compile_module_func])?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::modules::ScriptModuleLoader;
+    use crate::jsutils::Script;
+    use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+
+    struct CjsModuleLoader {}
+
+    impl ScriptModuleLoader for CjsModuleLoader {
+        fn normalize_path(
+            &self,
+            _realm: &QuickJsRealmAdapter,
+            _ref_path: &str,
+            path: &str,
+        ) -> Option<String> {
+            if path.ends_with(".cjs") {
+                Some(format!("/modules/{}", path.trim_start_matches("./")))
+            } else {
+                None
+            }
+        }
+
+        fn load_module(&self, _realm: &QuickJsRealmAdapter, absolute_path: &str) -> String {
+            match absolute_path {
+                "/modules/math.cjs" => {
+                    "globalThis.mathLoadCount = (globalThis.mathLoadCount || 0) + 1;\
+                    module.exports = {add: (a, b) => a + b, file: __filename};"
+                }
+                "/modules/a.cjs" => {
+                    "exports.early = 'a'; const b = require('./b.cjs'); exports.fromB = b.seenFromA;"
+                }
+                "/modules/b.cjs" => {
+                    "const a = require('./a.cjs'); exports.seenFromA = a.early + '|' + (a.fromB === undefined);"
+                }
+                _ => "throw Error('unexpected module');",
+            }
+            .to_string()
+        }
+    }
+
+    #[test]
+    fn test_require() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .script_module_loader(CjsModuleLoader {})
+            .build();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_require.js",
+                    r#"
+            const math = require('./math.cjs');
+            const again = require('./math.cjs');
+            const a = require('./a.cjs');
+            let missing;
+            try {
+                require('missing.js');
+            } catch (err) {
+                missing = err.message;
+            }
+            [math.add(1, 2), math.file, math === again, mathLoadCount, a.fromB, missing].join(',');
+        "#,
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(
+            res.get_str(),
+            "3,/modules/math.cjs,true,1,a|true,Cannot find module 'missing.js'"
+        );
+    }
+}
@@ -1,9 +1,15 @@
+#[cfg(feature = "console")]
+use crate::features::console::GroupContext;
 use crate::jsutils::JsError;
 use crate::quickjs_utils;
 use crate::quickjs_utils::{functions, get_global, objects, parse_args, primitives};
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 use hirofa_utils::eventloop::EventLoop;
 use libquickjs_sys as q;
+#[cfg(feature = "settimeout")]
+use std::cell::Cell;
+#[cfg(feature = "settimeout")]
+use std::rc::Rc;
 use std::time::Duration;
 
 /// provides the setImmediate methods for the runtime
@@ -96,12 +102,20 @@ unsafe extern "C" fn set_timeout(
         };
 
         let q_ctx_id = q_ctx.id.clone();
+        #[cfg(feature = "console")]
+        let group_context = GroupContext::capture(q_ctx);
+        // the id is only known after the timeout was added
+        let timer_id = Rc::new(Cell::new(0));
+        let timer_id2 = timer_id.clone();
 
         let id = EventLoop::add_timeout(
             move || {
+                #[cfg(feature = "console")]
+                let _group = group_context.enter();
                 QuickJsRuntimeAdapter::do_with(|q_js_rt| {
                     let func = &args[0];
                     if let Some(q_ctx) = q_js_rt.opt_context(q_ctx_id.as_str()) {
+                        q_ctx.remove_pending_timer(timer_id2.get());
                         match functions::call_function_q(q_ctx, func, &args[2..], None) {
                             Ok(_) => {}
                             Err(e) => {
@@ -116,6 +130,8 @@ unsafe extern "C" fn set_timeout(
             },
             Duration::from_millis(delay_ms),
         );
+        timer_id.set(id);
+        q_ctx.add_pending_timer(id);
         log::trace!("set_timeout: {}", id);
         primitives::from_i32(id).clone_value_incr_rc()
     })
@@ -157,9 +173,13 @@ unsafe extern "C" fn set_interval(
         };
 
         let q_ctx_id = q_ctx.id.clone();
+        #[cfg(feature = "console")]
+        let group_context = GroupContext::capture(q_ctx);
 
         let id = EventLoop::add_interval(
             move || {
+                #[cfg(feature = "console")]
+                let _group = group_context.enter();
                 QuickJsRuntimeAdapter::do_with(|q_js_rt| {
                     if let Some(q_ctx) = q_js_rt.opt_context(q_ctx_id.as_str()) {
                         let func = &args[0];
@@ -179,6 +199,7 @@ unsafe extern "C" fn set_interval(
             Duration::from_millis(delay_ms),
             Duration::from_millis(delay_ms),
         );
+        q_ctx.add_pending_timer(id);
         log::trace!("set_interval: {}", id);
         primitives::from_i32(id).clone_value_incr_rc()
     })
@@ -205,6 +226,10 @@ unsafe extern "C" fn clear_interval(
         let id = primitives::to_i32(&args[0]).ok().unwrap();
         log::trace!("clear_interval: {}", id);
         EventLoop::clear_interval(id);
+        // timer ids are unique for the runtime, the interval may have been scheduled from another realm
+        for realm in q_js_rt.contexts.values() {
+            realm.remove_pending_timer(id);
+        }
         quickjs_utils::new_null()
     })
 }
@@ -232,6 +257,10 @@ unsafe extern "C" fn clear_timeout(
         log::trace!("clear_timeout: {}", id);
 
         EventLoop::clear_timeout(id);
+        // timer ids are unique for the runtime, the timeout may have been scheduled from another realm
+        for realm in q_js_rt.contexts.values() {
+            realm.remove_pending_timer(id);
+        }
 
         quickjs_utils::new_null()
     })
@@ -319,6 +348,34 @@ pub mod tests {
         log::info!("done");
     }
 
+    #[test]
+    fn test_clear_pending_timeout() {
+        let rt = init_test_rt();
+        let esvf = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_clear_pending_timeout.es",
+                    "new Promise((resolve, reject) => {\
+                                const id = setTimeout(() => {reject('cleared timeout fired');}, 20);\
+                                clearTimeout(id);\
+                                setTimeout(() => {resolve(typeof id);}, 50);\
+                            });\
+            ",
+                ),
+            )
+            .expect("script failed");
+        assert!(esvf.is_js_promise());
+
+        match esvf {
+            JsValueFacade::JsPromise { cached_promise } => {
+                let res = cached_promise.get_promise_result_sync().expect("timed out");
+                assert_eq!(res.ok().expect("promise was rejected").get_str(), "number");
+            }
+            _ => panic!("not a promise"),
+        }
+    }
+
     #[test]
     fn test_set_timeout() {
         let rt = init_test_rt();
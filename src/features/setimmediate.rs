@@ -1,11 +1,22 @@
 use crate::facades::QuickJsRuntimeFacade;
+#[cfg(feature = "console")]
+use crate::features::console::GroupContext;
 use crate::jsutils::JsError;
 use crate::quickjs_utils;
-use crate::quickjs_utils::{functions, get_global_q, objects, parse_args};
+use crate::quickjs_utils::{functions, get_global_q, objects, parse_args, primitives};
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 use libquickjs_sys as q;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 
-/// provides the setImmediate methods for the runtime
+thread_local! {
+    static NEXT_IMMEDIATE_ID: Cell<i32> = Cell::new(1);
+    // ids of immediates which have been scheduled and were not yet run or cleared
+    static PENDING_IMMEDIATES: RefCell<HashSet<i32>> = RefCell::new(HashSet::new());
+}
+
+/// provides the setImmediate and clearImmediate methods for the runtime
+/// an immediate runs after the current task and its microtasks (e.g. Promise reactions) have completed but before timeouts
 /// # Example
 /// ```rust
 /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
@@ -26,6 +37,15 @@ pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
         let global = get_global_q(q_ctx);
 
         objects::set_property2_q(q_ctx, &global, "setImmediate", &set_immediate_func, 0)?;
+
+        let clear_immediate_func = functions::new_native_function_q(
+            q_ctx,
+            "clearImmediate",
+            Some(clear_immediate),
+            1,
+            false,
+        )?;
+        objects::set_property2_q(q_ctx, &global, "clearImmediate", &clear_immediate_func, 0)?;
         Ok(())
     })?;
     Ok(())
@@ -50,8 +70,23 @@ unsafe extern "C" fn set_immediate(
             return q_ctx.report_ex("setImmediate requires a functions as first arg");
         }
 
+        let id = NEXT_IMMEDIATE_ID.with(|rc| {
+            let id = rc.get();
+            rc.set(if id == i32::MAX { 1 } else { id + 1 });
+            id
+        });
+        PENDING_IMMEDIATES.with(|rc| rc.borrow_mut().insert(id));
+        #[cfg(feature = "console")]
+        let group_context = GroupContext::capture(q_ctx);
+
         QuickJsRuntimeFacade::add_local_task_to_event_loop(move |_q_js_rt| {
+            if !PENDING_IMMEDIATES.with(|rc| rc.borrow_mut().remove(&id)) {
+                log::trace!("immediate {} was cleared", id);
+                return;
+            }
             let func = &args[0];
+            #[cfg(feature = "console")]
+            let _group = group_context.enter();
 
             match functions::call_function(context, func, &args[1..], None) {
                 Ok(_) => {}
@@ -61,6 +96,71 @@ unsafe extern "C" fn set_immediate(
             };
         });
 
+        primitives::from_i32(id).clone_value_incr_rc()
+    })
+}
+
+unsafe extern "C" fn clear_immediate(
+    context: *mut q::JSContext,
+    _this_val: q::JSValue,
+    argc: ::std::os::raw::c_int,
+    argv: *mut q::JSValue,
+) -> q::JSValue {
+    log::trace!("> clear_immediate");
+
+    let args = parse_args(context, argc, argv);
+
+    QuickJsRuntimeAdapter::do_with(move |q_js_rt| {
+        let q_ctx = q_js_rt.get_quickjs_context(context);
+        if args.is_empty() {
+            return q_ctx.report_ex("clearImmediate requires at least one argument");
+        }
+        if !&args[0].is_i32() {
+            return q_ctx.report_ex("clearImmediate requires a number as first arg");
+        }
+        let id = primitives::to_i32(&args[0]).ok().unwrap();
+        log::trace!("clear_immediate: {}", id);
+
+        PENDING_IMMEDIATES.with(|rc| rc.borrow_mut().remove(&id));
+
         quickjs_utils::new_null()
     })
 }
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use std::time::Duration;
+
+    #[test]
+    #[cfg(feature = "settimeout")]
+    fn test_set_immediate_order() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.eval_sync(
+            None,
+            Script::new(
+                "test_set_immediate_order.js",
+                r#"
+            globalThis.order = [];
+            setImmediate(() => order.push('immediate'));
+            setTimeout(() => order.push('timeout'), 0);
+            Promise.resolve().then(() => order.push('microtask'));
+            let clearedId = setImmediate(() => order.push('cleared'));
+            clearImmediate(clearedId);
+        "#,
+            ),
+        )
+        .expect("script failed");
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new("test_set_immediate_order2.js", "order.join(',');"),
+            )
+            .expect("script failed");
+        assert_eq!(res.get_str(), "microtask,immediate,timeout");
+    }
+}
@@ -0,0 +1,211 @@
+//! provides the TextEncoder and TextDecoder classes for the utf-8 encoding
+//!
+//! this requires the optional "textencoding" feature, enable it in your Cargo.toml like
+//! ```toml
+//! quickjs_runtime = { version = "0.10", features = ["textencoding"] }
+//! ```
+//!
+//! TextEncoder.encode returns a Uint8Array, TextDecoder.decode accepts an ArrayBuffer or TypedArray
+//! a TextDecoder which is created with `{fatal: true}` throws a TypeError for invalid utf-8 sequences instead of replacing them with U+FFFD
+//! # Example
+//! ```rust
+//! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+//! use quickjs_runtime::jsutils::Script;
+//! let rt = QuickJsRuntimeBuilder::new().build();
+//! let res = rt.eval_sync(None, Script::new("text_encoding.js", r#"
+//!     const bytes = new TextEncoder().encode('héllo');
+//!     bytes.length + ':' + new TextDecoder().decode(bytes);
+//! "#)).expect("script failed");
+//! assert_eq!(res.get_str(), "6:héllo");
+//! ```
+
+use crate::jsutils::JsError;
+use crate::quickjs_utils::typedarrays::{
+    get_array_buffer_buffer_copy_q, get_array_buffer_q, get_typed_array_info_q, is_array_buffer_q,
+};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use crate::reflection::Proxy;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// the labels of the utf-8 encoding, see <https://encoding.spec.whatwg.org/#names-and-labels>
+const UTF8_LABELS: &[&str] = &[
+    "unicode-1-1-utf-8",
+    "unicode11utf8",
+    "unicode20utf8",
+    "utf-8",
+    "utf8",
+    "x-unicode20utf8",
+];
+
+struct DecoderOptions {
+    fatal: bool,
+    ignore_bom: bool,
+}
+
+thread_local! {
+    static DECODERS: RefCell<HashMap<usize, DecoderOptions>> = RefCell::new(HashMap::new());
+}
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("text_encoding::init");
+
+    q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_realm(q_ctx))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    Proxy::new()
+        .name("TextEncoder")
+        .constructor(|_rt, _realm, _id, _args| Ok(()))
+        .getter("encoding", |_rt, realm, _id| realm.create_string("utf-8"))
+        .method("encode", |_rt, realm, _id, args| {
+            let text = match args.first() {
+                Some(arg) if !arg.is_undefined() => arg.to_string()?,
+                _ => "".to_string(),
+            };
+            realm.create_typed_array_uint8(text.into_bytes())
+        })
+        .install(realm, true)?;
+
+    Proxy::new()
+        .name("TextDecoder")
+        .constructor(|_rt, realm, id, args| {
+            let label = match args.first() {
+                Some(arg) if !arg.is_undefined() => arg.to_string()?,
+                _ => "utf-8".to_string(),
+            };
+            if !UTF8_LABELS.contains(&label.trim().to_ascii_lowercase().as_str()) {
+                return Err(JsError::new(
+                    "RangeError".to_string(),
+                    format!("The encoding label provided ('{label}') is not supported"),
+                    "".to_string(),
+                ));
+            }
+            let options = match args.get(1) {
+                Some(arg) if arg.is_object() => DecoderOptions {
+                    fatal: is_option_set(realm, arg, "fatal")?,
+                    ignore_bom: is_option_set(realm, arg, "ignoreBOM")?,
+                },
+                _ => DecoderOptions {
+                    fatal: false,
+                    ignore_bom: false,
+                },
+            };
+            DECODERS.with(|rc| rc.borrow_mut().insert(id, options));
+            Ok(())
+        })
+        .getter("encoding", |_rt, realm, _id| realm.create_string("utf-8"))
+        .getter("fatal", |_rt, realm, id| {
+            let fatal = DECODERS.with(|rc| rc.borrow().get(id).map_or(false, |o| o.fatal));
+            realm.create_boolean(fatal)
+        })
+        .getter("ignoreBOM", |_rt, realm, id| {
+            let ignore_bom =
+                DECODERS.with(|rc| rc.borrow().get(id).map_or(false, |o| o.ignore_bom));
+            realm.create_boolean(ignore_bom)
+        })
+        .method("decode", |_rt, realm, id, args| {
+            let bytes = match args.first() {
+                Some(arg) if !arg.is_null_or_undefined() => get_bytes(realm, arg)?,
+                _ => vec![],
+            };
+            let (fatal, ignore_bom) = DECODERS.with(|rc| {
+                rc.borrow()
+                    .get(id)
+                    .map_or((false, false), |o| (o.fatal, o.ignore_bom))
+            });
+            let mut bytes = bytes.as_slice();
+            if !ignore_bom && bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+                bytes = &bytes[3..];
+            }
+            if fatal {
+                let text = std::str::from_utf8(bytes).map_err(|_| {
+                    JsError::new(
+                        "TypeError".to_string(),
+                        "The encoded data was not valid for encoding utf-8".to_string(),
+                        "".to_string(),
+                    )
+                })?;
+                realm.create_string(text)
+            } else {
+                realm.create_string(String::from_utf8_lossy(bytes).as_ref())
+            }
+        })
+        .finalizer(|_rt, _realm, id| {
+            DECODERS.with(|rc| rc.borrow_mut().remove(&id));
+        })
+        .install(realm, true)?;
+    Ok(())
+}
+
+fn is_option_set(
+    realm: &QuickJsRealmAdapter,
+    options: &QuickJsValueAdapter,
+    name: &str,
+) -> Result<bool, JsError> {
+    let value = realm.get_object_property(options, name)?;
+    Ok(value.is_bool() && value.to_bool())
+}
+
+/// copy the bytes of an ArrayBuffer or of the part of a buffer which is viewed by a TypedArray
+fn get_bytes(realm: &QuickJsRealmAdapter, input: &QuickJsValueAdapter) -> Result<Vec<u8>, JsError> {
+    if is_array_buffer_q(realm, input) {
+        return get_array_buffer_buffer_copy_q(realm, input);
+    }
+    if let Some(info) = get_typed_array_info_q(realm, input)? {
+        let buffer = get_array_buffer_q(realm, input)?;
+        let bytes = get_array_buffer_buffer_copy_q(realm, &buffer)?;
+        return Ok(bytes[info.byte_offset..info.byte_offset + info.byte_length].to_vec());
+    }
+    Err(JsError::new(
+        "TypeError".to_string(),
+        "decode requires an ArrayBuffer or a TypedArray".to_string(),
+        "".to_string(),
+    ))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::facades::tests::init_test_rt;
+    use crate::jsutils::Script;
+
+    #[test]
+    fn test_text_encoding() {
+        let rt = init_test_rt();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_text_encoding.js",
+                    r#"
+            const encoded = new TextEncoder().encode('a€');
+            const invalid = new Uint8Array([0x61, 0xff, 0x62]);
+            let fatalError;
+            try {
+                new TextDecoder('utf-8', {fatal: true}).decode(invalid);
+            } catch (err) {
+                fatalError = err instanceof TypeError;
+            }
+            [
+                encoded instanceof Uint8Array,
+                Array.from(encoded).join(' '),
+                new TextDecoder('UTF8').decode(encoded.buffer),
+                new TextDecoder().decode(encoded.subarray(1)),
+                new TextDecoder().decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x62])),
+                new TextDecoder().decode(invalid),
+                new TextDecoder('utf-8', {fatal: true}).fatal,
+                fatalError
+            ].join(',');
+        "#,
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(
+            res.get_str(),
+            "true,97 226 130 172,a€,€,b,a\u{FFFD}b,true,true"
+        );
+    }
+}
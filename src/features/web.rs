@@ -0,0 +1,255 @@
+//! provides the web globals which are installed in every realm by QuickJsRuntimeBuilder::with_web_features (requires the web feature)
+//!
+//! * structuredClone(value, {transfer}) which copies a value like postMessage does, see [structured_clone](crate::quickjs_utils::structured_clone)
+//! * atob / btoa for base64 encoding of binary strings
+//! * performance.now() and performance.timeOrigin
+//!
+//! with_web_features only installs these globals, the other web APIs of this crate (like console, setTimeout or EventTarget)
+//! are separate cargo features and are only present when those features are enabled
+//!
+//! URL, crypto and fetch are not provided by this crate
+//! # Example
+//! ```rust
+//! use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+//! use quickjs_runtime::jsutils::Script;
+//! let rt = QuickJsRuntimeBuilder::new().with_web_features().build();
+//! let res = rt.eval_sync(None, Script::new("web.js", r#"
+//!     const copy = structuredClone({a: [1, 2]});
+//!     copy.a.length + ':' + btoa('hello');
+//! "#)).expect("script failed");
+//! assert_eq!(res.get_str(), "2:aGVsbG8=");
+//! ```
+
+use crate::jsutils::JsError;
+use crate::quickjs_utils::arrays;
+use crate::quickjs_utils::structured_clone::structured_clone_q;
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use std::convert::TryFrom;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn init(q_js_rt: &QuickJsRuntimeAdapter) -> Result<(), JsError> {
+    log::trace!("web::init");
+
+    q_js_rt.add_context_init_hook(|_q_js_rt, q_ctx| init_realm(q_ctx))?;
+    Ok(())
+}
+
+fn init_realm(realm: &QuickJsRealmAdapter) -> Result<(), JsError> {
+    let global = realm.get_global()?;
+
+    let structured_clone_func = realm.create_function(
+        "structuredClone",
+        |realm, _this, args| {
+            let value = match args.first() {
+                Some(value) => value.clone(),
+                None => realm.create_undefined()?,
+            };
+            let transfer = match args.get(1) {
+                Some(options) if options.is_object() => {
+                    get_transfer_list(realm, &realm.get_object_property(options, "transfer")?)?
+                }
+                _ => vec![],
+            };
+            structured_clone_q(realm, realm, &value, &transfer)
+        },
+        1,
+    )?;
+    realm.set_object_property(&global, "structuredClone", &structured_clone_func)?;
+
+    let btoa_func = realm.create_function(
+        "btoa",
+        |realm, _this, args| {
+            let data = string_arg(args, "btoa")?;
+            realm.create_string(base64_encode(&latin1_bytes(data.as_str())?).as_str())
+        },
+        1,
+    )?;
+    realm.set_object_property(&global, "btoa", &btoa_func)?;
+
+    let atob_func = realm.create_function(
+        "atob",
+        |realm, _this, args| {
+            let data = string_arg(args, "atob")?;
+            let bytes = base64_decode(data.as_str())?;
+            let binary: String = bytes.into_iter().map(char::from).collect();
+            realm.create_string(binary.as_str())
+        },
+        1,
+    )?;
+    realm.set_object_property(&global, "atob", &atob_func)?;
+
+    let started = Instant::now();
+    let time_origin = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0);
+    let performance = realm.create_object()?;
+    let now_func = realm.create_function(
+        "now",
+        move |realm, _this, _args| realm.create_f64(started.elapsed().as_secs_f64() * 1000.0),
+        0,
+    )?;
+    realm.set_object_property(&performance, "now", &now_func)?;
+    realm.set_object_property(&performance, "timeOrigin", &realm.create_f64(time_origin)?)?;
+    realm.set_object_property(&global, "performance", &performance)?;
+
+    Ok(())
+}
+
+fn get_transfer_list(
+    realm: &QuickJsRealmAdapter,
+    transfer: &QuickJsValueAdapter,
+) -> Result<Vec<QuickJsValueAdapter>, JsError> {
+    if transfer.is_undefined() {
+        return Ok(vec![]);
+    }
+    if !arrays::is_array_q(realm, transfer) {
+        return Err(JsError::new_str("transfer should be an Array"));
+    }
+    let len = arrays::get_length_q(realm, transfer)?;
+    (0..len)
+        .map(|index| arrays::get_element_q(realm, transfer, index))
+        .collect()
+}
+
+fn string_arg(args: &[QuickJsValueAdapter], func_name: &str) -> Result<String, JsError> {
+    match args.first() {
+        Some(arg) => arg.to_string(),
+        None => Err(JsError::new(
+            "TypeError".to_string(),
+            format!("{func_name} requires 1 argument"),
+            "".to_string(),
+        )),
+    }
+}
+
+fn invalid_character_error(message: &str) -> JsError {
+    JsError::new(
+        "InvalidCharacterError".to_string(),
+        message.to_string(),
+        "".to_string(),
+    )
+}
+
+/// the bytes of a binary string, every char should be in the latin1 range
+fn latin1_bytes(data: &str) -> Result<Vec<u8>, JsError> {
+    data.chars()
+        .map(|c| {
+            u8::try_from(u32::from(c)).map_err(|_| {
+                invalid_character_error("string contains characters outside of the Latin1 range")
+            })
+        })
+        .collect()
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_CHARS[((n >> (18 - i * 6)) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// decode base64 like atob does, ascii whitespace is ignored and the padding is optional
+fn base64_decode(data: &str) -> Result<Vec<u8>, JsError> {
+    let mut chars: Vec<u8> = data
+        .bytes()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\x0C' | b'\r'))
+        .collect();
+    match chars.len() % 4 {
+        0 if chars.ends_with(b"==") => chars.truncate(chars.len() - 2),
+        0 if chars.ends_with(b"=") => chars.truncate(chars.len() - 1),
+        1 => return Err(invalid_character_error("string is not correctly encoded")),
+        _ => {}
+    }
+    let mut bytes = Vec::with_capacity(chars.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in chars {
+        let value = BASE64_CHARS
+            .iter()
+            .position(|b| *b == c)
+            .ok_or_else(|| invalid_character_error("string is not correctly encoded"))?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+
+    #[test]
+    fn test_web_features() {
+        let rt = QuickJsRuntimeBuilder::new().with_web_features().build();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_web_features.js",
+                    r#"
+            const original = {d: new Date(0), list: [1, 2]};
+            original.self = original;
+            const copy = structuredClone(original);
+            const buffer = new Uint8Array([1, 2, 3]).buffer;
+            const moved = structuredClone(buffer, {transfer: [buffer]});
+            let invalid;
+            try {
+                btoa('€');
+            } catch (err) {
+                invalid = err.name;
+            }
+            [
+                copy !== original && copy.self === copy && copy.d.getTime() === 0 && copy.list.join('|'),
+                moved.byteLength + '/' + buffer.byteLength,
+                btoa('hello'), btoa('hi'), btoa(''), atob('aGk='), atob(' aGVs bG8 '), invalid,
+                typeof performance.now(), performance.timeOrigin > 0
+            ].join(',');
+        "#,
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(
+            res.get_str(),
+            "1|2,3/0,aGVsbG8=,aGk=,,hi,hello,InvalidCharacterError,number,true"
+        );
+    }
+
+    #[test]
+    fn test_no_web_features() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_no_web_features.js",
+                    "[typeof structuredClone, typeof atob, typeof performance].join(',');",
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(res.get_str(), "undefined,undefined,undefined");
+    }
+}
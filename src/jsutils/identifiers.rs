@@ -0,0 +1,817 @@
+//! a best-effort static analysis of the identifiers used in a script
+//!
+//! this does not build a full syntax tree, it tokenizes the script and uses some heuristics to find the scopes of blocks and functions
+//! and to tell declared names from references, unusual syntax may still be misread
+
+use std::collections::{BTreeSet, HashSet};
+
+const KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "Infinity",
+    "instanceof",
+    "let",
+    "NaN",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+// keywords after which a slash starts a RegExp instead of a division
+const REGEXP_PRECEDING_KEYWORDS: &[&str] = &[
+    "case",
+    "delete",
+    "do",
+    "else",
+    "in",
+    "instanceof",
+    "new",
+    "of",
+    "return",
+    "throw",
+    "typeof",
+    "void",
+    "yield",
+    "await",
+];
+
+#[derive(PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Punct(String),
+    Literal,
+}
+
+impl Token {
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self, Token::Punct(p) if p == punct)
+    }
+}
+
+/// a scope in which names are declared, it spans the tokens after start up to end
+/// e.g. the block between two braces or a function from its parameter list up to the end of its body
+struct Scope<'a> {
+    start: usize,
+    end: usize,
+    // var declarations are scoped to the nearest function scope instead of the nearest block
+    function: bool,
+    names: HashSet<&'a str>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(start: usize, end: usize, function: bool) -> Self {
+        Self {
+            start,
+            end,
+            function,
+            names: HashSet::new(),
+        }
+    }
+    fn contains(&self, index: usize) -> bool {
+        self.start < index && index < self.end
+    }
+}
+
+/// get the names of all identifiers which are referenced but not declared in a script, sorted alphabetically
+/// these are the globals a script depends on, keywords and literals like `undefined` and `NaN` are never included
+/// `this` is included when it is used as it may refer to the global object
+///
+/// names are resolved against the scopes they are used in, a parameter or a let, const or class declared in a block only
+/// hides a global inside that function or block, var declarations are scoped to the function they are declared in
+/// # Example
+/// ```rust
+/// use quickjs_runtime::jsutils::identifiers::free_identifiers;
+/// let free = free_identifiers("let a = Math.max(1, 2); console.log(a, b);");
+/// assert_eq!(free, vec!["Math", "b", "console"]);
+/// let free = free_identifiers("function f(fetch) { return fetch; } fetch('x');");
+/// assert_eq!(free, vec!["fetch"]);
+/// ```
+pub fn free_identifiers(code: &str) -> Vec<String> {
+    let tokens = tokenize(code);
+    let mut top: HashSet<&str> = HashSet::new();
+    let mut scopes: Vec<Scope> = vec![];
+    // identifiers which are the name of a declaration instead of a reference
+    let mut declaration_sites: HashSet<usize> = HashSet::new();
+
+    // first find the scopes and declare the parameters in them
+    let mut brace_stack: Vec<usize> = vec![];
+    let mut object_braces: HashSet<usize> = HashSet::new();
+    let mut class_bodies: HashSet<usize> = HashSet::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let prev = if i > 0 { tokens.get(i - 1) } else { None };
+        let next = tokens.get(i + 1);
+        match token {
+            Token::Punct(p) if p == "{" => {
+                let end = find_close(&tokens, i).unwrap_or(tokens.len());
+                scopes.push(Scope::new(i, end, false));
+                if starts_object_literal(prev) {
+                    object_braces.insert(i);
+                }
+                brace_stack.push(i);
+            }
+            Token::Punct(p) if p == "}" => {
+                brace_stack.pop();
+            }
+            Token::Punct(p) if p == ")" && matches!(next, Some(t) if t.is_punct("=>")) => {
+                // parameters of an arrow function
+                if let Some(open) = find_open(&tokens, i) {
+                    let mut scope = Scope::new(open, arrow_body_end(&tokens, i + 1), true);
+                    scope.names.extend(param_names(&tokens, open));
+                    scopes.push(scope);
+                }
+            }
+            Token::Ident(_) if matches!(prev, Some(t) if t.is_punct(".")) => {
+                // property access, e.g. promise.catch(...)
+            }
+            Token::Ident(name) if matches!(next, Some(t) if t.is_punct("=>")) => {
+                // single parameter of an arrow function
+                let mut scope = Scope::new(i, arrow_body_end(&tokens, i + 1), true);
+                scope.names.insert(name.as_str());
+                scopes.push(scope);
+                declaration_sites.insert(i);
+            }
+            Token::Ident(name) if name == "function" => {
+                let mut j = i + 1;
+                if matches!(tokens.get(j), Some(t) if t.is_punct("*")) {
+                    j += 1;
+                }
+                let mut expression_name = None;
+                if let Some(Token::Ident(function_name)) = tokens.get(j) {
+                    declaration_sites.insert(j);
+                    if !starts_statement(&tokens, i) {
+                        // the name of a function expression is only visible in the function itself
+                        expression_name = Some(function_name.as_str());
+                    }
+                    j += 1;
+                }
+                if matches!(tokens.get(j), Some(t) if t.is_punct("(")) {
+                    let mut scope = function_scope(&tokens, j);
+                    scope.names.extend(expression_name);
+                    scopes.push(scope);
+                }
+            }
+            Token::Ident(name) if name == "class" => {
+                let mut j = i + 1;
+                if let Some(Token::Ident(class_name)) = tokens.get(j) {
+                    if class_name != "extends" {
+                        declaration_sites.insert(j);
+                        j += 1;
+                    }
+                }
+                if let Some(body) = find_at_depth(&tokens, j, "{") {
+                    class_bodies.insert(body);
+                }
+            }
+            Token::Ident(name)
+                if (name == "catch" || name == "for")
+                    && matches!(next, Some(t) if t.is_punct("(")) =>
+            {
+                let close = find_close(&tokens, i + 1).unwrap_or(tokens.len());
+                let end = if matches!(tokens.get(close + 1), Some(t) if t.is_punct("{")) {
+                    find_close(&tokens, close + 1).unwrap_or(tokens.len())
+                } else {
+                    find_at_depth(&tokens, close + 1, ";").unwrap_or(tokens.len())
+                };
+                let mut scope = Scope::new(i + 1, end, false);
+                if name == "catch" {
+                    scope.names.extend(param_names(&tokens, i + 1));
+                }
+                scopes.push(scope);
+            }
+            Token::Ident(name)
+                if !KEYWORDS.contains(&name.as_str())
+                    && matches!(next, Some(t) if t.is_punct("("))
+                    && brace_stack
+                        .last()
+                        .is_some_and(|b| object_braces.contains(b) || class_bodies.contains(b)) =>
+            {
+                if let Some(close) = find_close(&tokens, i + 1) {
+                    if matches!(tokens.get(close + 1), Some(t) if t.is_punct("{")) {
+                        // method definition
+                        declaration_sites.insert(i);
+                        scopes.push(function_scope(&tokens, i + 1));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // then declare the variables, functions and classes and collect the references
+    let mut references: Vec<(&str, usize)> = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        let name = match token {
+            Token::Ident(name) => name.as_str(),
+            _ => continue,
+        };
+        if declaration_sites.contains(&i) {
+            continue;
+        }
+        let prev = if i > 0 { tokens.get(i - 1) } else { None };
+        let next = tokens.get(i + 1);
+
+        if matches!(prev, Some(t) if t.is_punct(".") || t.is_punct("#")) {
+            // property access or private member
+            continue;
+        }
+        if name == "this" {
+            references.push((name, i));
+            continue;
+        }
+        if is_contextual_keyword(&tokens, i) {
+            continue;
+        }
+        if KEYWORDS.contains(&name) {
+            match name {
+                "let" | "const" | "var" => {
+                    let scope = innermost(&scopes, i, name == "var");
+                    declare(&mut scopes, &mut top, scope, variable_names(&tokens, i + 1));
+                }
+                "function" | "class" if starts_statement(&tokens, i) => {
+                    let mut j = i + 1;
+                    if matches!(tokens.get(j), Some(t) if t.is_punct("*")) {
+                        j += 1;
+                    }
+                    if let Some(Token::Ident(declared_name)) = tokens.get(j) {
+                        if declared_name != "extends" {
+                            let scope = innermost(&scopes, i, false);
+                            declare(&mut scopes, &mut top, scope, vec![declared_name.as_str()]);
+                        }
+                    }
+                }
+                "class" => {
+                    // the name of a class expression is only visible in the class body
+                    if let Some(Token::Ident(declared_name)) = tokens.get(i + 1) {
+                        if let Some(body) = find_at_depth(&tokens, i + 2, "{") {
+                            if let Some(scope) = scopes.iter_mut().find(|s| s.start == body) {
+                                scope.names.insert(declared_name.as_str());
+                            }
+                        }
+                    }
+                }
+                "import" => {
+                    for t in tokens[i + 1..].iter() {
+                        match t {
+                            Token::Ident(n) if n == "from" => break,
+                            Token::Ident(n) if n == "as" => {}
+                            Token::Ident(n) => {
+                                top.insert(n.as_str());
+                            }
+                            Token::Literal => break,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if matches!(next, Some(t) if t.is_punct(":"))
+            && matches!(prev, None | Some(Token::Punct(_)))
+            && !matches!(prev, Some(t) if t.is_punct("?"))
+        {
+            // object key or label
+            continue;
+        }
+        references.push((name, i));
+    }
+
+    // a scope declares a name for the references inside it, the top level for all references
+    let free: BTreeSet<&str> = references
+        .into_iter()
+        .filter(|(name, i)| {
+            *name == "this"
+                || !(top.contains(name)
+                    || scopes
+                        .iter()
+                        .any(|scope| scope.contains(*i) && scope.names.contains(name)))
+        })
+        .map(|(name, _)| name)
+        .collect();
+    free.into_iter().map(|name| name.to_string()).collect()
+}
+
+fn declare<'a>(
+    scopes: &mut [Scope<'a>],
+    top: &mut HashSet<&'a str>,
+    scope: Option<usize>,
+    names: Vec<&'a str>,
+) {
+    match scope {
+        Some(index) => scopes[index].names.extend(names),
+        None => top.extend(names),
+    }
+}
+
+/// get the index of the smallest scope which contains the token at index, None for the top level
+fn innermost(scopes: &[Scope], index: usize, function: bool) -> Option<usize> {
+    scopes
+        .iter()
+        .enumerate()
+        .filter(|(_, scope)| scope.contains(index) && (scope.function || !function))
+        .min_by_key(|(_, scope)| scope.end - scope.start)
+        .map(|(i, _)| i)
+}
+
+/// the scope of a function or method from the parameter list at open up to the end of its body
+/// the parameters and `arguments` are declared in it
+fn function_scope(tokens: &[Token], open: usize) -> Scope<'_> {
+    let close = find_close(tokens, open).unwrap_or(tokens.len());
+    let end = if matches!(tokens.get(close + 1), Some(t) if t.is_punct("{")) {
+        find_close(tokens, close + 1).unwrap_or(tokens.len())
+    } else {
+        close
+    };
+    let mut scope = Scope::new(open, end, true);
+    scope.names.extend(param_names(tokens, open));
+    scope.names.insert("arguments");
+    scope
+}
+
+/// get the index of the token after the body of an arrow function, arrow is the index of the token after the `=>`
+fn arrow_body_end(tokens: &[Token], arrow: usize) -> usize {
+    if matches!(tokens.get(arrow + 1), Some(t) if t.is_punct("{")) {
+        return find_close(tokens, arrow + 1).unwrap_or(tokens.len());
+    }
+    // an expression body ends at the first comma, semicolon or unmatched closing bracket
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(arrow + 1) {
+        if let Token::Punct(p) = token {
+            match p.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    if depth == 0 {
+                        return j;
+                    }
+                    depth -= 1;
+                }
+                "," | ";" if depth == 0 => return j,
+                _ => {}
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// check if an opening brace after prev starts an object literal instead of a block
+fn starts_object_literal(prev: Option<&Token>) -> bool {
+    match prev {
+        Some(Token::Punct(p)) => !matches!(p.as_str(), ")" | "]" | "}" | ";" | "{" | "=>"),
+        Some(Token::Ident(name)) => matches!(
+            name.as_str(),
+            "return" | "yield" | "await" | "typeof" | "case" | "in" | "of" | "void" | "delete"
+        ),
+        _ => false,
+    }
+}
+
+/// check if the function or class keyword at index starts a declaration instead of an expression
+fn starts_statement(tokens: &[Token], mut index: usize) -> bool {
+    if index > 0 && matches!(&tokens[index - 1], Token::Ident(n) if n == "async") {
+        index -= 1;
+    }
+    if index == 0 {
+        return true;
+    }
+    match &tokens[index - 1] {
+        Token::Punct(p) => matches!(p.as_str(), ";" | "{" | "}"),
+        Token::Ident(n) => n == "export" || n == "default",
+        Token::Literal => false,
+    }
+}
+
+/// check if a name which is not a reserved word is used as a keyword, e.g. `of` in a for loop or `get` in a class
+fn is_contextual_keyword(tokens: &[Token], index: usize) -> bool {
+    let prev = if index > 0 {
+        tokens.get(index - 1)
+    } else {
+        None
+    };
+    let next = tokens.get(index + 1);
+    let is_name =
+        |t: Option<&Token>| matches!(t, Some(Token::Ident(n)) if !KEYWORDS.contains(&n.as_str()));
+    match &tokens[index] {
+        Token::Ident(name) => match name.as_str() {
+            "get" | "set" | "static" => {
+                is_name(next)
+                    || matches!(next, Some(t) if t.is_punct("[") || t.is_punct("*"))
+                    || (name == "static" && matches!(next, Some(t) if t.is_punct("{")))
+            }
+            "async" => {
+                is_name(next)
+                    || matches!(next, Some(Token::Ident(n)) if n == "function")
+                    || (matches!(next, Some(t) if t.is_punct("("))
+                        && find_close(tokens, index + 1).is_some_and(
+                            |close| matches!(tokens.get(close + 1), Some(t) if t.is_punct("=>")),
+                        ))
+            }
+            "of" => is_name(prev) || matches!(prev, Some(t) if t.is_punct("]") || t.is_punct("}")),
+            "from" => {
+                matches!(next, Some(Token::Literal))
+                    && (is_name(prev)
+                        || matches!(prev, Some(t) if t.is_punct("}") || t.is_punct("*")))
+            }
+            "as" => (is_name(prev) || matches!(prev, Some(t) if t.is_punct("*"))) && is_name(next),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// get the names declared by a let, const or var statement, start is the index of the token after the keyword
+fn variable_names(tokens: &[Token], start: usize) -> Vec<&str> {
+    let mut names = vec![];
+    let mut depth = 0;
+    let mut expecting_name = true;
+    for (j, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Punct(p) => match p.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    if depth == 0 {
+                        return names;
+                    }
+                    depth -= 1;
+                }
+                ";" if depth == 0 => return names,
+                "," if depth == 0 => expecting_name = true,
+                "=" if depth == 0 => expecting_name = false,
+                _ => {}
+            },
+            Token::Ident(name) => {
+                if depth == 0 {
+                    if name == "in" || name == "of" {
+                        return names;
+                    }
+                    if expecting_name {
+                        names.push(name.as_str());
+                        expecting_name = false;
+                    } else if !matches!(tokens.get(j - 1), Some(Token::Punct(_))) {
+                        // a new statement without a semicolon
+                        return names;
+                    }
+                } else if expecting_name && !matches!(tokens.get(j + 1), Some(t) if t.is_punct(":"))
+                {
+                    // destructuring pattern
+                    names.push(name.as_str());
+                }
+            }
+            Token::Literal => {}
+        }
+    }
+    names
+}
+
+/// get the parameter names between the parentheses starting at open
+fn param_names(tokens: &[Token], open: usize) -> Vec<&str> {
+    let close = find_close(tokens, open).unwrap_or(tokens.len());
+    let mut names = vec![];
+    for j in open + 1..close {
+        if let Token::Ident(name) = &tokens[j] {
+            let prev = &tokens[j - 1];
+            if ["(", ",", "...", "{", "[", ":"]
+                .iter()
+                .any(|p| prev.is_punct(p))
+            {
+                names.push(name.as_str());
+            }
+        }
+    }
+    names
+}
+
+/// find the first punctuator from start which is not nested in brackets
+fn find_at_depth(tokens: &[Token], start: usize, punct: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(start) {
+        if let Token::Punct(p) = token {
+            if depth == 0 && p == punct {
+                return Some(j);
+            }
+            match p.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    if depth == 0 {
+                        return None;
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// find the index of the matching closing bracket for the opening bracket at open
+fn find_close(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(open) {
+        if let Token::Punct(p) = token {
+            match p.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(j);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// find the index of the matching opening bracket for the closing bracket at close
+fn find_open(tokens: &[Token], close: usize) -> Option<usize> {
+    let mut depth = 0;
+    for j in (0..=close).rev() {
+        if let Token::Punct(p) = &tokens[j] {
+            match p.as_str() {
+                ")" | "]" | "}" => depth += 1,
+                "(" | "[" | "{" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(j);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn tokenize(code: &str) -> Vec<Token> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = vec![];
+    // the brace depth at which each currently open template substitution started
+    let mut template_stack: Vec<usize> = vec![];
+    let mut brace_depth = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '\'' || c == '"' {
+            i = skip_string(&chars, i + 1, c);
+            tokens.push(Token::Literal);
+        } else if c == '`' {
+            i = skip_template(&chars, i + 1, &mut template_stack, &mut brace_depth);
+            tokens.push(Token::Literal);
+        } else if c == '}' && brace_depth > 0 && template_stack.last() == Some(&(brace_depth - 1)) {
+            // end of a template substitution, continue with the rest of the template
+            template_stack.pop();
+            brace_depth -= 1;
+            i = skip_template(&chars, i + 1, &mut template_stack, &mut brace_depth);
+            tokens.push(Token::Literal);
+        } else if is_ident_start(c) {
+            let start = i;
+            while i < chars.len() && is_ident_part(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            while i < chars.len() && (is_ident_part(chars[i]) || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Literal);
+        } else if c == '/' && regexp_allowed(tokens.last()) {
+            i = skip_regexp(&chars, i + 1);
+            tokens.push(Token::Literal);
+        } else if c == '?'
+            && next == Some('.')
+            && !chars.get(i + 2).is_some_and(|n| n.is_ascii_digit())
+        {
+            // optional chaining is treated like a regular property access
+            tokens.push(Token::Punct(".".to_string()));
+            i += 2;
+        } else if c == '.' && next == Some('.') && chars.get(i + 2) == Some(&'.') {
+            tokens.push(Token::Punct("...".to_string()));
+            i += 3;
+        } else if c == '=' && next == Some('>') {
+            tokens.push(Token::Punct("=>".to_string()));
+            i += 2;
+        } else {
+            if c == '{' {
+                brace_depth += 1;
+            } else if c == '}' && brace_depth > 0 {
+                brace_depth -= 1;
+            }
+            tokens.push(Token::Punct(c.to_string()));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn regexp_allowed(prev: Option<&Token>) -> bool {
+    match prev {
+        None => true,
+        Some(Token::Punct(p)) => !matches!(p.as_str(), ")" | "]" | "}"),
+        Some(Token::Ident(name)) => REGEXP_PRECEDING_KEYWORDS.contains(&name.as_str()),
+        Some(Token::Literal) => false,
+    }
+}
+
+/// skip a string literal, returns the index after the closing quote
+fn skip_string(chars: &[char], mut i: usize, quote: char) -> usize {
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// skip a template literal up to its end or up to the start of a substitution
+fn skip_template(
+    chars: &[char],
+    mut i: usize,
+    template_stack: &mut Vec<usize>,
+    brace_depth: &mut usize,
+) -> usize {
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '`' => return i + 1,
+            '$' if chars.get(i + 1) == Some(&'{') => {
+                template_stack.push(*brace_depth);
+                *brace_depth += 1;
+                return i + 2;
+            }
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// skip a RegExp literal including its flags
+fn skip_regexp(chars: &[char], mut i: usize) -> usize {
+    let mut in_class = false;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '[' => {
+                in_class = true;
+                i += 1;
+            }
+            ']' => {
+                in_class = false;
+                i += 1;
+            }
+            '/' if !in_class => {
+                i += 1;
+                while i < chars.len() && is_ident_part(chars[i]) {
+                    i += 1;
+                }
+                return i;
+            }
+            '\n' => return i,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::jsutils::identifiers::free_identifiers;
+
+    #[test]
+    fn test_free_identifiers() {
+        let free = free_identifiers(
+            r#"
+            // a comment mentioning commentGlobal
+            import {helper as h} from 'helpers.mjs';
+            const {a, b: renamed} = source, [c] = list;
+            let counter = 0
+            class Greeter extends Base {
+                greet(name, ...rest) {
+                    return `hello ${name + suffix({x: 1})}` + /reGlobal/g.source + h(rest);
+                }
+                get value() { return this.v?.w; }
+            }
+            function outer(p = 1) {
+                try { counter++; } catch (e) { report(e, 'stringGlobal'); }
+                return arr.map((item, idx) => item + idx).filter(i => i > p);
+            }
+            "#,
+        );
+        assert_eq!(
+            free,
+            vec!["Base", "arr", "list", "report", "source", "suffix", "this"]
+        );
+    }
+
+    #[test]
+    fn test_free_identifiers_scopes() {
+        // parameters and block scoped declarations only hide a global inside their function or block
+        let free = free_identifiers(
+            r#"
+            function f(fetch) { return fetch; }
+            fetch('x');
+            { let inner = 1; inner++; }
+            inner;
+            for (let i = 0; i < 2; i++) { i; }
+            i;
+            try { load(); } catch (err) { err; }
+            err;
+            function g() { if (x) { var v = 1; } return v; }
+            v;
+            const h = function named() { return named; };
+            named;
+            promise.catch(reason => reason);
+            reason;
+            "#,
+        );
+        assert_eq!(
+            free,
+            vec!["err", "fetch", "i", "inner", "load", "named", "promise", "reason", "v", "x"]
+        );
+    }
+
+    #[test]
+    fn test_free_identifiers_contextual_keywords() {
+        let free = free_identifiers(
+            r#"
+            get(1); set(2); from(3); of(4); as(5); async(6); arguments.length;
+            (function() { return arguments.length; })();
+            for (const item of list) { item; }
+            const o = { get value() { return 1; }, async load() {} };
+            "#,
+        );
+        assert_eq!(
+            free,
+            vec![
+                "arguments",
+                "as",
+                "async",
+                "from",
+                "get",
+                "list",
+                "of",
+                "set"
+            ]
+        );
+        assert_eq!(free_identifiers("this['fe' + 'tch']('x');"), vec!["this"]);
+        assert_eq!(
+            free_identifiers("globalThis.fetch('x');"),
+            vec!["globalThis"]
+        );
+    }
+}
@@ -6,9 +6,11 @@
 use std::fmt::{Debug, Display, Error, Formatter};
 
 pub mod helper_tasks;
+pub mod identifiers;
 pub mod jsproxies;
 pub mod modules;
 pub mod promises;
+pub mod streams;
 
 pub trait ScriptPreProcessor {
     fn process(&self, script: &mut Script) -> Result<(), JsError>;
@@ -57,6 +59,11 @@ pub struct JsError {
     name: String,
     message: String,
     stack: String,
+    cause: Option<Box<JsError>>,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
 }
 
 impl JsError {
@@ -65,6 +72,30 @@ impl JsError {
             name,
             message,
             stack,
+            cause: None,
+            source: None,
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+    /// create an error which wraps the rust error which caused it, e.g. an io or serde error in a native function
+    /// the rust error is returned by std::error::Error::source (if no cause was set with with_cause)
+    pub fn with_source<E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+        name: String,
+        message: String,
+        stack: String,
+        source: E,
+    ) -> Self {
+        Self {
+            name,
+            message,
+            stack,
+            cause: None,
+            source: Some(source.into()),
+            file: None,
+            line: None,
+            column: None,
         }
     }
     pub fn new_str(err: &str) -> Self {
@@ -75,7 +106,37 @@ impl JsError {
             name: "Error".to_string(),
             message: err,
             stack: "".to_string(),
+            cause: None,
+            source: None,
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+    /// set the error which caused this error
+    pub fn with_cause(mut self, cause: JsError) -> Self {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+    /// prepend context to the message, e.g. when a native function rethrows the error of a callback
+    /// the name, stack, cause and location are kept so the error still points at the script which threw it
+    pub fn with_context<C: Display>(mut self, context: C) -> Self {
+        self.message = format!("{context} {}", self.message);
+        self
+    }
+    /// get the error which caused this error, this is filled from the cause property when converting a script Error
+    pub fn cause(&self) -> Option<&JsError> {
+        self.cause.as_deref()
+    }
+    /// format this error and all its causes, each cause is printed in a "Caused by:" section
+    pub fn format_with_causes(&self) -> String {
+        let mut ret = format!("{self}");
+        let mut cause_opt = self.cause();
+        while let Some(cause) = cause_opt {
+            ret.push_str(format!("\nCaused by:\n{cause}").as_str());
+            cause_opt = cause.cause();
         }
+        ret
     }
     pub fn get_message(&self) -> &str {
         self.message.as_str()
@@ -86,12 +147,40 @@ impl JsError {
     pub fn get_name(&self) -> &str {
         self.name.as_str()
     }
+    /// set the location in the script where this error occurred
+    pub fn with_location(mut self, file: &str, line: u32, column: Option<u32>) -> Self {
+        self.file = Some(file.to_string());
+        self.line = Some(line);
+        self.column = column;
+        self
+    }
+    /// get the name of the script where this error occurred, this is parsed from the exception when a script fails
+    pub fn get_file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+    /// get the line in the script where this error occurred, this is parsed from the exception when a script fails
+    pub fn get_line(&self) -> Option<u32> {
+        self.line
+    }
+    /// get the column in the script where this error occurred, QuickJS versions which do not track columns leave this None
+    pub fn get_column(&self) -> Option<u32> {
+        self.column
+    }
 }
 
 impl std::error::Error for JsError {
     fn description(&self) -> &str {
         self.get_message()
     }
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.cause.as_deref() {
+            Some(cause) => Some(cause as &(dyn std::error::Error + 'static)),
+            None => self
+                .source
+                .as_deref()
+                .map(|source| source as &(dyn std::error::Error + 'static)),
+        }
+    }
 }
 
 impl std::fmt::Display for JsError {
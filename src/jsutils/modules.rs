@@ -30,4 +30,12 @@ pub trait NativeModuleLoader {
         realm: &QuickJsRealmAdapter,
         module_name: &str,
     ) -> Vec<(&str, QuickJsValueAdapter)>;
+    /// names of exports which should be frozen (Object.freeze) before they are exported, e.g. for exporting constants
+    fn get_frozen_export_names(
+        &self,
+        _realm: &QuickJsRealmAdapter,
+        _module_name: &str,
+    ) -> Vec<&str> {
+        vec![]
+    }
 }
@@ -2,9 +2,26 @@ use crate::jsutils::helper_tasks::{add_helper_task, add_helper_task_async};
 use crate::jsutils::JsError;
 use crate::quickjs_utils::promises::QuickJsPromiseAdapter;
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use futures::Future;
 
+/// get the error to reject a new promise with when the runtime already has the maximum number of pending promises
+fn pending_promise_limit_error() -> Option<JsError> {
+    QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+        let max = q_js_rt.max_pending_promises?;
+        if q_js_rt.pending_promise_count() >= max {
+            Some(JsError::new(
+                "RangeError".to_string(),
+                format!("too many pending promises, the maximum is {max}"),
+                "".to_string(),
+            ))
+        } else {
+            None
+        }
+    })
+}
+
 #[allow(clippy::type_complexity)]
 /// create a new promise with a producer and a mapper
 /// the producer will run in a helper thread(in the tokio thread pool) and thus get a result asynchronously
@@ -20,6 +37,10 @@ where
     P: FnOnce() -> Result<R, JsError> + Send + 'static,
     M: FnOnce(&QuickJsRealmAdapter, R) -> Result<QuickJsValueAdapter, JsError> + Send + 'static,
 {
+    if let Some(err) = pending_promise_limit_error() {
+        return realm.new_rejected_promise(err);
+    }
+
     // create promise
     let promise_ref = realm.create_promise()?;
     let return_ref = promise_ref.js_promise_get_value(realm);
@@ -117,6 +138,10 @@ where
     P: Future<Output = Result<R, JsError>> + Send + 'static,
     M: FnOnce(&QuickJsRealmAdapter, R) -> Result<QuickJsValueAdapter, JsError> + Send + 'static,
 {
+    if let Some(err) = pending_promise_limit_error() {
+        return realm.new_rejected_promise(err);
+    }
+
     // create promise
     let promise_ref = realm.create_promise()?;
     let return_ref = promise_ref.js_promise_get_value(realm);
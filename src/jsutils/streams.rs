@@ -0,0 +1,158 @@
+//! utils for passing streamed data from rust to script
+
+use crate::jsutils::promises::new_resolving_promise_async;
+use crate::jsutils::JsError;
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use crate::values::JsValueFacade;
+use futures::lock::Mutex;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+
+type SharedStream = Arc<Mutex<Pin<Box<dyn Stream<Item = Result<JsValueFacade, JsError>> + Send>>>>;
+
+/// create a minimal ReadableStream object which reads its chunks from a rust Stream
+/// from script the chunks can be read by using getReader().read() which returns a Promise of {value, done}
+/// an Err item in the stream will reject the Promise returned by read()
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::values::JsValueFacade;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let realm = q_js_rt.get_main_realm();
+///     let chunks = futures::stream::iter(vec![Ok(JsValueFacade::new_str("chunk"))]);
+///     let stream = realm.create_readable_stream(chunks).expect("could not create stream");
+///     realm.set_object_property(&realm.get_global().unwrap(), "myStream", &stream).expect("could not set stream");
+/// });
+/// rt.eval_sync(None, Script::new("read_stream.js", "myStream.getReader().read().then((res) => console.log(res.value));")).expect("script failed");
+/// ```
+pub fn new_readable_stream<S>(
+    realm: &QuickJsRealmAdapter,
+    stream: S,
+) -> Result<QuickJsValueAdapter, JsError>
+where
+    S: Stream<Item = Result<JsValueFacade, JsError>> + Send + 'static,
+{
+    let shared: SharedStream = Arc::new(Mutex::new(Box::pin(stream)));
+
+    let stream_obj = realm.create_object()?;
+    let get_reader_func = realm.create_function(
+        "getReader",
+        move |realm, _this, _args| new_reader(realm, shared.clone()),
+        0,
+    )?;
+    realm.set_object_property(&stream_obj, "getReader", &get_reader_func)?;
+    Ok(stream_obj)
+}
+
+fn new_reader(
+    realm: &QuickJsRealmAdapter,
+    shared: SharedStream,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let reader_obj = realm.create_object()?;
+    let read_func = realm.create_function(
+        "read",
+        move |realm, _this, _args| {
+            let stream = shared.clone();
+            new_resolving_promise_async(
+                realm,
+                async move {
+                    let mut stream = stream.lock().await;
+                    stream.next().await.transpose()
+                },
+                |realm, chunk_opt| {
+                    let result = realm.create_object()?;
+                    match chunk_opt {
+                        Some(chunk) => {
+                            let value = realm.from_js_value_facade(chunk)?;
+                            realm.set_object_property(&result, "value", &value)?;
+                            realm.set_object_property(
+                                &result,
+                                "done",
+                                &realm.create_boolean(false)?,
+                            )?;
+                        }
+                        None => {
+                            realm.set_object_property(
+                                &result,
+                                "value",
+                                &realm.create_undefined()?,
+                            )?;
+                            realm.set_object_property(
+                                &result,
+                                "done",
+                                &realm.create_boolean(true)?,
+                            )?;
+                        }
+                    }
+                    Ok(result)
+                },
+            )
+        },
+        0,
+    )?;
+    realm.set_object_property(&reader_obj, "read", &read_func)?;
+    Ok(reader_obj)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use crate::values::JsValueFacade;
+
+    #[test]
+    fn test_readable_stream() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let chunks = futures::stream::iter(vec![
+                Ok(JsValueFacade::new_str("a")),
+                Ok(JsValueFacade::new_str("b")),
+                Ok(JsValueFacade::new_str("c")),
+            ]);
+            let stream = realm
+                .create_readable_stream(chunks)
+                .expect("could not create stream");
+            let global = realm.get_global().expect("could not get global");
+            realm
+                .set_object_property(&global, "testStream", &stream)
+                .expect("could not set stream");
+        });
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_readable_stream.js",
+                    r#"
+            (async () => {
+                let reader = testStream.getReader();
+                let chunks = [];
+                while (true) {
+                    let {value, done} = await reader.read();
+                    if (done) {
+                        break;
+                    }
+                    chunks.push(value);
+                }
+                return chunks.join(',');
+            })();
+        "#,
+                ),
+            )
+            .expect("script failed");
+        if let JsValueFacade::JsPromise { cached_promise } = res {
+            let chunks = cached_promise
+                .get_promise_result_sync()
+                .expect("promise timed out")
+                .expect("promise was rejected");
+            assert_eq!(chunks.get_str(), "a,b,c");
+        } else {
+            panic!("script did not return a promise");
+        }
+    }
+}
@@ -94,7 +94,12 @@ pub mod facades;
     feature = "settimeout",
     feature = "setinterval",
     feature = "console",
-    feature = "setimmediate"
+    feature = "setimmediate",
+    feature = "eventtarget",
+    feature = "require",
+    feature = "hostlog",
+    feature = "queuemicrotask",
+    feature = "textencoding"
 ))]
 pub mod features;
 pub mod jsutils;
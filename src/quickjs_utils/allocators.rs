@@ -0,0 +1,347 @@
+//! custom malloc functions for the quickjs runtime
+//!
+//! these are used to keep track of the number of bytes allocated by a runtime so a budget may be enforced per eval
+//! and to route all allocations of a runtime through a custom [QuickJsAllocator](QuickJsAllocator)
+
+use libquickjs_sys as q;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::os::raw::c_void;
+use std::ptr::null_mut;
+
+// every allocation is prefixed with a header which holds the requested size
+const HEADER_SIZE: usize = 16;
+const ALIGN: usize = 16;
+// after a budget was exceeded we allow a small amount of extra allocations so quickjs can create the error object
+const BUDGET_GRACE_BYTES: usize = 64 * 1024;
+
+thread_local! {
+    static EVAL_BUDGET: Cell<Option<usize>> = Cell::new(None);
+    static EVAL_ALLOCATED: Cell<usize> = Cell::new(0);
+    static EVAL_EXCEEDED: Cell<bool> = Cell::new(false);
+    static EVAL_ACTIVE: Cell<bool> = Cell::new(false);
+}
+
+static MALLOC_FUNCTIONS: q::JSMallocFunctions = q::JSMallocFunctions {
+    js_malloc: Some(js_malloc),
+    js_free: Some(js_free),
+    js_realloc: Some(js_realloc),
+    js_malloc_usable_size: Some(js_malloc_usable_size),
+};
+
+/// an allocator which is used for all allocations of a runtime, see QuickJsRuntimeBuilder::custom_allocator
+/// this may be used for accounting, arenas or limits
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::allocators::QuickJsAllocator;
+/// use std::alloc::{GlobalAlloc, Layout, System};
+///
+/// struct LoggingAllocator {}
+/// impl QuickJsAllocator for LoggingAllocator {
+///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+///         log::trace!("alloc {}", layout.size());
+///         System.alloc(layout)
+///     }
+///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+///         System.dealloc(ptr, layout)
+///     }
+/// }
+///
+/// let rt = QuickJsRuntimeBuilder::new().custom_allocator(LoggingAllocator{}).build();
+/// ```
+pub trait QuickJsAllocator {
+    /// allocate a block of memory, return a null pointer if the allocation should fail
+    /// # Safety
+    /// the same rules apply as for [GlobalAlloc::alloc](std::alloc::GlobalAlloc::alloc)
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+    /// free a block of memory which was allocated by this allocator
+    /// # Safety
+    /// the same rules apply as for [GlobalAlloc::dealloc](std::alloc::GlobalAlloc::dealloc)
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+    /// resize a block of memory, return a null pointer if the allocation should fail
+    /// the default implementation allocates a new block, copies the data and frees the old block
+    /// # Safety
+    /// the same rules apply as for [GlobalAlloc::realloc](std::alloc::GlobalAlloc::realloc)
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            std::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+pub(crate) type BoxedAllocator = Box<dyn QuickJsAllocator + Send>;
+
+/// create a new runtime which uses the tracking malloc functions of this module
+/// when no custom allocator is passed the system allocator is used
+/// # Safety
+/// the runtime should be freed with JS_FreeRuntime in the same thread it was created in
+/// the custom allocator should not be moved or dropped until the runtime was freed
+pub(crate) unsafe fn new_runtime(custom_allocator: Option<&BoxedAllocator>) -> *mut q::JSRuntime {
+    let opaque = match custom_allocator {
+        Some(allocator) => allocator as *const BoxedAllocator as *mut c_void,
+        None => null_mut(),
+    };
+    q::JS_NewRuntime2(&MALLOC_FUNCTIONS, opaque)
+}
+
+unsafe fn alloc(state: &q::JSMallocState, layout: Layout) -> *mut u8 {
+    if state.opaque.is_null() {
+        System.alloc(layout)
+    } else {
+        (*(state.opaque as *const BoxedAllocator)).alloc(layout)
+    }
+}
+
+unsafe fn dealloc(state: &q::JSMallocState, ptr: *mut u8, layout: Layout) {
+    if state.opaque.is_null() {
+        System.dealloc(ptr, layout)
+    } else {
+        (*(state.opaque as *const BoxedAllocator)).dealloc(ptr, layout)
+    }
+}
+
+unsafe fn realloc(
+    state: &q::JSMallocState,
+    ptr: *mut u8,
+    layout: Layout,
+    new_size: usize,
+) -> *mut u8 {
+    if state.opaque.is_null() {
+        System.realloc(ptr, layout, new_size)
+    } else {
+        (*(state.opaque as *const BoxedAllocator)).realloc(ptr, layout, new_size)
+    }
+}
+
+/// set the max number of bytes a single eval may allocate in the current thread
+pub(crate) fn set_eval_budget(budget: Option<usize>) {
+    EVAL_BUDGET.with(|rc| rc.set(budget));
+}
+
+/// guard which tracks allocations from creation until it is dropped
+/// nested guards (e.g. an eval from a native function called from script) are counted as part of the outermost eval
+pub(crate) struct EvalAllocationGuard {
+    outermost: bool,
+}
+
+impl EvalAllocationGuard {
+    pub(crate) fn start() -> Self {
+        let outermost = !EVAL_ACTIVE.with(|rc| rc.replace(true));
+        if outermost {
+            EVAL_ALLOCATED.with(|rc| rc.set(0));
+            EVAL_EXCEEDED.with(|rc| rc.set(false));
+        }
+        Self { outermost }
+    }
+}
+
+impl Drop for EvalAllocationGuard {
+    fn drop(&mut self) {
+        if self.outermost {
+            EVAL_ACTIVE.with(|rc| rc.set(false));
+        }
+    }
+}
+
+/// check and register an allocation against the eval budget, returns false if the allocation should fail
+fn register_allocation(size: usize) -> bool {
+    if !EVAL_ACTIVE.with(|rc| rc.get()) {
+        return true;
+    }
+    if let Some(budget) = EVAL_BUDGET.with(|rc| rc.get()) {
+        let allocated = EVAL_ALLOCATED.with(|rc| rc.get()) + size;
+        if EVAL_EXCEEDED.with(|rc| rc.get()) {
+            if allocated > budget + BUDGET_GRACE_BYTES {
+                return false;
+            }
+        } else if allocated > budget {
+            log::debug!("eval exceeded allocation budget of {} bytes", budget);
+            EVAL_EXCEEDED.with(|rc| rc.set(true));
+            return false;
+        }
+        EVAL_ALLOCATED.with(|rc| rc.set(allocated));
+    }
+    true
+}
+
+fn layout_for(size: usize) -> Layout {
+    Layout::from_size_align(size + HEADER_SIZE, ALIGN).expect("invalid layout")
+}
+
+unsafe fn header_ptr(ptr: *const c_void) -> *mut u8 {
+    (ptr as *mut u8).sub(HEADER_SIZE)
+}
+
+unsafe fn allocated_size(ptr: *const c_void) -> usize {
+    *(header_ptr(ptr) as *const usize)
+}
+
+unsafe extern "C" fn js_malloc(s: *mut q::JSMallocState, size: q::size_t) -> *mut c_void {
+    let state = &mut *s;
+    let size = size as usize;
+
+    if state.malloc_size as usize + size > state.malloc_limit as usize {
+        return null_mut();
+    }
+    if !register_allocation(size) {
+        return null_mut();
+    }
+
+    let raw = alloc(state, layout_for(size));
+    if raw.is_null() {
+        return null_mut();
+    }
+    *(raw as *mut usize) = size;
+
+    state.malloc_count += 1;
+    state.malloc_size += (size + HEADER_SIZE) as q::size_t;
+    raw.add(HEADER_SIZE) as *mut c_void
+}
+
+unsafe extern "C" fn js_free(s: *mut q::JSMallocState, ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let state = &mut *s;
+    let size = allocated_size(ptr);
+
+    state.malloc_count -= 1;
+    state.malloc_size -= (size + HEADER_SIZE) as q::size_t;
+    dealloc(state, header_ptr(ptr), layout_for(size));
+}
+
+unsafe extern "C" fn js_realloc(
+    s: *mut q::JSMallocState,
+    ptr: *mut c_void,
+    size: q::size_t,
+) -> *mut c_void {
+    if ptr.is_null() {
+        if size == 0 {
+            return null_mut();
+        }
+        return js_malloc(s, size);
+    }
+    if size == 0 {
+        js_free(s, ptr);
+        return null_mut();
+    }
+
+    let state = &mut *s;
+    let size = size as usize;
+    let old_size = allocated_size(ptr);
+
+    if size > old_size {
+        let growth = size - old_size;
+        if state.malloc_size as usize + growth > state.malloc_limit as usize {
+            return null_mut();
+        }
+        if !register_allocation(growth) {
+            return null_mut();
+        }
+    }
+
+    let raw = realloc(
+        state,
+        header_ptr(ptr),
+        layout_for(old_size),
+        size + HEADER_SIZE,
+    );
+    if raw.is_null() {
+        return null_mut();
+    }
+    *(raw as *mut usize) = size;
+
+    state.malloc_size = state.malloc_size - old_size as q::size_t + size as q::size_t;
+    raw.add(HEADER_SIZE) as *mut c_void
+}
+
+unsafe extern "C" fn js_malloc_usable_size(ptr: *const c_void) -> q::size_t {
+    if ptr.is_null() {
+        return 0;
+    }
+    allocated_size(ptr) as q::size_t
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::allocators::QuickJsAllocator;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingAllocator {
+        allocated: Arc<AtomicUsize>,
+    }
+
+    impl QuickJsAllocator for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.allocated.fetch_add(layout.size(), Ordering::SeqCst);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        let allocated = Arc::new(AtomicUsize::new(0));
+        let rt = QuickJsRuntimeBuilder::new()
+            .custom_allocator(CountingAllocator {
+                allocated: allocated.clone(),
+            })
+            .build();
+        let before = allocated.load(Ordering::SeqCst);
+        assert!(before > 0);
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_custom_allocator.js",
+                    "let data = []; for (let x = 0; x < 1000; x++) {data.push({id: x, name: 'item_' + x});}; data.length;",
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(res.get_i32(), 1000);
+        assert!(allocated.load(Ordering::SeqCst) > before);
+    }
+
+    #[test]
+    fn test_alloc_budget_per_eval() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .alloc_budget_per_eval(1024 * 1024)
+            .build();
+
+        // small scripts should just run, also after one another
+        for _ in 0..3 {
+            let res = rt
+                .eval_sync(None, Script::new("small.js", "[1, 2, 3].length;"))
+                .expect("script failed");
+            assert_eq!(res.get_i32(), 3);
+        }
+
+        // this does not retain memory but churns way more than 1MB
+        let res = rt.eval_sync(
+            None,
+            Script::new(
+                "churn.js",
+                "for (let x = 0; x < 1000000; x++) {let o = {a: x, b: 'str_' + x};}",
+            ),
+        );
+        assert!(res.is_err());
+
+        // budget is reset for the next eval
+        let res = rt
+            .eval_sync(None, Script::new("after.js", "(1 + 2);"))
+            .expect("script failed");
+        assert_eq!(res.get_i32(), 3);
+    }
+}
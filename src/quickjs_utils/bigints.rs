@@ -4,6 +4,8 @@ use crate::quickjs_utils::{functions, primitives};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use libquickjs_sys as q;
+use std::convert::TryFrom;
+use std::str::FromStr;
 
 pub fn new_bigint_i64_q(
     context: &QuickJsRealmAdapter,
@@ -80,9 +82,113 @@ pub unsafe fn to_string(
     functions::call_to_string(context, big_int_ref)
 }
 
+/// create a BigInt from an i128, unlike a Number this represents every integer exactly
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::bigints;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let id = (1_i128 << 53) + 1;
+///     let bi_ref = bigints::new_bigint_i128_q(q_ctx, id).expect("could not create bigint");
+///     assert_eq!(bigints::to_i128_q(q_ctx, &bi_ref).expect("could not read bigint"), id);
+/// });
+/// ```
+pub fn new_bigint_i128_q(
+    context: &QuickJsRealmAdapter,
+    int: i128,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { new_bigint_i128(context.context, int) }
+}
+
+/// create a BigInt from an i128
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn new_bigint_i128(
+    context: *mut q::JSContext,
+    int: i128,
+) -> Result<QuickJsValueAdapter, JsError> {
+    match i64::try_from(int) {
+        Ok(int) => new_bigint_i64(context, int),
+        Err(_) => new_bigint_str(context, int.to_string().as_str()),
+    }
+}
+
+/// create a BigInt from a u128
+pub fn new_bigint_u128_q(
+    context: &QuickJsRealmAdapter,
+    int: u128,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { new_bigint_u128(context.context, int) }
+}
+
+/// create a BigInt from a u128
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn new_bigint_u128(
+    context: *mut q::JSContext,
+    int: u128,
+) -> Result<QuickJsValueAdapter, JsError> {
+    match u64::try_from(int) {
+        Ok(int) => new_bigint_u64(context, int),
+        Err(_) => new_bigint_str(context, int.to_string().as_str()),
+    }
+}
+
+/// read a BigInt as an i128, fails if the BigInt does not fit in an i128
+pub fn to_i128_q(
+    context: &QuickJsRealmAdapter,
+    big_int_ref: &QuickJsValueAdapter,
+) -> Result<i128, JsError> {
+    unsafe { to_i128(context.context, big_int_ref) }
+}
+
+/// read a BigInt as an i128, fails if the BigInt does not fit in an i128
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn to_i128(
+    context: *mut q::JSContext,
+    big_int_ref: &QuickJsValueAdapter,
+) -> Result<i128, JsError> {
+    parse_bigint(to_string(context, big_int_ref)?.as_str())
+}
+
+/// read a BigInt as a u128, fails if the BigInt is negative or does not fit in a u128
+pub fn to_u128_q(
+    context: &QuickJsRealmAdapter,
+    big_int_ref: &QuickJsValueAdapter,
+) -> Result<u128, JsError> {
+    unsafe { to_u128(context.context, big_int_ref) }
+}
+
+/// read a BigInt as a u128, fails if the BigInt is negative or does not fit in a u128
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn to_u128(
+    context: *mut q::JSContext,
+    big_int_ref: &QuickJsValueAdapter,
+) -> Result<u128, JsError> {
+    parse_bigint(to_string(context, big_int_ref)?.as_str())
+}
+
+fn parse_bigint<T: FromStr>(digits: &str) -> Result<T, JsError> {
+    digits.parse().map_err(|_| {
+        JsError::new(
+            "RangeError".to_string(),
+            format!(
+                "BigInt {digits} does not fit in {}",
+                std::any::type_name::<T>()
+            ),
+            "".to_string(),
+        )
+    })
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::facades::tests::init_test_rt;
+    use crate::jsutils::Script;
     use crate::quickjs_utils::bigints;
     use crate::quickjs_utils::bigints::new_bigint_str_q;
 
@@ -106,4 +212,45 @@ pub mod tests {
             assert_eq!(to_str, "659863456457");
         });
     }
+
+    #[test]
+    fn test_bigint_128() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            for int in [i128::MAX, i128::MIN, -1, 0, (1 << 53) + 1] {
+                let bi_ref =
+                    bigints::new_bigint_i128_q(q_ctx, int).expect("could not create bigint");
+                assert!(bi_ref.is_big_int());
+                assert_eq!(
+                    bigints::to_i128_q(q_ctx, &bi_ref).expect("could not read bigint"),
+                    int
+                );
+            }
+            for int in [u64::MAX as u128, (1 << 53) + 1, u128::MAX] {
+                let bi_ref =
+                    bigints::new_bigint_u128_q(q_ctx, int).expect("could not create bigint");
+                assert_eq!(
+                    bigints::to_u128_q(q_ctx, &bi_ref).expect("could not read bigint"),
+                    int
+                );
+            }
+
+            // a value from script keeps the precision a Number would lose
+            let bi_ref = q_ctx
+                .eval(Script::new("test_bigint_128.js", "9007199254740993n * 2n;"))
+                .expect("script failed");
+            assert_eq!(
+                bigints::to_u128_q(q_ctx, &bi_ref).expect("could not read bigint"),
+                18014398509481986
+            );
+
+            let negative = bigints::new_bigint_i128_q(q_ctx, -5).expect("could not create bigint");
+            let err = bigints::to_u128_q(q_ctx, &negative).expect_err("negative BigInt was read");
+            assert_eq!(err.get_name(), "RangeError");
+            let huge = bigints::new_bigint_str_q(q_ctx, "1000000000000000000000000000000000000000")
+                .expect("could not create bigint");
+            assert!(bigints::to_i128_q(q_ctx, &huge).is_err());
+        });
+    }
 }
@@ -35,18 +35,68 @@ use std::os::raw::c_void;
 pub unsafe fn compile(
     context: *mut q::JSContext,
     script: Script,
+) -> Result<QuickJsValueAdapter, JsError> {
+    compile_with_options(context, script, &CompileOptions::default())
+}
+
+/// options for compile_with_options
+#[derive(Clone, Default)]
+pub struct CompileOptions {
+    strip_debug: bool,
+}
+
+impl CompileOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// strip debug info like line numbers and source code from the compiled function
+    /// this results in smaller bytecode but stack traces will no longer contain line numbers and Function.prototype.toString will not return the source
+    pub fn strip_debug(mut self, strip: bool) -> Self {
+        self.strip_debug = strip;
+        self
+    }
+}
+
+/// compile a script with CompileOptions, see compile
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::compile::{compile_with_options, to_bytecode, CompileOptions};
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     unsafe {
+///         let q_ctx = q_js_rt.get_main_realm();
+///         let options = CompileOptions::new().strip_debug(true);
+///         let func = compile_with_options(q_ctx.context, Script::new("test_func.es", "let a = 7; let b = 5; a * b;"), &options).expect("func compile failed");
+///         let bytecode = to_bytecode(q_ctx.context, &func);
+///         assert!(!bytecode.is_empty());
+///     }
+/// });
+/// ```
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn compile_with_options(
+    context: *mut q::JSContext,
+    script: Script,
+    options: &CompileOptions,
 ) -> Result<QuickJsValueAdapter, JsError> {
     let filename_c = make_cstring(script.get_path())?;
     let code_c = make_cstring(script.get_code())?;
 
     log::debug!("q_js_rt.compile file {}", script.get_path());
 
+    let mut flags = q::JS_EVAL_FLAG_COMPILE_ONLY as i32;
+    if options.strip_debug {
+        flags |= q::JS_EVAL_FLAG_STRIP as i32;
+    }
+
     let value_raw = q::JS_Eval(
         context,
         code_c.as_ptr(),
         script.get_code().len() as _,
         filename_c.as_ptr(),
-        q::JS_EVAL_FLAG_COMPILE_ONLY as i32,
+        flags,
     );
 
     log::trace!("after compile, checking error");
@@ -185,7 +235,8 @@ pub mod tests {
     use crate::jsutils::modules::CompiledModuleLoader;
     use crate::jsutils::Script;
     use crate::quickjs_utils::compile::{
-        compile, from_bytecode, run_compiled_function, to_bytecode,
+        compile, compile_with_options, from_bytecode, run_compiled_function, to_bytecode,
+        CompileOptions,
     };
     use crate::quickjs_utils::modules::compile_module;
     use crate::quickjs_utils::primitives;
@@ -265,6 +316,33 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_bytecode_strip_debug() {
+        let rt = init_test_rt();
+        let (full_len, stripped_len, res) = rt.exe_rt_task_in_event_loop(|q_js_rt| unsafe {
+            let q_ctx = q_js_rt.get_main_realm();
+            let code = "function mul(a, b) {\n    return a * b;\n}\nfunction add(a, b) {\n    return a + b;\n}\nadd(mul(7, 5), 1);";
+            let full = compile(q_ctx.context, Script::new("test_strip.es", code))
+                .expect("func compile failed");
+            let stripped = compile_with_options(
+                q_ctx.context,
+                Script::new("test_strip.es", code),
+                &CompileOptions::new().strip_debug(true),
+            )
+            .expect("func compile failed");
+            let full_len = to_bytecode(q_ctx.context, &full).len();
+            let stripped_bytecode = to_bytecode(q_ctx.context, &stripped);
+            drop(stripped);
+
+            let func = from_bytecode(q_ctx.context, &stripped_bytecode)
+                .expect("could not read bytecode");
+            let res = run_compiled_function(q_ctx.context, &func).expect("run failed");
+            (full_len, stripped_bytecode.len(), res.to_i32())
+        });
+        assert!(stripped_len < full_len);
+        assert_eq!(res, 36);
+    }
+
     #[test]
     fn test_bytecode_bad_compile() {
         let rt = QuickJsRuntimeBuilder::new().build();
@@ -80,12 +80,132 @@ pub unsafe fn get_time(
     }
 }
 
+/// format a Date object as a UTC ISO-8601 string, e.g. "2023-01-02T03:04:05.678Z"
+/// errors if the Date is invalid
+pub fn to_iso_string_q(
+    context: &QuickJsRealmAdapter,
+    date_ref: &QuickJsValueAdapter,
+) -> Result<String, JsError> {
+    unsafe { to_iso_string(context.context, date_ref) }
+}
+
+/// format a Date object as a UTC ISO-8601 string, e.g. "2023-01-02T03:04:05.678Z"
+/// errors if the Date is invalid
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn to_iso_string(
+    context: *mut q::JSContext,
+    date_ref: &QuickJsValueAdapter,
+) -> Result<String, JsError> {
+    if get_time(context, date_ref)?.is_nan() {
+        return Err(JsError::new_str("Invalid Date"));
+    }
+    let iso_ref = functions::invoke_member_function(context, date_ref, "toISOString", &[])?;
+    primitives::to_string(context, &iso_ref)
+}
+
+/// create a new Date object from an ISO-8601 string
+/// errors if the string could not be parsed as a valid Date
+pub fn from_iso_string_q(
+    context: &QuickJsRealmAdapter,
+    iso_string: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { from_iso_string(context.context, iso_string) }
+}
+
+/// create a new Date object from an ISO-8601 string
+/// errors if the string could not be parsed as a valid Date
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn from_iso_string(
+    context: *mut q::JSContext,
+    iso_string: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let constructor = quickjs_utils::get_constructor(context, "Date")?;
+    let iso_ref = primitives::from_string(context, iso_string)?;
+    let date_ref = functions::call_constructor(context, &constructor, &[iso_ref])?;
+    if get_time(context, &date_ref)?.is_nan() {
+        return Err(JsError::new_string(format!("Invalid Date: {iso_string}")));
+    }
+    Ok(date_ref)
+}
+
+/// create a new Date object from a chrono DateTime, the DateTime is truncated to milliseconds
+/// errors if the DateTime is outside the range of a Date
+#[cfg(feature = "chrono")]
+pub fn from_chrono_q(
+    context: &QuickJsRealmAdapter,
+    date_time: &chrono::DateTime<chrono::Utc>,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { from_chrono(context.context, date_time) }
+}
+
+/// create a new Date object from a chrono DateTime, the DateTime is truncated to milliseconds
+/// errors if the DateTime is outside the range of a Date
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+#[cfg(feature = "chrono")]
+pub unsafe fn from_chrono(
+    context: *mut q::JSContext,
+    date_time: &chrono::DateTime<chrono::Utc>,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let date_ref = new_date(context)?;
+    set_time(context, &date_ref, date_time.timestamp_millis() as f64)?;
+    if get_time(context, &date_ref)?.is_nan() {
+        return Err(JsError::new(
+            "RangeError".to_string(),
+            format!("{date_time} is outside the range of a Date"),
+            "".to_string(),
+        ));
+    }
+    Ok(date_ref)
+}
+
+/// convert a Date object to a chrono DateTime
+/// errors if the value is not a Date or if the Date is invalid
+#[cfg(feature = "chrono")]
+pub fn to_chrono_q(
+    context: &QuickJsRealmAdapter,
+    date_ref: &QuickJsValueAdapter,
+) -> Result<chrono::DateTime<chrono::Utc>, JsError> {
+    unsafe { to_chrono(context.context, date_ref) }
+}
+
+/// convert a Date object to a chrono DateTime
+/// errors if the value is not a Date or if the Date is invalid
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+#[cfg(feature = "chrono")]
+pub unsafe fn to_chrono(
+    context: *mut q::JSContext,
+    date_ref: &QuickJsValueAdapter,
+) -> Result<chrono::DateTime<chrono::Utc>, JsError> {
+    use chrono::TimeZone;
+    if !is_date(context, date_ref) {
+        return Err(JsError::new(
+            "TypeError".to_string(),
+            "value is not a Date".to_string(),
+            "".to_string(),
+        ));
+    }
+    let timestamp = get_time(context, date_ref)?;
+    if timestamp.is_nan() {
+        return Err(JsError::new_str("Invalid Date"));
+    }
+    chrono::Utc
+        .timestamp_millis_opt(timestamp as i64)
+        .single()
+        .ok_or_else(|| JsError::new_string(format!("Invalid Date: {timestamp}")))
+}
+
 #[cfg(test)]
 pub mod tests {
 
     use crate::facades::tests::init_test_rt;
     use crate::quickjs_utils::dates;
-    use crate::quickjs_utils::dates::{get_time_q, is_date_q, set_time_q};
+    use crate::quickjs_utils::dates::{
+        from_iso_string_q, get_time_q, is_date_q, set_time_q, to_iso_string_q,
+    };
 
     #[test]
     fn test_date() {
@@ -118,4 +238,55 @@ pub mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_iso_string_round_trip() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let date_ref =
+                from_iso_string_q(q_ctx, "2023-01-02T03:04:05.678Z").expect("could not parse");
+            assert!(is_date_q(q_ctx, &date_ref));
+            assert_eq!(
+                get_time_q(q_ctx, &date_ref).expect("could not get time"),
+                1672628645678f64
+            );
+            assert_eq!(
+                to_iso_string_q(q_ctx, &date_ref).expect("could not format"),
+                "2023-01-02T03:04:05.678Z"
+            );
+
+            assert!(from_iso_string_q(q_ctx, "not a date").is_err());
+            set_time_q(q_ctx, &date_ref, f64::NAN).expect("could not set time");
+            assert!(to_iso_string_q(q_ctx, &date_ref).is_err());
+        });
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_round_trip() {
+        use crate::quickjs_utils::dates::{from_chrono_q, to_chrono_q};
+        use chrono::TimeZone;
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let date_time = chrono::Utc
+                .timestamp_millis_opt(1672628645678)
+                .single()
+                .expect("invalid timestamp");
+            let date_ref = from_chrono_q(q_ctx, &date_time).expect("could not create Date");
+            assert!(is_date_q(q_ctx, &date_ref));
+            assert_eq!(
+                to_iso_string_q(q_ctx, &date_ref).expect("could not format"),
+                "2023-01-02T03:04:05.678Z"
+            );
+            let round_trip = to_chrono_q(q_ctx, &date_ref).expect("could not convert Date");
+            assert_eq!(round_trip, date_time);
+            assert_eq!(round_trip.timestamp_subsec_millis(), 678);
+
+            let not_a_date = q_ctx.create_f64(1672628645678f64).unwrap();
+            let err = to_chrono_q(q_ctx, &not_a_date).expect_err("number was converted");
+            assert_eq!(err.get_name(), "TypeError");
+        });
+    }
 }
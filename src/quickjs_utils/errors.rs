@@ -1,7 +1,8 @@
 //! utils for getting and reporting exceptions
 
 use crate::jsutils::JsError;
-use crate::quickjs_utils::{objects, primitives};
+use crate::quickjs_utils;
+use crate::quickjs_utils::{functions, objects, primitives};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::{QuickJsValueAdapter, TAG_EXCEPTION};
 use libquickjs_sys as q;
@@ -34,6 +35,17 @@ pub unsafe fn get_exception(context: *mut q::JSContext) -> Option<JsError> {
 pub unsafe fn error_to_js_error(
     context: *mut q::JSContext,
     exception_ref: &QuickJsValueAdapter,
+) -> JsError {
+    error_to_js_error_with_depth(context, exception_ref, 0)
+}
+
+// max number of causes to convert, this also guards against errors which cause each other
+const MAX_CAUSE_DEPTH: usize = 16;
+
+unsafe fn error_to_js_error_with_depth(
+    context: *mut q::JSContext,
+    exception_ref: &QuickJsValueAdapter,
+    depth: usize,
 ) -> JsError {
     let name_ref = objects::get_property(context, exception_ref, "name")
         .ok()
@@ -69,10 +81,91 @@ pub unsafe fn error_to_js_error(
         );
     }
 
-    JsError::new(name_string, message_string, stack_string)
+    let location = property_location(context, exception_ref)
+        .or_else(|| parse_stack_location(stack_string.as_str()));
+
+    let mut js_error = JsError::new(name_string, message_string, stack_string);
+    if let Some((file, line, column)) = location {
+        js_error = js_error.with_location(file.as_str(), line, column);
+    }
+
+    if depth < MAX_CAUSE_DEPTH {
+        if let Ok(cause_ref) = objects::get_property(context, exception_ref, "cause") {
+            if is_error(context, &cause_ref) {
+                return js_error.with_cause(error_to_js_error_with_depth(
+                    context,
+                    &cause_ref,
+                    depth + 1,
+                ));
+            } else if !cause_ref.is_null_or_undefined() {
+                if let Ok(cause_str) = functions::call_to_string(context, &cause_ref) {
+                    return js_error.with_cause(JsError::new_string(cause_str));
+                }
+            }
+        }
+    }
+
+    js_error
+}
+
+/// get the location of an error from the fileName, lineNumber and columnNumber properties QuickJS sets for a SyntaxError
+unsafe fn property_location(
+    context: *mut q::JSContext,
+    exception_ref: &QuickJsValueAdapter,
+) -> Option<(String, u32, Option<u32>)> {
+    let file_ref = objects::get_property(context, exception_ref, "fileName").ok()?;
+    let line_ref = objects::get_property(context, exception_ref, "lineNumber").ok()?;
+    if !file_ref.is_string() || !line_ref.is_i32() {
+        return None;
+    }
+    let file = primitives::to_string(context, &file_ref).ok()?;
+    let column = objects::get_property(context, exception_ref, "columnNumber")
+        .ok()
+        .filter(|column_ref| column_ref.is_i32())
+        .map(|column_ref| column_ref.to_i32() as u32);
+    Some((file, line_ref.to_i32() as u32, column))
+}
+
+/// get the location of the first script frame of a stack, frames look like `at func (file:line)` or `at file:line:column`
+/// returns None when the stack format is not recognized
+pub(crate) fn parse_stack_location(stack: &str) -> Option<(String, u32, Option<u32>)> {
+    for frame in stack.lines() {
+        let location = match frame.trim().strip_prefix("at ") {
+            Some(location) => location,
+            None => continue,
+        };
+        let location = match location.rfind('(') {
+            Some(open) if location.ends_with(')') => &location[open + 1..location.len() - 1],
+            _ => location,
+        };
+        let parts: Vec<&str> = location.rsplitn(3, ':').collect();
+        let numeric = |part: &str| part.parse::<u32>().ok();
+        match parts.as_slice() {
+            [column, line, file] if numeric(column).is_some() && numeric(line).is_some() => {
+                return Some((file.to_string(), numeric(line)?, numeric(column)));
+            }
+            [line, ..] if numeric(line).is_some() => {
+                let file = &location[..location.len() - line.len() - 1];
+                return Some((file.to_string(), numeric(line)?, None));
+            }
+            _ => {}
+        }
+    }
+    None
 }
 
+/// the native Error subclasses, errors with one of these names are created as instance of that class
+const NATIVE_ERROR_NAMES: &[&str] = &[
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+];
+
 /// Create a new Error object
+/// when the name is that of a native Error subclass like TypeError the object is an instance of that class so `err instanceof TypeError` works in script
 /// # Safety
 /// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
 pub unsafe fn new_error(
@@ -81,14 +174,19 @@ pub unsafe fn new_error(
     message: &str,
     stack: &str,
 ) -> Result<QuickJsValueAdapter, JsError> {
-    let obj = q::JS_NewError(context);
-    let obj_ref = QuickJsValueAdapter::new(
-        context,
-        obj,
-        false,
-        true,
-        format!("new_error {name}").as_str(),
-    );
+    let obj_ref = if NATIVE_ERROR_NAMES.contains(&name) {
+        let constructor = quickjs_utils::get_constructor(context, name)?;
+        functions::call_constructor(context, &constructor, &[])?
+    } else {
+        let obj = q::JS_NewError(context);
+        QuickJsValueAdapter::new(
+            context,
+            obj,
+            false,
+            true,
+            format!("new_error {name}").as_str(),
+        )
+    };
     objects::set_property(
         context,
         &obj_ref,
@@ -110,6 +208,60 @@ pub unsafe fn new_error(
     Ok(obj_ref)
 }
 
+/// get the name of the Error for an HTTP-like status, e.g. 404 -> "NotFoundError"
+/// statuses without a specific name get a name derived from their class ("ClientError" for 4xx, "ServerError" for 5xx, "HttpError" otherwise)
+pub fn http_error_name(status: u16) -> &'static str {
+    match status {
+        400 => "BadRequestError",
+        401 => "UnauthorizedError",
+        403 => "ForbiddenError",
+        404 => "NotFoundError",
+        405 => "MethodNotAllowedError",
+        408 => "RequestTimeoutError",
+        409 => "ConflictError",
+        410 => "GoneError",
+        413 => "PayloadTooLargeError",
+        415 => "UnsupportedMediaTypeError",
+        422 => "UnprocessableEntityError",
+        429 => "TooManyRequestsError",
+        500 => "InternalServerError",
+        501 => "NotImplementedError",
+        502 => "BadGatewayError",
+        503 => "ServiceUnavailableError",
+        504 => "GatewayTimeoutError",
+        400..=499 => "ClientError",
+        500..=599 => "ServerError",
+        _ => "HttpError",
+    }
+}
+
+/// Create a new Error object with a numeric `status` property and a name derived from that status
+pub fn new_http_error_q(
+    q_ctx: &QuickJsRealmAdapter,
+    status: u16,
+    message: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { new_http_error(q_ctx.context, status, message) }
+}
+
+/// Create a new Error object with a numeric `status` property and a name derived from that status
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn new_http_error(
+    context: *mut q::JSContext,
+    status: u16,
+    message: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let obj_ref = new_error(context, http_error_name(status), message, "")?;
+    objects::set_property(
+        context,
+        &obj_ref,
+        "status",
+        &primitives::from_i32(status as i32),
+    )?;
+    Ok(obj_ref)
+}
+
 /// See if a JSValueRef is an Error object
 pub fn is_error_q(q_ctx: &QuickJsRealmAdapter, obj_ref: &QuickJsValueAdapter) -> bool {
     unsafe { is_error(q_ctx.context, obj_ref) }
@@ -143,10 +295,208 @@ pub unsafe fn throw(context: *mut q::JSContext, error: QuickJsValueAdapter) -> q
 pub mod tests {
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::{JsError, Script};
-    use crate::quickjs_utils::functions;
+    use crate::quickjs_utils;
+    use crate::quickjs_utils::{errors, functions, objects};
     use crate::values::{JsValueConvertable, JsValueFacade};
     use std::time::Duration;
 
+    #[test]
+    fn test_error_cause() {
+        let rt = init_test_rt();
+        let err = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_error_cause.js",
+                    r#"
+                let inner = new TypeError('inner fail');
+                inner.cause = 'root fail';
+                let outer = new Error('outer fail');
+                outer.cause = inner;
+                throw outer;
+            "#,
+                ),
+            )
+            .expect_err("script did not fail");
+
+        assert_eq!(err.get_message(), "outer fail");
+        let cause = err.cause().expect("no cause");
+        assert_eq!(cause.get_name(), "TypeError");
+        assert_eq!(cause.get_message(), "inner fail");
+        let root_cause = cause.cause().expect("no root cause");
+        assert_eq!(root_cause.get_message(), "root fail");
+        assert!(root_cause.cause().is_none());
+
+        let formatted = err.format_with_causes();
+        assert!(formatted.starts_with("Error: outer fail"));
+        assert!(formatted.contains("\nCaused by:\nTypeError: inner fail"));
+        assert!(formatted.contains("\nCaused by:\nError: root fail"));
+    }
+
+    #[test]
+    fn test_boxed_error() {
+        fn eval_boxed(
+            rt: &crate::facades::QuickJsRuntimeFacade,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            rt.eval_sync(
+                None,
+                Script::new(
+                    "test_boxed_error.js",
+                    "let err = new RangeError('too far'); err.cause = new Error('root'); throw err;",
+                ),
+            )?;
+            Ok(())
+        }
+
+        let rt = init_test_rt();
+        let boxed = eval_boxed(&rt).expect_err("script did not fail");
+        assert!(boxed.to_string().starts_with("RangeError: too far"));
+        let source = boxed.source().expect("no source");
+        assert!(source.to_string().starts_with("Error: root"));
+        assert!(source.source().is_none());
+        let err = boxed.downcast::<JsError>().expect("not a JsError");
+        assert_eq!(err.get_name(), "RangeError");
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error;
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "config.json not found");
+        let err = JsError::with_source(
+            "IoError".to_string(),
+            "could not load config".to_string(),
+            "".to_string(),
+            io_err,
+        );
+        assert_eq!(err.get_name(), "IoError");
+        let source = err.source().expect("no source");
+        assert_eq!(source.to_string(), "config.json not found");
+        let io_err = source
+            .downcast_ref::<std::io::Error>()
+            .expect("not an io error");
+        assert_eq!(io_err.kind(), std::io::ErrorKind::NotFound);
+
+        assert!(JsError::new_str("plain").source().is_none());
+    }
+
+    #[test]
+    fn test_parse_stack_location() {
+        assert_eq!(
+            errors::parse_stack_location("    at <eval> (broken.js:2)\n"),
+            Some(("broken.js".to_string(), 2, None))
+        );
+        assert_eq!(
+            errors::parse_stack_location("    at log (native)\n    at c:/app/test.js:3:7\n"),
+            Some(("c:/app/test.js".to_string(), 3, Some(7)))
+        );
+        assert_eq!(errors::parse_stack_location("    at log (native)\n"), None);
+        assert_eq!(errors::parse_stack_location(""), None);
+    }
+
+    #[test]
+    fn test_error_location() {
+        let rt = init_test_rt();
+        let err = rt
+            .eval_sync(
+                None,
+                Script::new("broken.js", "let a = 1;\nlet b = undefinedVar + a;"),
+            )
+            .expect_err("script did not fail");
+        assert_eq!(err.get_name(), "ReferenceError");
+        assert_eq!(err.get_file(), Some("broken.js"));
+        assert_eq!(err.get_line(), Some(2));
+
+        let err = rt
+            .eval_sync(None, Script::new("syntax.js", "let a = 1;\n\nlet b = ;"))
+            .expect_err("script did not fail");
+        assert_eq!(err.get_name(), "SyntaxError");
+        assert_eq!(err.get_file(), Some("syntax.js"));
+        assert_eq!(err.get_line(), Some(3));
+
+        let err = JsError::new_str("not from a script");
+        assert_eq!(err.get_file(), None);
+        assert_eq!(err.get_line(), None);
+        assert_eq!(err.get_column(), None);
+    }
+
+    #[test]
+    fn test_error_with_context() {
+        let rt = init_test_rt();
+        let err = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let process_items = realm
+                .create_function(
+                    "processItems",
+                    |realm, _this, args| {
+                        let len = realm.get_array_length(&args[0])?;
+                        for index in 0..len {
+                            let item = realm.get_array_element(&args[0], index)?;
+                            realm
+                                .invoke_function(None, &args[1], &[&item])
+                                .map_err(|e| {
+                                    e.with_context(format!("while processing item {}:", index + 1))
+                                })?;
+                        }
+                        realm.create_undefined()
+                    },
+                    2,
+                )
+                .expect("could not create function");
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "processItems", &process_items)
+                .expect("could not set processItems");
+            realm
+                .eval(Script::new(
+                    "test_error_with_context.js",
+                    r#"
+                function failOnThree(item) {
+                    if (item === 'c') {
+                        throw new TypeError('bad item');
+                    }
+                }
+                let caught;
+                try {
+                    processItems(['a', 'b', 'c'], failOnThree);
+                } catch (err) {
+                    caught = err;
+                }
+                if (!(caught instanceof TypeError) || caught.message !== 'while processing item 3: bad item') {
+                    throw Error('unexpected error: ' + caught);
+                }
+                processItems(['a', 'b', 'c'], failOnThree);
+            "#,
+                ))
+                .expect_err("script did not fail")
+        });
+        assert_eq!(err.get_name(), "TypeError");
+        assert_eq!(err.get_message(), "while processing item 3: bad item");
+        // the stack still contains the frame of the callback which threw the original error
+        assert!(err.get_stack().contains("failOnThree"));
+    }
+
+    #[test]
+    fn test_http_error() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let err = errors::new_http_error_q(q_ctx, 404, "no such user")
+                .expect("could not create error");
+            let global = quickjs_utils::get_global_q(q_ctx);
+            objects::set_property_q(q_ctx, &global, "httpErr", &err).expect("could not set prop");
+            let res = q_ctx
+                .eval(Script::new(
+                    "test_http_error.js",
+                    "[httpErr instanceof Error, httpErr.status, httpErr.name, httpErr.message, JSON.parse(JSON.stringify(httpErr)).status].join('|');",
+                ))
+                .expect("script failed");
+            res.to_string().expect("not a string")
+        });
+        assert_eq!(res, "true|404|NotFoundError|no such user|404");
+        assert_eq!(errors::http_error_name(418), "ClientError");
+        assert_eq!(errors::http_error_name(599), "ServerError");
+    }
+
     #[test]
     fn test_ex_nat() {
         // check if stacktrace is preserved when invoking native methods
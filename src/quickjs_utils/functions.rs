@@ -72,6 +72,7 @@ pub fn call_function_q_ref_args(
     arguments: &[&QuickJsValueAdapter],
     this_ref_opt: Option<&QuickJsValueAdapter>,
 ) -> Result<QuickJsValueAdapter, JsError> {
+    q_ctx.mark_active();
     unsafe { call_function_ref_args(q_ctx.context, function_ref, arguments, this_ref_opt) }
 }
 
@@ -83,6 +84,7 @@ pub fn call_function_q(
     this_ref_opt: Option<&QuickJsValueAdapter>,
 ) -> Result<QuickJsValueAdapter, JsError> {
     let r: Vec<&QuickJsValueAdapter> = arguments.iter().collect();
+    q_ctx.mark_active();
     unsafe { call_function_ref_args(q_ctx.context, function_ref, &r, this_ref_opt) }
 }
 
@@ -164,6 +166,7 @@ pub fn invoke_member_function_q(
     function_name: &str,
     arguments: &[QuickJsValueAdapter],
 ) -> Result<QuickJsValueAdapter, JsError> {
+    q_ctx.mark_active();
     unsafe { invoke_member_function(q_ctx.context, obj_ref, function_name, arguments) }
 }
 
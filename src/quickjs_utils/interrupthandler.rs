@@ -1,9 +1,14 @@
-use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+use crate::quickjsruntimeadapter::{QuickJsRuntimeAdapter, QJS_RT};
 use libquickjs_sys as q;
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::os::raw::c_int;
+use std::time::{Duration, Instant};
 
-//
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+    static DEADLINE_HIT: Cell<bool> = Cell::new(false);
+}
 
 /// set an interrupt handler for the runtime
 /// # Safety
@@ -12,14 +17,70 @@ pub unsafe fn set_interrupt_handler(runtime: *mut q::JSRuntime, handler: q::JSIn
     q::JS_SetInterruptHandler(runtime, handler, std::ptr::null_mut());
 }
 
+/// install the interrupt handler which checks the deadline of a DeadlineGuard and calls the handler of the QuickJsRuntimeAdapter
+/// this is done once when the runtime is initialized for its worker thread
 pub(crate) fn init(q_js_rt: &QuickJsRuntimeAdapter) {
     unsafe { set_interrupt_handler(q_js_rt.runtime, Some(interrupt_handler)) };
 }
 
+/// guard which interrupts running script when it takes longer than a given timeout
+/// this is used to make sure operations like calling a user defined toString can not hang the runtime
+/// a nested guard never extends the deadline of an outer guard, the previous deadline is restored when the guard is dropped
+pub(crate) struct DeadlineGuard {
+    previous_deadline: Option<Instant>,
+    previous_hit: bool,
+}
+
+impl DeadlineGuard {
+    pub(crate) fn start(timeout: Duration) -> Self {
+        let deadline = Instant::now() + timeout;
+        let previous_deadline = DEADLINE.with(|rc| {
+            let previous = rc.get();
+            rc.set(Some(
+                previous.map_or(deadline, |previous| previous.min(deadline)),
+            ));
+            previous
+        });
+        let previous_hit = DEADLINE_HIT.with(|rc| rc.replace(false));
+        Self {
+            previous_deadline,
+            previous_hit,
+        }
+    }
+    /// check if script was interrupted because the deadline passed
+    pub(crate) fn was_interrupted(&self) -> bool {
+        DEADLINE_HIT.with(|rc| rc.get())
+    }
+}
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        DEADLINE.with(|rc| rc.set(self.previous_deadline));
+        DEADLINE_HIT.with(|rc| rc.set(self.previous_hit));
+    }
+}
+
 unsafe extern "C" fn interrupt_handler(_rt: *mut q::JSRuntime, _opaque: *mut c_void) -> c_int {
-    QuickJsRuntimeAdapter::do_with(|q_js_rt| {
-        let handler = q_js_rt.interrupt_handler.as_ref().unwrap();
-        i32::from(handler(q_js_rt))
+    if let Some(deadline) = DEADLINE.with(|rc| rc.get()) {
+        if Instant::now() > deadline {
+            DEADLINE_HIT.with(|rc| rc.set(true));
+            return 1;
+        }
+    }
+    // the handler is installed for the lifetime of the runtime, script may also run while the runtime is borrowed mutably
+    // (e.g. when adding a realm), in that case there is no handler to call
+    QJS_RT.with(|rc| {
+        let q_js_rt_opt = match rc.try_borrow() {
+            Ok(q_js_rt_opt) => q_js_rt_opt,
+            Err(_) => return 0,
+        };
+        match q_js_rt_opt.as_ref() {
+            Some(q_js_rt) => match q_js_rt.interrupt_handler.as_ref() {
+                Some(handler) => i32::from(handler(q_js_rt)),
+                None => 0,
+            },
+            None => 0,
+        }
     })
 }
 
@@ -28,11 +89,27 @@ pub mod tests {
     use crate::builder::QuickJsRuntimeBuilder;
     use crate::jsutils::Script;
     use crate::quickjs_utils::get_script_or_module_name_q;
+    use crate::quickjs_utils::interrupthandler::{DeadlineGuard, DEADLINE};
     use backtrace::Backtrace;
     use log::LevelFilter;
     use std::cell::RefCell;
     use std::panic;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn test_nested_deadline() {
+        let outer = DeadlineGuard::start(Duration::from_millis(10));
+        let outer_deadline = DEADLINE.with(|rc| rc.get()).expect("no deadline");
+        {
+            let _inner = DeadlineGuard::start(Duration::from_secs(10));
+            // a nested guard can not extend the deadline of the outer guard
+            assert_eq!(DEADLINE.with(|rc| rc.get()), Some(outer_deadline));
+        }
+        assert_eq!(DEADLINE.with(|rc| rc.get()), Some(outer_deadline));
+        drop(outer);
+        assert_eq!(DEADLINE.with(|rc| rc.get()), None);
+    }
 
     #[test]
     fn test_interrupt_handler() {
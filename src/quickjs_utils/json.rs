@@ -1,7 +1,8 @@
 //! serialize and stringify JavaScript objects
 
-use crate::jsutils::JsError;
+use crate::jsutils::{JsError, JsValueType};
 use crate::quickjs_utils;
+use crate::quickjs_utils::{arrays, functions, objects, primitives};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use libquickjs_sys as q;
@@ -60,6 +61,115 @@ pub unsafe fn parse(
         Ok(ret)
     }
 }
+/// a reviver for parse_with_reviver, called with the key and value of every parsed member (like the reviver of JSON.parse)
+/// the returned value replaces the parsed value, returning undefined removes the member
+pub type JsonReviver = Box<
+    dyn Fn(
+        &QuickJsRealmAdapter,
+        &str,
+        &QuickJsValueAdapter,
+    ) -> Result<QuickJsValueAdapter, JsError>,
+>;
+
+/// Parse a JSON string into an Object while transforming the parsed values with a reviver, like JSON.parse(text, reviver)
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::{json, objects, primitives};
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = json::parse_with_reviver_q(q_ctx, "{\"a\": 1}", Box::new(|_realm, _key, value| {
+///         if value.is_i32() {
+///             Ok(primitives::from_i32(value.to_i32() * 2))
+///         } else {
+///             Ok(value.clone())
+///         }
+///     })).expect("parse failed");
+///     let a_ref = objects::get_property_q(q_ctx, &obj_ref, "a").expect("no a");
+///     assert_eq!(a_ref.to_i32(), 2);
+/// });
+/// ```
+pub fn parse_with_reviver_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &str,
+    reviver: JsonReviver,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let reviver_func = functions::new_function_q(
+        q_ctx,
+        "reviver",
+        move |realm, _this, args| {
+            let key = args[0].to_string()?;
+            reviver(realm, key.as_str(), &args[1])
+        },
+        2,
+    )?;
+    let json_ref = objects::get_property_q(q_ctx, &quickjs_utils::get_global_q(q_ctx), "JSON")?;
+    let input_ref = primitives::from_string_q(q_ctx, input)?;
+    functions::invoke_member_function_q(q_ctx, &json_ref, "parse", &[input_ref, reviver_func])
+}
+
+/// Parse a JSON string into an Object while transforming the parsed values with a reviver, like JSON.parse(text, reviver)
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn parse_with_reviver(
+    context: *mut q::JSContext,
+    input: &str,
+    reviver: JsonReviver,
+) -> Result<QuickJsValueAdapter, JsError> {
+    QuickJsRealmAdapter::with_context(context, |q_ctx| parse_with_reviver_q(q_ctx, input, reviver))
+}
+
+/// Parse a JSON string into an Object while transforming the parsed values with a reviver function from script
+/// the reviver is called bottom-up with the key and value of every member like the reviver of JSON.parse(text, reviver), `this` is undefined
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::{json, objects};
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let reviver = q_ctx.eval(Script::new("reviver.js", "(key, value) => key === 'a' ? 'revived' : value;")).expect("script failed");
+///     let obj_ref = json::parse_with_reviver_function_q(q_ctx, "{\"a\": 1}", &reviver).expect("parse failed");
+///     let a_ref = objects::get_property_q(q_ctx, &obj_ref, "a").expect("no a");
+///     assert_eq!(a_ref.to_string().expect("not a string"), "revived");
+/// });
+/// ```
+pub fn parse_with_reviver_function_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &str,
+    reviver: &QuickJsValueAdapter,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { parse_with_reviver_function(q_ctx.context, input, reviver) }
+}
+
+/// Parse a JSON string into an Object while transforming the parsed values with a reviver function from script
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn parse_with_reviver_function(
+    context: *mut q::JSContext,
+    input: &str,
+    reviver: &QuickJsValueAdapter,
+) -> Result<QuickJsValueAdapter, JsError> {
+    if !functions::is_function(context, reviver) {
+        return Err(JsError::new(
+            "TypeError".to_string(),
+            "reviver is not a function".to_string(),
+            "".to_string(),
+        ));
+    }
+    let reviver = reviver.clone();
+    parse_with_reviver(
+        context,
+        input,
+        Box::new(move |realm, key, value| {
+            let key_ref = primitives::from_string_q(realm, key)?;
+            functions::call_function_q(realm, &reviver, &[key_ref, value.clone()], None)
+        }),
+    )
+}
+
 /// Stringify an Object in script
 /// # Example
 /// ```rust
@@ -91,23 +201,129 @@ pub unsafe fn stringify(
     input: &QuickJsValueAdapter,
     opt_space: Option<QuickJsValueAdapter>,
 ) -> Result<QuickJsValueAdapter, JsError> {
-    //pub fn JS_JSONStringify(
-    //         ctx: *mut JSContext,
-    //         obj: JSValue,
-    //         replacer: JSValue,
-    //         space0: JSValue,
-    //     ) -> JSValue;
-
     let space_ref = match opt_space {
         None => quickjs_utils::new_null_ref(),
         Some(s) => s,
     };
+    json_stringify(context, input, &quickjs_utils::new_null_ref(), &space_ref)
+}
+
+/// the replacer of StringifyOptions, like the replacer argument of JSON.stringify
+pub enum JsonReplacer {
+    /// a function from script which is called with the key and value of every member, the value it returns is serialized instead
+    Function(QuickJsValueAdapter),
+    /// the names of the properties to serialize, other properties are left out at every level
+    Keys(Vec<String>),
+}
+
+/// the indentation of StringifyOptions, like the space argument of JSON.stringify
+pub enum JsonIndent {
+    /// indent every level with a number of spaces, like JSON.stringify at most 10 spaces are used
+    Spaces(u8),
+    /// indent every level with a string, like JSON.stringify at most the first 10 characters are used
+    Text(String),
+}
+
+/// options for stringify_with_options_q
+#[derive(Default)]
+pub struct StringifyOptions {
+    replacer: Option<JsonReplacer>,
+    indent: Option<JsonIndent>,
+}
+
+impl StringifyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// transform or filter the serialized members
+    pub fn replacer(mut self, replacer: JsonReplacer) -> Self {
+        self.replacer = Some(replacer);
+        self
+    }
+    /// pretty-print the output with every member on a new line
+    pub fn indent(mut self, indent: JsonIndent) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+}
+
+/// Stringify an Object in script with a replacer and indentation, like JSON.stringify(value, replacer, space)
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::json::{self, JsonIndent, JsonReplacer, StringifyOptions};
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = q_ctx.eval(Script::new("stringify_with_options.es", "({a: 1, secret: 'x'});")).expect("script failed");
+///     let options = StringifyOptions::new()
+///         .replacer(JsonReplacer::Keys(vec!["a".to_string()]))
+///         .indent(JsonIndent::Spaces(2));
+///     let str_ref = json::stringify_with_options_q(q_ctx, &obj_ref, &options).expect("stringify failed");
+///     assert_eq!(str_ref.to_string().expect("not a string"), "{\n  \"a\": 1\n}");
+/// });
+/// ```
+pub fn stringify_with_options_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &QuickJsValueAdapter,
+    options: &StringifyOptions,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { stringify_with_options(q_ctx.context, input, options) }
+}
 
+/// Stringify an Object in script with a replacer and indentation, like JSON.stringify(value, replacer, space)
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn stringify_with_options(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    options: &StringifyOptions,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let replacer_ref = match &options.replacer {
+        None => quickjs_utils::new_null_ref(),
+        Some(JsonReplacer::Function(func)) => {
+            if !functions::is_function(context, func) {
+                return Err(JsError::new(
+                    "TypeError".to_string(),
+                    "replacer is not a function".to_string(),
+                    "".to_string(),
+                ));
+            }
+            func.clone()
+        }
+        Some(JsonReplacer::Keys(keys)) => {
+            let array = arrays::create_array(context)?;
+            for (index, key) in keys.iter().enumerate() {
+                arrays::set_element(
+                    context,
+                    &array,
+                    index as u32,
+                    &primitives::from_string(context, key)?,
+                )?;
+            }
+            array
+        }
+    };
+    let space_ref = match &options.indent {
+        None => quickjs_utils::new_null_ref(),
+        Some(JsonIndent::Spaces(spaces)) => primitives::from_i32(*spaces as i32),
+        Some(JsonIndent::Text(text)) => primitives::from_string(context, text)?,
+    };
+    json_stringify(context, input, &replacer_ref, &space_ref)
+}
+
+unsafe fn json_stringify(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    replacer: &QuickJsValueAdapter,
+    space: &QuickJsValueAdapter,
+) -> Result<QuickJsValueAdapter, JsError> {
     let val = q::JS_JSONStringify(
         context,
         *input.borrow_value(),
-        quickjs_utils::new_null(),
-        *space_ref.borrow_value(),
+        *replacer.borrow_value(),
+        *space.borrow_value(),
     );
     let ret = QuickJsValueAdapter::new(context, val, false, true, "json::stringify result");
 
@@ -122,12 +338,508 @@ pub unsafe fn stringify(
     }
 }
 
+/// stringify a value for logging, unlike stringify this never fails
+/// values which can not be serialized are replaced by placeholders
+/// * circular references are output as "[Circular]"
+/// * BigInts are output as "123n"
+/// * functions are output as "[Function: name]" and symbols as "[Symbol]"
+/// * values for which a getter or toJSON method throws are output as "[Unserializable: message]"
+///
+/// nested placeholders are output as JSON strings, a placeholder for the value itself is returned as is
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::json;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = q_ctx.eval(Script::new("safe_stringify.es", "let o = {a: 1}; o.me = o; o;")).ok().unwrap();
+///     assert_eq!(json::safe_stringify_q(q_ctx, &obj_ref), "{\"a\":1,\"me\":\"[Circular]\"}");
+/// });
+/// ```
+pub fn safe_stringify_q(q_ctx: &QuickJsRealmAdapter, input: &QuickJsValueAdapter) -> String {
+    unsafe { safe_stringify(q_ctx.context, input) }
+}
+
+/// stringify a value for logging, unlike stringify this never fails
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn safe_stringify(context: *mut q::JSContext, input: &QuickJsValueAdapter) -> String {
+    safe_stringify_nodes(context, input, None, None)
+}
+
+/// stringify a value for logging like safe_stringify but stop after visiting max_nodes values
+/// this guards against huge (but acyclic) object graphs, the output then ends with a "… (output limited)" placeholder
+pub fn safe_stringify_limited_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &QuickJsValueAdapter,
+    max_nodes: usize,
+) -> String {
+    unsafe { safe_stringify_limited(q_ctx.context, input, max_nodes) }
+}
+
+/// stringify a value for logging like safe_stringify but stop after visiting max_nodes values
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn safe_stringify_limited(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    max_nodes: usize,
+) -> String {
+    safe_stringify_nodes(context, input, Some(max_nodes), None)
+}
+
+/// stringify a value for logging like safe_stringify but output objects and arrays which are nested deeper than max_depth
+/// as an "[Object]" or "[Array]" placeholder, a max_depth of 0 only outputs the members of the value itself
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::json;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = q_ctx.eval(Script::new("safe_stringify_depth.es", "({a: {b: [1]}});")).ok().unwrap();
+///     assert_eq!(json::safe_stringify_depth_q(q_ctx, &obj_ref, 1), "{\"a\":{\"b\":\"[Array]\"}}");
+/// });
+/// ```
+pub fn safe_stringify_depth_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &QuickJsValueAdapter,
+    max_depth: usize,
+) -> String {
+    unsafe { safe_stringify_depth(q_ctx.context, input, max_depth) }
+}
+
+/// stringify a value for logging like safe_stringify but output objects and arrays which are nested deeper than max_depth as a placeholder
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn safe_stringify_depth(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    max_depth: usize,
+) -> String {
+    safe_stringify_nodes(context, input, None, Some(max_depth))
+}
+
+/// estimate the size in bytes of the JSON of a value without building the JSON, strings and property names are counted as utf-8 including their escapes
+/// the estimate stops growing as soon as it exceeds limit so walking a huge value stays cheap
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::json;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = q_ctx.eval(Script::new("estimate_json_size.es", "({a: [1, 'b']});")).ok().unwrap();
+///     assert_eq!(json::estimate_json_size_q(q_ctx, &obj_ref, 1024), "{\"a\":[1,\"b\"]}".len());
+///     assert!(json::estimate_json_size_q(q_ctx, &obj_ref, 4) > 4);
+/// });
+/// ```
+pub fn estimate_json_size_q(
+    q_ctx: &QuickJsRealmAdapter,
+    input: &QuickJsValueAdapter,
+    limit: usize,
+) -> usize {
+    unsafe { estimate_json_size(q_ctx.context, input, limit) }
+}
+
+/// estimate the size in bytes of the JSON of a value without building the JSON, see estimate_json_size_q
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn estimate_json_size(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    limit: usize,
+) -> usize {
+    let mut size = 0;
+    add_json_size(context, input, &mut vec![], &mut size, limit);
+    size
+}
+
+unsafe fn add_json_size(
+    context: *mut q::JSContext,
+    value: &QuickJsValueAdapter,
+    ancestors: &mut Vec<QuickJsValueAdapter>,
+    size: &mut usize,
+    limit: usize,
+) {
+    if *size > limit {
+        return;
+    }
+    if value.is_big_int() || value.is_symbol() {
+        // not serializable, count the placeholder used by safe_stringify
+        *size += 8;
+        return;
+    }
+    match value.get_js_type() {
+        JsValueType::Undefined | JsValueType::Function => {}
+        JsValueType::Null => *size += 4,
+        JsValueType::Boolean => *size += if value.to_bool() { 4 } else { 5 },
+        JsValueType::I32 => *size += value.to_i32().to_string().len(),
+        JsValueType::F64 => *size += value.to_f64().to_string().len(),
+        JsValueType::String => {
+            let len = objects::get_property(context, value, "length")
+                .map(|len| len.to_i32().max(0) as usize)
+                .unwrap_or(0);
+            if *size + len + 2 > limit {
+                // the utf-8 size is at least the utf-16 length so the limit is exceeded anyway, no need to copy the string
+                *size += len + 2;
+            } else {
+                *size += primitives::to_string(context, value)
+                    .map(|s| json_string_size(s.as_str()))
+                    .unwrap_or(len + 2);
+            }
+        }
+        _ => {
+            if ancestors.contains(value) {
+                *size += "\"[Circular]\"".len();
+                return;
+            }
+            if let Ok(to_json) = get_property_checked(context, value, "toJSON") {
+                if functions::is_function(context, &to_json) {
+                    if let Ok(json_value) =
+                        functions::invoke_member_function(context, value, "toJSON", &[])
+                    {
+                        ancestors.push(value.clone());
+                        add_json_size(context, &json_value, ancestors, size, limit);
+                        ancestors.pop();
+                    }
+                    return;
+                }
+            }
+            ancestors.push(value.clone());
+            if arrays::is_array(context, value) {
+                let len = arrays::get_length(context, value).unwrap_or(0);
+                // brackets and separators
+                *size += 2 + len.saturating_sub(1) as usize;
+                for index in 0..len {
+                    if *size > limit {
+                        break;
+                    }
+                    match arrays::get_element(context, value, index) {
+                        Ok(element) => add_json_size(context, &element, ancestors, size, limit),
+                        Err(_) => *size += 4,
+                    }
+                }
+            } else {
+                // the names are read one by one so a huge object is not enumerated further once the limit is exceeded
+                match objects::get_own_string_property_enum(context, value) {
+                    Ok(names) => {
+                        *size += 2 + (names.len() as usize).saturating_sub(1);
+                        for index in 0..names.len() {
+                            if *size > limit {
+                                break;
+                            }
+                            let name = match names.get_name(index) {
+                                Ok(name) => name,
+                                Err(_) => continue,
+                            };
+                            // the quoted name and the colon
+                            *size += json_string_size(name.as_str()) + 1;
+                            if let Ok(prop) = get_property_checked(context, value, name.as_str()) {
+                                add_json_size(context, &prop, ancestors, size, limit);
+                            }
+                        }
+                    }
+                    Err(_) => *size += 2,
+                }
+            }
+            ancestors.pop();
+        }
+    }
+}
+
+/// the size in bytes of a string as a quoted JSON string, like JSON.stringify the escapes are counted and other chars as utf-8
+fn json_string_size(s: &str) -> usize {
+    2 + s
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' | '\u{8}' | '\u{c}' | '\n' | '\r' | '\t' => 2,
+            c if (c as u32) < 0x20 => 6,
+            c => c.len_utf8(),
+        })
+        .sum::<usize>()
+}
+
+pub(crate) const OUTPUT_LIMITED: &str = "… (output limited)";
+
+struct SafeJsonState {
+    ancestors: Vec<QuickJsValueAdapter>,
+    visited: usize,
+    max_nodes: Option<usize>,
+    max_depth: Option<usize>,
+    limited: bool,
+}
+
+pub(crate) unsafe fn safe_stringify_nodes(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    max_nodes: Option<usize>,
+    max_depth: Option<usize>,
+) -> String {
+    match safe_json(context, input, max_nodes, max_depth) {
+        Ok(Some(json)) => json,
+        Ok(None) => "undefined".to_string(),
+        Err(placeholder) => placeholder,
+    }
+}
+
+/// stringify a value for logging like safe_stringify_nodes but lay out objects and arrays as a tree
+/// every member and element is output on its own line, indented by two spaces per level
+pub(crate) unsafe fn safe_stringify_tree(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    max_nodes: Option<usize>,
+) -> String {
+    match safe_json(context, input, max_nodes, None) {
+        Ok(Some(json)) => indent_json(json.as_str(), "  "),
+        Ok(None) => "undefined".to_string(),
+        Err(placeholder) => placeholder,
+    }
+}
+
+unsafe fn safe_json(
+    context: *mut q::JSContext,
+    input: &QuickJsValueAdapter,
+    max_nodes: Option<usize>,
+    max_depth: Option<usize>,
+) -> Result<Option<String>, String> {
+    let mut state = SafeJsonState {
+        ancestors: vec![],
+        visited: 0,
+        max_nodes,
+        max_depth,
+        limited: false,
+    };
+    to_safe_json(context, input, &mut state)
+}
+
+/// lay out compact JSON with every member and element on its own line, empty objects and arrays stay on one line
+fn indent_json(json: &str, indent: &str) -> String {
+    fn new_line(output: &mut String, indent: &str, depth: usize) {
+        output.push('\n');
+        for _ in 0..depth {
+            output.push_str(indent);
+        }
+    }
+    let mut output = String::with_capacity(json.len() * 2);
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = json.chars().peekable();
+    while let Some(chr) = chars.next() {
+        if in_string {
+            output.push(chr);
+            if escaped {
+                escaped = false;
+            } else if chr == '\\' {
+                escaped = true;
+            } else if chr == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match chr {
+            '"' => {
+                in_string = true;
+                output.push(chr);
+            }
+            '{' | '[' => {
+                output.push(chr);
+                if let Some(close) = chars.next_if(|next| *next == '}' || *next == ']') {
+                    output.push(close);
+                } else {
+                    depth += 1;
+                    new_line(&mut output, indent, depth);
+                }
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                new_line(&mut output, indent, depth);
+                output.push(chr);
+            }
+            ',' => {
+                output.push(chr);
+                new_line(&mut output, indent, depth);
+            }
+            ':' => output.push_str(": "),
+            _ => output.push(chr),
+        }
+    }
+    output
+}
+
+/// convert a value to JSON, returns None for values which are omitted by JSON.stringify (undefined) or Err with a placeholder
+unsafe fn to_safe_json(
+    context: *mut q::JSContext,
+    value: &QuickJsValueAdapter,
+    state: &mut SafeJsonState,
+) -> Result<Option<String>, String> {
+    state.visited += 1;
+    if state
+        .max_nodes
+        .is_some_and(|max_nodes| state.visited > max_nodes)
+    {
+        state.limited = true;
+        return Err(OUTPUT_LIMITED.to_string());
+    }
+    if value.is_big_int() {
+        return Err(format!(
+            "{}n",
+            functions::call_to_string(context, value).map_err(|e| unserializable(context, e))?
+        ));
+    }
+    if value.is_symbol() {
+        return Err("[Symbol]".to_string());
+    }
+    match value.get_js_type() {
+        JsValueType::Undefined => Ok(None),
+        JsValueType::Null => Ok(Some("null".to_string())),
+        JsValueType::Boolean => Ok(Some(value.to_bool().to_string())),
+        JsValueType::I32 => Ok(Some(value.to_i32().to_string())),
+        JsValueType::F64 => {
+            let f = value.to_f64();
+            if f.is_finite() {
+                Ok(Some(f.to_string()))
+            } else {
+                Ok(Some("null".to_string()))
+            }
+        }
+        JsValueType::String => {
+            let s =
+                primitives::to_string(context, value).map_err(|e| unserializable(context, e))?;
+            Ok(Some(quote(s.as_str())))
+        }
+        JsValueType::Function => {
+            let name = get_property_checked(context, value, "name")
+                .ok()
+                .and_then(|name| name.to_string().ok())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "anonymous".to_string());
+            Err(format!("[Function: {name}]"))
+        }
+        _ => {
+            if state.ancestors.contains(value) {
+                return Err("[Circular]".to_string());
+            }
+            let to_json = get_property_checked(context, value, "toJSON")
+                .map_err(|e| unserializable(context, e))?;
+            if functions::is_function(context, &to_json) {
+                let json_value = functions::invoke_member_function(context, value, "toJSON", &[])
+                    .map_err(|e| unserializable(context, e))?;
+                state.ancestors.push(value.clone());
+                let res = to_safe_json(context, &json_value, state);
+                state.ancestors.pop();
+                return res;
+            }
+            let is_array = arrays::is_array(context, value);
+            if state
+                .max_depth
+                .is_some_and(|max_depth| state.ancestors.len() > max_depth)
+            {
+                return Err(if is_array { "[Array]" } else { "[Object]" }.to_string());
+            }
+
+            state.ancestors.push(value.clone());
+            let res = if is_array {
+                safe_array_json(context, value, state)
+            } else {
+                safe_object_json(context, value, state)
+            };
+            state.ancestors.pop();
+            res.map(Some)
+        }
+    }
+}
+
+unsafe fn safe_array_json(
+    context: *mut q::JSContext,
+    array_ref: &QuickJsValueAdapter,
+    state: &mut SafeJsonState,
+) -> Result<String, String> {
+    let len = arrays::get_length(context, array_ref).map_err(|e| unserializable(context, e))?;
+    let mut elements = vec![];
+    for index in 0..len {
+        if state.limited {
+            break;
+        }
+        let element = match arrays::get_element(context, array_ref, index) {
+            Ok(element) => nested_json(to_safe_json(context, &element, state)),
+            Err(e) => Some(quote(unserializable(context, e).as_str())),
+        };
+        elements.push(element.unwrap_or_else(|| "null".to_string()));
+    }
+    Ok(format!("[{}]", elements.join(",")))
+}
+
+unsafe fn safe_object_json(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+    state: &mut SafeJsonState,
+) -> Result<String, String> {
+    let names = objects::get_own_string_property_names(context, obj_ref)
+        .map_err(|e| unserializable(context, e))?;
+    let mut members = vec![];
+    for name in names {
+        if state.limited {
+            break;
+        }
+        let member = match get_property_checked(context, obj_ref, name.as_str()) {
+            Ok(prop) => nested_json(to_safe_json(context, &prop, state)),
+            Err(e) => Some(quote(unserializable(context, e).as_str())),
+        };
+        if let Some(member) = member {
+            members.push(format!("{}:{}", quote(name.as_str()), member));
+        }
+    }
+    Ok(format!("{{{}}}", members.join(",")))
+}
+
+/// a nested placeholder is output as a JSON string
+fn nested_json(res: Result<Option<String>, String>) -> Option<String> {
+    match res {
+        Ok(json) => json,
+        Err(placeholder) => Some(quote(placeholder.as_str())),
+    }
+}
+
+unsafe fn get_property_checked(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+    prop_name: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let prop_ref = objects::get_property(context, obj_ref, prop_name)?;
+    if prop_ref.is_exception() {
+        Err(QuickJsRealmAdapter::get_exception(context)
+            .unwrap_or_else(|| JsError::new_str("Could not get object property")))
+    } else {
+        Ok(prop_ref)
+    }
+}
+
+/// create the placeholder for a failure, this also clears the exception which may still be pending
+unsafe fn unserializable(context: *mut q::JSContext, err: JsError) -> String {
+    let err = QuickJsRealmAdapter::get_exception(context).unwrap_or(err);
+    format!("[Unserializable: {}]", err.get_message())
+}
+
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::Script;
-    use crate::quickjs_utils::json::parse_q;
-    use crate::quickjs_utils::{get_global_q, json, objects, primitives};
+    use crate::quickjs_utils::json::{
+        estimate_json_size_q, parse_q, parse_with_reviver_function_q, parse_with_reviver_q,
+        safe_stringify_depth_q, safe_stringify_limited_q, safe_stringify_q,
+        stringify_with_options_q, JsonIndent, JsonReplacer, StringifyOptions,
+    };
+    use crate::quickjs_utils::{dates, get_global_q, json, objects, primitives};
     use crate::values::JsValueFacade;
     use std::collections::HashMap;
 
@@ -261,4 +973,229 @@ pub mod tests {
         let jsv = func_res.ok().expect("got err");
         assert_eq!(jsv.stringify(), "String: hello value");
     }
+
+    #[test]
+    fn test_safe_stringify() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let stringify_script = |code: &str| {
+                let value = q_ctx
+                    .eval(Script::new("test_safe_stringify.es", code))
+                    .expect("script failed");
+                safe_stringify_q(q_ctx, &value)
+            };
+
+            assert_eq!(
+                stringify_script("let cyclic = {name: 'c', list: [1]}; cyclic.self = cyclic; cyclic.list.push(cyclic); cyclic;"),
+                r#"{"name":"c","list":[1,"[Circular]"],"self":"[Circular]"}"#
+            );
+            assert_eq!(stringify_script("123n;"), "123n");
+            assert_eq!(
+                stringify_script("({big: 123n, u: undefined, f: function named() {}});"),
+                r#"{"big":"123n","f":"[Function: named]"}"#
+            );
+            assert_eq!(
+                stringify_script("({ok: 1, get bad() {throw Error('getter failed');}});"),
+                r#"{"ok":1,"bad":"[Unserializable: getter failed]"}"#
+            );
+            assert_eq!(
+                stringify_script("({when: new Date(0)});"),
+                r#"{"when":"1970-01-01T00:00:00.000Z"}"#
+            );
+            // no exception should be left pending
+            assert_eq!(stringify_script("'still ' + 'working';"), r#""still working""#);
+        });
+    }
+
+    #[test]
+    fn test_parse_with_reviver() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let obj_ref = parse_with_reviver_q(
+                q_ctx,
+                r#"{"name": "release", "when": "2023-01-02T03:04:05.678Z", "drop": 1}"#,
+                Box::new(|realm, key, value| match key {
+                    "when" => dates::from_iso_string_q(realm, value.to_string()?.as_str()),
+                    "drop" => realm.create_undefined(),
+                    _ => Ok(value.clone()),
+                }),
+            )
+            .expect("parse failed");
+            let when = objects::get_property_q(q_ctx, &obj_ref, "when").expect("no when");
+            assert!(dates::is_date_q(q_ctx, &when));
+            assert_eq!(
+                dates::get_time_q(q_ctx, &when).expect("no time"),
+                1672628645678.0
+            );
+            let global = get_global_q(q_ctx);
+            objects::set_property_q(q_ctx, &global, "revived", &obj_ref).expect("set failed");
+            q_ctx
+                .eval(Script::new(
+                    "test_parse_with_reviver.es",
+                    "[revived.name, revived.when instanceof Date, 'drop' in revived].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "release,true,false");
+    }
+
+    #[test]
+    fn test_parse_with_reviver_function() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let reviver = q_ctx
+                .eval(Script::new(
+                    "test_parse_with_reviver_function.es",
+                    r#"
+                globalThis.visited = [];
+                (function(key, value) {
+                    visited.push(key);
+                    return typeof value === 'number' ? value * 2 : value;
+                });
+            "#,
+                ))
+                .expect("script failed");
+            let obj_ref = parse_with_reviver_function_q(
+                q_ctx,
+                r#"{"a": 1, "b": {"c": 2.5, "d": [3, "x"]}}"#,
+                &reviver,
+            )
+            .expect("parse failed");
+            let global = get_global_q(q_ctx);
+            objects::set_property_q(q_ctx, &global, "revived", &obj_ref).expect("set failed");
+            assert!(parse_with_reviver_function_q(q_ctx, "{}", &primitives::from_i32(1)).is_err());
+            q_ctx
+                .eval(Script::new(
+                    "test_parse_with_reviver_function2.es",
+                    "JSON.stringify(revived) + '|' + visited.join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        // members are revived before the object which holds them, the root is revived last with an empty key
+        assert_eq!(res, r#"{"a":2,"b":{"c":5,"d":[6,"x"]}}|a,c,0,1,d,b,"#);
+    }
+
+    #[test]
+    fn test_stringify_with_options() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let obj_ref = q_ctx
+                .eval(Script::new(
+                    "test_stringify_with_options.es",
+                    "({id: 1, password: 'secret', items: [{id: 2, password: 'x', name: 'b'}]});",
+                ))
+                .expect("script failed");
+            let stringify = |options: &StringifyOptions| {
+                stringify_with_options_q(q_ctx, &obj_ref, options)
+                    .expect("stringify failed")
+                    .to_string()
+                    .expect("not a string")
+            };
+
+            // only the listed keys are serialized, at every level
+            let keys = JsonReplacer::Keys(vec!["id".to_string(), "items".to_string()]);
+            assert_eq!(
+                stringify(&StringifyOptions::new().replacer(keys)),
+                r#"{"id":1,"items":[{"id":2}]}"#
+            );
+
+            let pretty = stringify(&StringifyOptions::new().indent(JsonIndent::Spaces(2)));
+            assert_eq!(pretty.lines().count(), 11);
+            assert!(pretty.starts_with("{\n  \"id\": 1,\n  \"password\": \"secret\",\n  \"items\": [\n    {\n      \"id\": 2,"));
+
+            let tabbed = stringify(
+                &StringifyOptions::new()
+                    .replacer(JsonReplacer::Keys(vec!["id".to_string()]))
+                    .indent(JsonIndent::Text("\t".to_string())),
+            );
+            assert_eq!(tabbed, "{\n\t\"id\": 1\n}");
+
+            let replacer = q_ctx
+                .eval(Script::new(
+                    "test_stringify_with_options2.es",
+                    "(function(key, value) { return key === 'password' ? '***' : value; });",
+                ))
+                .expect("script failed");
+            assert_eq!(
+                stringify(&StringifyOptions::new().replacer(JsonReplacer::Function(replacer))),
+                r#"{"id":1,"password":"***","items":[{"id":2,"password":"***","name":"b"}]}"#
+            );
+        });
+    }
+
+    #[test]
+    fn test_safe_stringify_limited() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let value = q_ctx
+                .eval(Script::new(
+                    "test_safe_stringify_limited.es",
+                    "({a: [1, 2], b: {c: 3, d: 4}});",
+                ))
+                .expect("script failed");
+            // 7 nodes: the object, a, 1, 2, b, c and d
+            assert_eq!(
+                safe_stringify_limited_q(q_ctx, &value, 7),
+                r#"{"a":[1,2],"b":{"c":3,"d":4}}"#
+            );
+            assert_eq!(
+                safe_stringify_limited_q(q_ctx, &value, 3),
+                r#"{"a":[1,"… (output limited)"]}"#
+            );
+            assert_eq!(
+                safe_stringify_limited_q(q_ctx, &value, 0),
+                "… (output limited)"
+            );
+        });
+    }
+
+    #[test]
+    fn test_safe_stringify_depth() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let value = q_ctx
+                .eval(Script::new(
+                    "test_safe_stringify_depth.es",
+                    "let o = {a: {b: {c: {d: 1}}, l: [[1]]}}; o.a.me = o; o;",
+                ))
+                .expect("script failed");
+            assert_eq!(
+                safe_stringify_depth_q(q_ctx, &value, 2),
+                r#"{"a":{"b":{"c":"[Object]"},"l":["[Array]"],"me":"[Circular]"}}"#
+            );
+            assert_eq!(
+                safe_stringify_depth_q(q_ctx, &value, 0),
+                r#"{"a":"[Object]"}"#
+            );
+        });
+    }
+
+    #[test]
+    fn test_estimate_json_size() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let value = q_ctx
+                .eval(Script::new(
+                    "test_estimate_json_size.es",
+                    "let o = {s: 'abc', e: 'a\"b\\\\c\\n\\u0001é€', 'kéy': 1, n: 12.5, b: false, z: null, l: [1, [2]], d: {toJSON() {return 'day';}}}; o.me = o; o;",
+                ))
+                .expect("script failed");
+            let json = safe_stringify_q(q_ctx, &value);
+            assert_eq!(estimate_json_size_q(q_ctx, &value, 1024), json.len());
+            // the walk stops soon after the limit is exceeded
+            let limited = estimate_json_size_q(q_ctx, &value, 10);
+            assert!(limited > 10 && limited < json.len());
+        });
+    }
 }
@@ -6,7 +6,10 @@ use crate::quickjs_utils::objects::{construct_object, is_instance_of_by_name};
 use crate::quickjs_utils::{arrays, functions, get_constructor, iterators, objects, primitives};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use crate::values::JsValueFacade;
 use libquickjs_sys as q;
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FromIterator;
 
 /// create new instance of Map
 /// # Example
@@ -384,3 +387,156 @@ pub unsafe fn entries<C: Fn(QuickJsValueAdapter, QuickJsValueAdapter) -> Result<
         consumer_producer(key, value)
     })
 }
+
+/// convert a Map with string keys to a HashMap
+/// an error is returned if the Map contains a key which is not a string
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::maps::to_hashmap_q;
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///    let q_ctx = q_js_rt.get_main_realm();
+///    let my_map = q_ctx.eval(Script::new("to_hashmap.js", "new Map([['a', 1], ['b', 2]]);")).ok().unwrap();
+///    let hash_map = to_hashmap_q(q_ctx, &my_map).ok().unwrap();
+///    assert_eq!(hash_map.get("b").unwrap().get_i32(), 2);
+/// });
+/// ```
+pub fn to_hashmap_q(
+    q_ctx: &QuickJsRealmAdapter,
+    map: &QuickJsValueAdapter,
+) -> Result<HashMap<String, JsValueFacade>, JsError> {
+    to_string_keyed_collection(q_ctx, map)
+}
+
+/// convert a Map with string keys to a HashMap
+/// an error is returned if the Map contains a key which is not a string
+/// # Safety
+/// please ensure the passed JSContext is still valid
+pub unsafe fn to_hashmap(
+    ctx: *mut q::JSContext,
+    map: &QuickJsValueAdapter,
+) -> Result<HashMap<String, JsValueFacade>, JsError> {
+    QuickJsRealmAdapter::with_context(ctx, |q_ctx| to_hashmap_q(q_ctx, map))
+}
+
+/// convert a Map with string keys to a BTreeMap
+/// an error is returned if the Map contains a key which is not a string
+pub fn to_btreemap_q(
+    q_ctx: &QuickJsRealmAdapter,
+    map: &QuickJsValueAdapter,
+) -> Result<BTreeMap<String, JsValueFacade>, JsError> {
+    to_string_keyed_collection(q_ctx, map)
+}
+
+/// convert a Map with string keys to a BTreeMap
+/// an error is returned if the Map contains a key which is not a string
+/// # Safety
+/// please ensure the passed JSContext is still valid
+pub unsafe fn to_btreemap(
+    ctx: *mut q::JSContext,
+    map: &QuickJsValueAdapter,
+) -> Result<BTreeMap<String, JsValueFacade>, JsError> {
+    QuickJsRealmAdapter::with_context(ctx, |q_ctx| to_btreemap_q(q_ctx, map))
+}
+
+fn to_string_keyed_collection<M: FromIterator<(String, JsValueFacade)>>(
+    q_ctx: &QuickJsRealmAdapter,
+    map: &QuickJsValueAdapter,
+) -> Result<M, JsError> {
+    if !is_map_q(q_ctx, map)? {
+        return Err(JsError::new_str("value is not a Map"));
+    }
+    let entries = entries_q(q_ctx, map, |key, value| {
+        if !key.is_string() {
+            return Err(JsError::new_string(format!(
+                "Map key is not a String but a {}",
+                key.get_js_type()
+            )));
+        }
+        Ok((key.to_string()?, q_ctx.to_js_value_facade(&value)?))
+    })?;
+    Ok(entries.into_iter().collect())
+}
+
+/// create a new Map from a HashMap or BTreeMap (or any other iterator of String/JsValueFacade pairs)
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::maps::{from_hashmap_q, size_q};
+/// use quickjs_runtime::values::JsValueFacade;
+/// use std::collections::HashMap;
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///    let q_ctx = q_js_rt.get_main_realm();
+///    let mut hash_map = HashMap::new();
+///    hash_map.insert("a".to_string(), JsValueFacade::new_i32(1));
+///    let my_map = from_hashmap_q(q_ctx, hash_map).ok().unwrap();
+///    assert_eq!(size_q(q_ctx, &my_map).ok().unwrap(), 1);
+/// });
+/// ```
+pub fn from_hashmap_q<M: IntoIterator<Item = (String, JsValueFacade)>>(
+    q_ctx: &QuickJsRealmAdapter,
+    map: M,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let js_map = new_map_q(q_ctx)?;
+    for (key, value) in map {
+        let key_ref = q_ctx.create_string(key.as_str())?;
+        let value_ref = q_ctx.from_js_value_facade(value)?;
+        set_q(q_ctx, &js_map, key_ref, value_ref)?;
+    }
+    Ok(js_map)
+}
+
+/// create a new Map from a HashMap or BTreeMap (or any other iterator of String/JsValueFacade pairs)
+/// # Safety
+/// please ensure the passed JSContext is still valid
+pub unsafe fn from_hashmap<M: IntoIterator<Item = (String, JsValueFacade)>>(
+    ctx: *mut q::JSContext,
+    map: M,
+) -> Result<QuickJsValueAdapter, JsError> {
+    QuickJsRealmAdapter::with_context(ctx, |q_ctx| from_hashmap_q(q_ctx, map))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::maps::{from_hashmap_q, to_btreemap_q, to_hashmap_q};
+    use crate::values::JsValueFacade;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hashmap_round_trip() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let (round_tripped, keys, err) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let mut config = HashMap::new();
+            config.insert("name".to_string(), JsValueFacade::new_str("test"));
+            config.insert("retries".to_string(), JsValueFacade::new_i32(3));
+            config.insert("verbose".to_string(), JsValueFacade::new_bool(true));
+
+            let js_map = from_hashmap_q(q_ctx, config).expect("from_hashmap failed");
+            let round_tripped = to_hashmap_q(q_ctx, &js_map).expect("to_hashmap failed");
+            let keys: Vec<String> = to_btreemap_q(q_ctx, &js_map)
+                .expect("to_btreemap failed")
+                .into_keys()
+                .collect();
+
+            let bad_map = q_ctx
+                .eval(Script::new("bad_map.js", "new Map([[1, 'one']]);"))
+                .expect("script failed");
+            let err = to_hashmap_q(q_ctx, &bad_map).expect_err("numeric key did not fail");
+            (round_tripped, keys, err.get_message().to_string())
+        });
+        assert_eq!(round_tripped.len(), 3);
+        assert_eq!(round_tripped.get("name").unwrap().get_str(), "test");
+        assert_eq!(round_tripped.get("retries").unwrap().get_i32(), 3);
+        assert!(round_tripped.get("verbose").unwrap().get_bool());
+        assert_eq!(keys, vec!["name", "retries", "verbose"]);
+        assert!(err.contains("not a String"));
+    }
+}
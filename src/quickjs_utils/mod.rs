@@ -2,6 +2,7 @@
 
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 
+pub mod allocators;
 pub mod arrays;
 pub mod atoms;
 pub mod bigints;
@@ -18,7 +19,11 @@ pub mod objects;
 pub mod primitives;
 pub mod promises;
 pub mod properties;
+pub mod proxies;
+pub mod regexp;
+pub mod serde;
 pub mod sets;
+pub mod structured_clone;
 pub mod typedarrays;
 
 use crate::jsutils::JsError;
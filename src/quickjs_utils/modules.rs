@@ -61,6 +61,35 @@ pub fn get_module_def(value: &QuickJsValueAdapter) -> *mut q::JSModuleDef {
     unsafe { value.borrow_value().u.ptr as *mut q::JSModuleDef }
 }
 
+/// get the namespace object of a module, the module should be evaluated before its exports are read
+/// # Safety
+/// please ensure the corresponding QuickJSContext is still valid
+pub unsafe fn get_module_namespace(
+    context: *mut q::JSContext,
+    module: *mut q::JSModuleDef,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let value_raw = q::JS_GetModuleNamespace(context, module);
+    let ret = QuickJsValueAdapter::new(
+        context,
+        value_raw,
+        false,
+        true,
+        "get_module_namespace result",
+    );
+    if ret.is_exception() {
+        let ex_opt = QuickJsRealmAdapter::get_exception(context);
+        if let Some(ex) = ex_opt {
+            Err(ex)
+        } else {
+            Err(JsError::new_str(
+                "get_module_namespace failed and could not get exception",
+            ))
+        }
+    } else {
+        Ok(ret)
+    }
+}
+
 #[allow(dead_code)]
 pub fn set_module_loader(q_js_rt: &QuickJsRuntimeAdapter) {
     log::trace!("setting up module loader");
@@ -2,11 +2,12 @@
 
 use crate::jsutils::JsError;
 use crate::quickjs_utils::properties::JSPropertyEnumRef;
-use crate::quickjs_utils::{atoms, functions, get_constructor, get_global};
+use crate::quickjs_utils::{atoms, functions, get_constructor, get_global, new_null, primitives};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsruntimeadapter::{make_cstring, QuickJsRuntimeAdapter};
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use libquickjs_sys as q;
+use std::collections::HashSet;
 
 /// get a namespace object
 /// this is used to get nested object properties which are used as namespaces
@@ -116,6 +117,34 @@ pub unsafe fn create_object(context: *mut q::JSContext) -> Result<QuickJsValueAd
     Ok(obj_ref)
 }
 
+/// create a new object without a prototype, e.g. `let obj = Object.create(null);`
+/// such an object can be used as a dictionary as its keys can not collide with the members of Object.prototype
+pub fn create_object_null_proto_q(
+    q_ctx: &QuickJsRealmAdapter,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { create_object_null_proto(q_ctx.context) }
+}
+
+/// create a new object without a prototype, e.g. `let obj = Object.create(null);`
+/// # Safety
+/// when passing a context ptr please be sure that the corresponding QuickJsContext is still active
+pub unsafe fn create_object_null_proto(
+    context: *mut q::JSContext,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let obj = q::JS_NewObjectProto(context, new_null());
+    let obj_ref = QuickJsValueAdapter::new(
+        context,
+        obj,
+        false,
+        true,
+        "objects::create_object_null_proto",
+    );
+    if obj_ref.is_exception() {
+        return Err(JsError::new_str("Could not create object"));
+    }
+    Ok(obj_ref)
+}
+
 /// set a property in an object, like `obj[propName] = val;`
 pub fn set_property_q(
     q_ctx: &QuickJsRealmAdapter,
@@ -382,11 +411,19 @@ pub fn get_own_property_names_q(
 pub unsafe fn get_own_property_names(
     context: *mut q::JSContext,
     obj_ref: &QuickJsValueAdapter,
+) -> Result<JSPropertyEnumRef, JsError> {
+    let flags = (q::JS_GPN_STRING_MASK | q::JS_GPN_SYMBOL_MASK | q::JS_GPN_ENUM_ONLY) as i32;
+    get_own_property_names_with_flags(context, obj_ref, flags)
+}
+
+unsafe fn get_own_property_names_with_flags(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+    flags: i32,
 ) -> Result<JSPropertyEnumRef, JsError> {
     let mut properties: *mut q::JSPropertyEnum = std::ptr::null_mut();
     let mut count: u32 = 0;
 
-    let flags = (q::JS_GPN_STRING_MASK | q::JS_GPN_SYMBOL_MASK | q::JS_GPN_ENUM_ONLY) as i32;
     let ret = q::JS_GetOwnPropertyNames(
         context,
         &mut properties,
@@ -402,6 +439,30 @@ pub unsafe fn get_own_property_names(
     Ok(enum_ref)
 }
 
+/// get the enumerable own string keyed properties of an object, like `Object.keys(obj)`, the names are converted when read from the enum
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub(crate) unsafe fn get_own_string_property_enum(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<JSPropertyEnumRef, JsError> {
+    let flags = (q::JS_GPN_STRING_MASK | q::JS_GPN_ENUM_ONLY) as i32;
+    get_own_property_names_with_flags(context, obj_ref, flags)
+}
+
+/// get the names of the enumerable own string keyed properties of an object, like `Object.keys(obj)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub(crate) unsafe fn get_own_string_property_names(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<Vec<String>, JsError> {
+    let enum_ref = get_own_string_property_enum(context, obj_ref)?;
+    (0..enum_ref.len())
+        .map(|index| enum_ref.get_name(index))
+        .collect()
+}
+
 /// get the names of all properties of an object
 pub fn get_property_names_q(
     q_ctx: &QuickJsRealmAdapter,
@@ -429,6 +490,69 @@ pub unsafe fn get_property_names(
     Ok(names)
 }
 
+/// get the enumerable own string keyed properties of an object as key/value pairs, like `Object.entries(obj)`
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::objects::get_entries_q;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let q_ctx = q_js_rt.get_main_realm();
+///     let obj_ref = q_ctx.eval(Script::new("get_entries.es", "({a: 1, b: 2});")).ok().expect("script failed");
+///     let entries = get_entries_q(q_ctx, &obj_ref).ok().expect("could not get entries");
+///     assert_eq!(entries.len(), 2);
+///     assert_eq!(entries[0].0, "a");
+/// })
+/// ```
+pub fn get_entries_q(
+    q_ctx: &QuickJsRealmAdapter,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<Vec<(String, QuickJsValueAdapter)>, JsError> {
+    unsafe { get_entries(q_ctx.context, obj_ref) }
+}
+
+/// get the enumerable own string keyed properties of an object as key/value pairs, like `Object.entries(obj)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn get_entries(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<Vec<(String, QuickJsValueAdapter)>, JsError> {
+    let flags = (q::JS_GPN_STRING_MASK | q::JS_GPN_ENUM_ONLY) as i32;
+    let enum_ref = get_own_property_names_with_flags(context, obj_ref, flags)?;
+
+    let mut entries = vec![];
+
+    for index in 0..enum_ref.len() {
+        let atom = enum_ref.get_atom_raw(index) as q::JSAtom;
+        let prop_name = atoms::to_string2(context, &atom)?;
+
+        let raw_value = q::JS_GetPropertyInternal(
+            context,
+            *obj_ref.borrow_value(),
+            atom,
+            *obj_ref.borrow_value(),
+            0,
+        );
+        let prop_val_ref = QuickJsValueAdapter::new(
+            context,
+            raw_value,
+            false,
+            true,
+            "objects::get_entries raw_value",
+        );
+        if prop_val_ref.is_exception() {
+            return Err(QuickJsRealmAdapter::get_exception(context)
+                .unwrap_or_else(|| JsError::new_str("Could not get object property")));
+        }
+
+        entries.push((prop_name, prop_val_ref));
+    }
+
+    Ok(entries)
+}
+
 pub fn traverse_properties_q<V, R>(
     q_ctx: &QuickJsRealmAdapter,
     obj_ref: &QuickJsValueAdapter,
@@ -631,15 +755,189 @@ pub unsafe fn is_instance_of_by_name(
     }
 }
 
+/// freeze an object, like `Object.freeze(obj)`
+pub fn freeze_q(q_ctx: &QuickJsRealmAdapter, obj_ref: &QuickJsValueAdapter) -> Result<(), JsError> {
+    unsafe { freeze(q_ctx.context, obj_ref) }
+}
+
+/// freeze an object, like `Object.freeze(obj)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn freeze(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<(), JsError> {
+    if !obj_ref.is_object() {
+        // primitives are immutable anyway
+        return Ok(());
+    }
+    let object_constructor = get_constructor(context, "Object")?;
+    functions::invoke_member_function(context, &object_constructor, "freeze", &[obj_ref.clone()])?;
+    Ok(())
+}
+
+/// freeze an object and all objects and arrays which are reachable through its enumerable own properties
+pub fn deep_freeze_q(
+    q_ctx: &QuickJsRealmAdapter,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<(), JsError> {
+    unsafe { deep_freeze(q_ctx.context, obj_ref) }
+}
+
+/// freeze an object and all objects and arrays which are reachable through its enumerable own properties
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn deep_freeze(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<(), JsError> {
+    let mut visited = HashSet::new();
+    deep_freeze_visit(context, obj_ref, &mut visited)
+}
+
+unsafe fn deep_freeze_visit(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+    visited: &mut HashSet<QuickJsValueAdapter>,
+) -> Result<(), JsError> {
+    if !obj_ref.is_object() || !visited.insert(obj_ref.clone()) {
+        return Ok(());
+    }
+    for (_name, value) in get_entries(context, obj_ref)? {
+        deep_freeze_visit(context, &value, visited)?;
+    }
+    freeze(context, obj_ref)
+}
+
+/// check if an object is frozen, like `Object.isFrozen(obj)`
+pub fn is_frozen_q(
+    q_ctx: &QuickJsRealmAdapter,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<bool, JsError> {
+    unsafe { is_frozen(q_ctx.context, obj_ref) }
+}
+
+/// check if an object is frozen, like `Object.isFrozen(obj)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn is_frozen(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+) -> Result<bool, JsError> {
+    let object_constructor = get_constructor(context, "Object")?;
+    let res = functions::invoke_member_function(
+        context,
+        &object_constructor,
+        "isFrozen",
+        &[obj_ref.clone()],
+    )?;
+    primitives::to_bool(&res)
+}
+
 #[cfg(test)]
 pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
     use crate::facades::tests::init_test_rt;
+    use crate::jsutils::modules::NativeModuleLoader;
     use crate::jsutils::Script;
     use crate::quickjs_utils::objects::{
-        create_object_q, get_property_names_q, get_property_q, set_property_q,
+        create_object_null_proto_q, create_object_q, freeze_q, get_property_names_q,
+        get_property_q, is_frozen_q, set_property_q,
     };
     use crate::quickjs_utils::primitives::{from_i32, to_i32};
     use crate::quickjs_utils::{get_global_q, primitives};
+    use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+    use crate::quickjsvalueadapter::QuickJsValueAdapter;
+
+    struct ConstantsModuleLoader {}
+
+    impl NativeModuleLoader for ConstantsModuleLoader {
+        fn has_module(&self, _realm: &QuickJsRealmAdapter, module_name: &str) -> bool {
+            module_name.eq("constants")
+        }
+
+        fn get_module_export_names(
+            &self,
+            _realm: &QuickJsRealmAdapter,
+            _module_name: &str,
+        ) -> Vec<&str> {
+            vec!["config"]
+        }
+
+        fn get_module_exports(
+            &self,
+            realm: &QuickJsRealmAdapter,
+            _module_name: &str,
+        ) -> Vec<(&str, QuickJsValueAdapter)> {
+            let config = create_object_q(realm).expect("could not create obj");
+            set_property_q(realm, &config, "maxItems", &from_i32(100)).expect("set prop failed");
+            vec![("config", config)]
+        }
+
+        fn get_frozen_export_names(
+            &self,
+            _realm: &QuickJsRealmAdapter,
+            _module_name: &str,
+        ) -> Vec<&str> {
+            vec!["config"]
+        }
+    }
+
+    #[test]
+    fn test_create_object_null_proto() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let dict = create_object_null_proto_q(realm).expect("could not create object");
+            set_property_q(
+                realm,
+                &dict,
+                "constructor",
+                &primitives::from_string_q(realm, "mine").unwrap(),
+            )
+            .expect("could not set prop");
+            set_property_q(realm, &get_global_q(realm), "dict", &dict).expect("could not set dict");
+            realm
+                .eval(Script::new(
+                    "test_create_object_null_proto.js",
+                    "[Object.getPrototypeOf(dict) === null, dict.constructor, dict.constructor === Object.prototype.constructor, 'toString' in dict].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "true,mine,false,false");
+    }
+
+    #[test]
+    fn test_frozen_module_export() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .native_module_loader(ConstantsModuleLoader {})
+            .build();
+
+        // modules are always strict so mutating a frozen object throws
+        let res = rt.eval_module_sync(
+            None,
+            Script::new(
+                "test_frozen.mes",
+                "import {config} from 'constants';\nif (!Object.isFrozen(config) || config.maxItems !== 100) {throw Error('not frozen');}\nconfig.maxItems = 5;",
+            ),
+        );
+        let err = res.expect_err("mutating a frozen export should fail");
+        assert_eq!(err.get_name(), "TypeError");
+    }
+
+    #[test]
+    fn test_freeze() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let obj = create_object_q(q_ctx).expect("a");
+            assert!(!is_frozen_q(q_ctx, &obj).expect("b"));
+            freeze_q(q_ctx, &obj).expect("c");
+            assert!(is_frozen_q(q_ctx, &obj).expect("d"));
+        });
+    }
 
     #[test]
     fn test_get_refs() {
@@ -734,6 +1032,31 @@ pub mod tests {
         log::info!("< test_propnames");
     }
 
+    #[test]
+    fn test_entries() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+
+            let obj_ref = q_ctx
+                .eval(Script::new(
+                    "test_entries.es",
+                    "let o = {one: 1, two: 'b', three: true}; Object.defineProperty(o, 'hidden', {value: 4, enumerable: false}); o[Symbol('sym')] = 5; o;",
+                ))
+                .expect("could not get test obj");
+
+            let entries = obj_ref.object_entries().expect("could not get entries");
+            assert_eq!(entries.len(), 3);
+
+            assert_eq!(entries[0].0, "one");
+            assert_eq!(entries[0].1.to_i32(), 1);
+            assert_eq!(entries[1].0, "two");
+            assert_eq!(entries[1].1.to_str().expect("not a str"), "b");
+            assert_eq!(entries[2].0, "three");
+            assert!(entries[2].1.to_bool());
+        });
+    }
+
     #[test]
     fn test_set_prop() {
         log::info!("> test_set_prop");
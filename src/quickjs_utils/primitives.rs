@@ -1,4 +1,5 @@
 use crate::jsutils::JsError;
+use crate::quickjs_utils::{functions, get_constructor};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use core::ptr;
@@ -81,9 +82,10 @@ pub unsafe fn to_string(
         ));
     }
 
-    let cstr = std::ffi::CStr::from_ptr(ptr);
+    // QuickJS tells us the length so there is no need to scan for the terminating nul, the bytes are copied exactly once
+    let bytes = std::slice::from_raw_parts(ptr as *const u8, len as usize);
 
-    let s = cstr.to_string_lossy().into_owned();
+    let s = String::from_utf8_lossy(bytes).into_owned();
 
     // Free the c string.
     q::JS_FreeCString(context, ptr);
@@ -144,11 +146,77 @@ pub unsafe fn from_string(
     Ok(ret)
 }
 
+/// create a new unique Symbol, like `Symbol(description)`
+pub fn new_symbol_q(
+    q_ctx: &QuickJsRealmAdapter,
+    description: Option<&str>,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { new_symbol(q_ctx.context, description) }
+}
+
+/// create a new unique Symbol, like `Symbol(description)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn new_symbol(
+    context: *mut q::JSContext,
+    description: Option<&str>,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let symbol_function = get_constructor(context, "Symbol")?;
+    let args = match description {
+        Some(d) => vec![from_string(context, d)?],
+        None => vec![],
+    };
+    functions::call_function(context, &symbol_function, &args, None)
+}
+
+/// get a Symbol from the global symbol registry, like `Symbol.for(key)`
+pub fn symbol_for_q(
+    q_ctx: &QuickJsRealmAdapter,
+    key: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { symbol_for(q_ctx.context, key) }
+}
+
+/// get a Symbol from the global symbol registry, like `Symbol.for(key)`
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn symbol_for(
+    context: *mut q::JSContext,
+    key: &str,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let symbol_function = get_constructor(context, "Symbol")?;
+    functions::invoke_member_function(
+        context,
+        &symbol_function,
+        "for",
+        &[from_string(context, key)?],
+    )
+}
+
 #[cfg(test)]
 pub mod tests {
 
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::Script;
+    use crate::quickjs_utils::primitives::{new_symbol_q, symbol_for_q};
+
+    #[test]
+    fn test_symbols() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+
+            let x1 = symbol_for_q(q_ctx, "x").expect("could not get symbol x");
+            let x2 = symbol_for_q(q_ctx, "x").expect("could not get symbol x");
+            assert!(x1.is_symbol());
+            assert!(x1 == x2);
+
+            let plain = new_symbol_q(q_ctx, Some("x")).expect("could not create symbol");
+            assert!(plain.is_symbol());
+            assert!(plain != x1);
+            assert_eq!(plain.type_of(), "symbol");
+        });
+    }
 
     #[tokio::test]
     async fn test_emoji() {
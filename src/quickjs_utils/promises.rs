@@ -1,7 +1,7 @@
 use crate::jsutils::JsError;
 use crate::quickjs_utils;
-use crate::quickjs_utils::functions;
 use crate::quickjs_utils::objects::is_instance_of_by_name;
+use crate::quickjs_utils::{errors, functions};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
@@ -224,16 +224,152 @@ pub unsafe fn add_promise_reactions(
     Ok(())
 }
 
+/// get the reason a Promise was rejected with without adding a reaction to it
+/// returns None if the Promise is still pending or was fulfilled
+/// if mark_handled is true a no-op catch reaction is added so the rejection is no longer reported as unhandled
+pub fn get_rejection_reason_q(
+    q_ctx: &QuickJsRealmAdapter,
+    promise_obj_ref: &QuickJsValueAdapter,
+    mark_handled: bool,
+) -> Result<Option<QuickJsValueAdapter>, JsError> {
+    unsafe { get_rejection_reason(q_ctx.context, promise_obj_ref, mark_handled) }
+}
+
+/// get the reason a Promise was rejected with without adding a reaction to it
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid
+pub unsafe fn get_rejection_reason(
+    context: *mut q::JSContext,
+    promise_obj_ref: &QuickJsValueAdapter,
+    mark_handled: bool,
+) -> Result<Option<QuickJsValueAdapter>, JsError> {
+    if !is_promise(context, promise_obj_ref) {
+        return Err(JsError::new_str("value is not a Promise"));
+    }
+    let state = q::JS_PromiseState(context, *promise_obj_ref.borrow_value());
+    if state != q::JSPromiseStateEnum_JS_PROMISE_REJECTED {
+        return Ok(None);
+    }
+    let reason = q::JS_PromiseResult(context, *promise_obj_ref.borrow_value());
+    let reason_ref = QuickJsValueAdapter::new(
+        context,
+        reason,
+        false,
+        true,
+        "promises::get_rejection_reason reason",
+    );
+    if mark_handled {
+        let catch_func = functions::new_function(
+            context,
+            "catch",
+            |_ctx, _this, _args| Ok(quickjs_utils::new_null_ref()),
+            1,
+        )?;
+        add_promise_reactions(context, promise_obj_ref, None, Some(catch_func), None)?;
+    }
+    Ok(Some(reason_ref))
+}
+
+/// the action to take for an unhandled promise rejection, this is returned by the handler set with QuickJsRuntimeBuilder::on_unhandled_rejection
+pub enum RejectionAction {
+    /// log the rejection reason as error
+    Log,
+    /// do nothing
+    Ignore,
+    /// pass the rejection reason as JsError to the callback of QuickJsRuntimeAdapter::run_pending_jobs_with
+    Throw,
+}
+
+/// a rejected promise which did not have a handler when it was rejected
+/// these are kept until pending jobs have been run so a handler may still be added before the rejection is reported
+pub(crate) struct UnhandledRejection {
+    pub(crate) realm_id: String,
+    pub(crate) promise: QuickJsValueAdapter,
+    pub(crate) reason: QuickJsValueAdapter,
+}
+
+/// convert the reason of a rejected promise to a JsError
+pub(crate) fn rejection_to_js_error(
+    realm: &QuickJsRealmAdapter,
+    reason: &QuickJsValueAdapter,
+) -> JsError {
+    unsafe {
+        if errors::is_error(realm.context, reason) {
+            errors::error_to_js_error(realm.context, reason)
+        } else {
+            match functions::call_to_string(realm.context, reason) {
+                Ok(reason_str) => {
+                    JsError::new_string(format!("unhandled promise rejection: {reason_str}"))
+                }
+                Err(e) => e,
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn log_unhandled_rejection(
+    ctx: *mut q::JSContext,
+    reason_ref: &QuickJsValueAdapter,
+) {
+    log::error!("unhandled promise rejection detected");
+
+    let reason_str_res = functions::call_to_string(ctx, reason_ref);
+    match reason_str_res {
+        Ok(reason_str) => {
+            log::error!("unhandled promise rejection - reason: {}", reason_str);
+        }
+        Err(e) => {
+            log::error!("could not get reason: {}", e);
+        }
+    }
+}
+
 unsafe extern "C" fn promise_rejection_tracker(
     ctx: *mut q::JSContext,
-    _promise: q::JSValue,
+    promise: q::JSValue,
     reason: q::JSValue,
     is_handled: ::std::os::raw::c_int,
     _opaque: *mut ::std::os::raw::c_void,
 ) {
-    if is_handled == 0 {
-        log::error!("unhandled promise rejection detected");
+    let tracked = QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+        if q_js_rt.unhandled_rejection_handler.is_none() {
+            return false;
+        }
+        let rejections = &mut *q_js_rt.unhandled_rejections.borrow_mut();
+        if is_handled == 0 {
+            let realm = q_js_rt.get_quickjs_context(ctx);
+            rejections.push(UnhandledRejection {
+                realm_id: realm.id.clone(),
+                promise: QuickJsValueAdapter::new(
+                    ctx,
+                    promise,
+                    true,
+                    true,
+                    "promises::promise_rejection_tracker promise",
+                ),
+                reason: QuickJsValueAdapter::new(
+                    ctx,
+                    reason,
+                    true,
+                    true,
+                    "promises::promise_rejection_tracker reason",
+                ),
+            });
+        } else {
+            // a handler was added after all
+            let promise_ref = QuickJsValueAdapter::new(
+                ctx,
+                promise,
+                false,
+                false,
+                "promises::promise_rejection_tracker handled promise",
+            );
+            rejections.retain(|rejection| rejection.promise != promise_ref);
+        }
+        true
+    });
 
+    if !tracked && is_handled == 0 {
         let reason_ref = QuickJsValueAdapter::new(
             ctx,
             reason,
@@ -241,15 +377,7 @@ unsafe extern "C" fn promise_rejection_tracker(
             false,
             "promises::promise_rejection_tracker reason",
         );
-        let reason_str_res = functions::call_to_string(ctx, &reason_ref);
-        match reason_str_res {
-            Ok(reason_str) => {
-                log::error!("unhandled promise rejection - reason: {}", reason_str);
-            }
-            Err(e) => {
-                log::error!("could not get reason: {}", e);
-            }
-        }
+        log_unhandled_rejection(ctx, &reason_ref);
     }
 }
 
@@ -258,7 +386,10 @@ pub mod tests {
     use crate::builder::QuickJsRuntimeBuilder;
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::Script;
-    use crate::quickjs_utils::promises::{add_promise_reactions_q, is_promise_q, new_promise_q};
+    use crate::quickjs_utils::promises::{
+        add_promise_reactions_q, get_rejection_reason_q, is_promise_q, new_promise_q,
+        RejectionAction,
+    };
     use crate::quickjs_utils::{functions, new_null_ref, primitives};
     use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
     use crate::values::JsValueFacade;
@@ -497,4 +628,142 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_unhandled_rejection_throw() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .on_unhandled_rejection(|_realm, _reason| RejectionAction::Throw)
+            .build();
+        let errors = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            q_ctx
+                .eval(Script::new(
+                    "test_unhandled_rejection_throw.js",
+                    r#"
+                Promise.reject(new Error('boom'));
+                Promise.reject(new Error('handled')).catch(() => {});
+            "#,
+                ))
+                .expect("script failed");
+            let mut errors = vec![];
+            q_js_rt.run_pending_jobs_with(|res| {
+                if let Err(e) = res {
+                    errors.push(e.get_message().to_string());
+                }
+            });
+            errors
+        });
+        assert_eq!(errors, vec!["boom".to_string()]);
+    }
+
+    #[test]
+    fn test_max_pending_promises() {
+        let rt = QuickJsRuntimeBuilder::new().max_pending_promises(2).build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let slow = q_ctx
+                .create_function(
+                    "slow",
+                    |realm, _this, args| {
+                        let value = args[0].to_i32();
+                        realm.create_resolving_promise(
+                            move || {
+                                std::thread::sleep(Duration::from_millis(50));
+                                Ok(value)
+                            },
+                            |realm, value| realm.create_i32(value),
+                        )
+                    },
+                    1,
+                )
+                .expect("could not create function");
+            q_ctx
+                .set_object_property(&q_ctx.get_global().unwrap(), "slow", &slow)
+                .expect("could not set slow");
+        });
+        // the script is wrapped in a function so it can run twice without redeclaring its constants
+        let script = r#"(() => {
+            const promises = [0, 1, 2, 3, 4].map((i) => slow(i));
+            return Promise.allSettled(promises).then((settled) => {
+                return settled.map((s) => s.status === 'fulfilled' ? s.value : s.reason.name).join(',');
+            });
+        })()"#;
+        let run = |path: &str| -> String {
+            match rt
+                .eval_sync(None, Script::new(path, script))
+                .expect("script failed")
+            {
+                JsValueFacade::JsPromise { cached_promise } => cached_promise
+                    .get_promise_result_sync()
+                    .expect("promise timed out")
+                    .expect("promise was rejected")
+                    .get_str()
+                    .to_string(),
+                _ => panic!("script did not return a promise"),
+            }
+        };
+        assert_eq!(
+            run("test_max_pending_promises.js"),
+            "0,1,RangeError,RangeError,RangeError"
+        );
+
+        // settled promises no longer count
+        let pending = rt.exe_rt_task_in_event_loop(|q_js_rt| q_js_rt.pending_promise_count());
+        assert_eq!(pending, 0);
+        assert_eq!(
+            run("test_max_pending_promises2.js"),
+            "0,1,RangeError,RangeError,RangeError"
+        );
+    }
+
+    #[test]
+    fn test_rejection_reason() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let pending = q_ctx
+                .eval(Script::new(
+                    "test_rejection_reason_pending.js",
+                    "(new Promise(() => {}));",
+                ))
+                .expect("script failed");
+            let reason = get_rejection_reason_q(q_ctx, &pending, false).expect("get failed");
+            assert!(reason.is_none());
+
+            let rejected = q_ctx
+                .eval(Script::new(
+                    "test_rejection_reason.js",
+                    "(Promise.reject('no good'));",
+                ))
+                .expect("script failed");
+            let reason = get_rejection_reason_q(q_ctx, &rejected, true)
+                .expect("get failed")
+                .expect("promise was not rejected");
+            assert_eq!(
+                primitives::to_string_q(q_ctx, &reason).expect("not a string"),
+                "no good"
+            );
+        });
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new("test_rejection_reason2.js", "(Promise.reject('poof'));"),
+            )
+            .expect("script failed");
+        if let JsValueFacade::JsPromise { cached_promise } = res {
+            let reason = rt.exe_rt_task_in_event_loop(move |q_js_rt| {
+                let q_ctx = q_js_rt.get_main_realm();
+                cached_promise
+                    .rejection_reason(q_ctx, true)
+                    .expect("get failed")
+                    .expect("promise was not rejected")
+                    .get_str()
+                    .to_string()
+            });
+            assert_eq!(reason, "poof");
+        } else {
+            panic!("script did not return a promise");
+        }
+    }
 }
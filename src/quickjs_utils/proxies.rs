@@ -0,0 +1,285 @@
+//! utils for creating language level Proxy objects with traps implemented in rust
+//! see [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy) for more on Proxies
+//!
+//! this is not to be confused with reflection::Proxy which is used to create classes which are implemented in rust
+
+use crate::jsutils::JsError;
+use crate::quickjs_utils::objects::construct_object;
+use crate::quickjs_utils::{functions, get_constructor, get_global};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use libquickjs_sys as q;
+
+pub type ProxyGetTrap = dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter, &str) -> Result<QuickJsValueAdapter, JsError>
+    + 'static;
+pub type ProxySetTrap = dyn Fn(
+        &QuickJsRealmAdapter,
+        &QuickJsValueAdapter,
+        &str,
+        &QuickJsValueAdapter,
+    ) -> Result<bool, JsError>
+    + 'static;
+pub type ProxyHasTrap =
+    dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter, &str) -> Result<bool, JsError> + 'static;
+pub type ProxyDeletePropertyTrap =
+    dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter, &str) -> Result<bool, JsError> + 'static;
+pub type ProxyOwnKeysTrap =
+    dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> Result<Vec<String>, JsError> + 'static;
+
+/// the traps of a Proxy, traps which are not set fall back to the default behaviour of the target object
+/// the traps receive the target object and the property name, properties keyed by a Symbol are not passed to the traps but are always handled by the target
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::proxies::{new_proxy_q, ProxyHandler};
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///    let q_ctx = q_js_rt.get_main_realm();
+///    let target = q_ctx.create_object().ok().unwrap();
+///    let handler = ProxyHandler::new().get(|realm, _target, prop| realm.create_string(prop.to_uppercase().as_str()));
+///    let proxy = new_proxy_q(q_ctx, &target, handler).ok().unwrap();
+///    q_ctx.set_object_property(&q_ctx.get_global().ok().unwrap(), "upper", &proxy).ok().unwrap();
+///    let res = q_ctx.eval(Script::new("upper.js", "upper.hello;")).ok().unwrap();
+///    assert_eq!(res.to_string().ok().unwrap(), "HELLO");
+/// });
+/// ```
+#[derive(Default)]
+pub struct ProxyHandler {
+    get: Option<Box<ProxyGetTrap>>,
+    set: Option<Box<ProxySetTrap>>,
+    has: Option<Box<ProxyHasTrap>>,
+    delete_property: Option<Box<ProxyDeletePropertyTrap>>,
+    own_keys: Option<Box<ProxyOwnKeysTrap>>,
+}
+
+impl ProxyHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// set the get trap, this is called for `proxy[prop]`
+    pub fn get<T>(mut self, trap: T) -> Self
+    where
+        T: Fn(
+                &QuickJsRealmAdapter,
+                &QuickJsValueAdapter,
+                &str,
+            ) -> Result<QuickJsValueAdapter, JsError>
+            + 'static,
+    {
+        self.get = Some(Box::new(trap));
+        self
+    }
+    /// set the set trap, this is called for `proxy[prop] = value`
+    /// the trap should return false if the property could not be set, this results in a TypeError in strict mode
+    pub fn set<T>(mut self, trap: T) -> Self
+    where
+        T: Fn(
+                &QuickJsRealmAdapter,
+                &QuickJsValueAdapter,
+                &str,
+                &QuickJsValueAdapter,
+            ) -> Result<bool, JsError>
+            + 'static,
+    {
+        self.set = Some(Box::new(trap));
+        self
+    }
+    /// set the has trap, this is called for `prop in proxy`
+    pub fn has<T>(mut self, trap: T) -> Self
+    where
+        T: Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter, &str) -> Result<bool, JsError> + 'static,
+    {
+        self.has = Some(Box::new(trap));
+        self
+    }
+    /// set the deleteProperty trap, this is called for `delete proxy[prop]`
+    pub fn delete_property<T>(mut self, trap: T) -> Self
+    where
+        T: Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter, &str) -> Result<bool, JsError> + 'static,
+    {
+        self.delete_property = Some(Box::new(trap));
+        self
+    }
+    /// set the ownKeys trap, this is called for e.g. `Object.keys(proxy)`
+    /// please note that the result must contain all non-configurable own properties of the target
+    pub fn own_keys<T>(mut self, trap: T) -> Self
+    where
+        T: Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> Result<Vec<String>, JsError> + 'static,
+    {
+        self.own_keys = Some(Box::new(trap));
+        self
+    }
+}
+
+/// create a new Proxy for a target object, like `new Proxy(target, handler)`
+pub fn new_proxy_q(
+    q_ctx: &QuickJsRealmAdapter,
+    target: &QuickJsValueAdapter,
+    handler: ProxyHandler,
+) -> Result<QuickJsValueAdapter, JsError> {
+    let handler_obj = q_ctx.create_object()?;
+
+    if let Some(get) = handler.get {
+        let func = q_ctx.create_function(
+            "get",
+            move |realm, _this, args| match string_key(args) {
+                Some(prop) => get(realm, &args[0], prop.as_str()),
+                None => reflect(realm, "get", args),
+            },
+            3,
+        )?;
+        q_ctx.set_object_property(&handler_obj, "get", &func)?;
+    }
+    if let Some(set) = handler.set {
+        let func = q_ctx.create_function(
+            "set",
+            move |realm, _this, args| match string_key(args) {
+                Some(prop) => {
+                    let value = match args.get(2) {
+                        Some(value) => value.clone(),
+                        None => realm.create_undefined()?,
+                    };
+                    realm.create_boolean(set(realm, &args[0], prop.as_str(), &value)?)
+                }
+                None => reflect(realm, "set", args),
+            },
+            4,
+        )?;
+        q_ctx.set_object_property(&handler_obj, "set", &func)?;
+    }
+    if let Some(has) = handler.has {
+        let func = q_ctx.create_function(
+            "has",
+            move |realm, _this, args| match string_key(args) {
+                Some(prop) => realm.create_boolean(has(realm, &args[0], prop.as_str())?),
+                None => reflect(realm, "has", args),
+            },
+            2,
+        )?;
+        q_ctx.set_object_property(&handler_obj, "has", &func)?;
+    }
+    if let Some(delete_property) = handler.delete_property {
+        let func = q_ctx.create_function(
+            "deleteProperty",
+            move |realm, _this, args| match string_key(args) {
+                Some(prop) => {
+                    realm.create_boolean(delete_property(realm, &args[0], prop.as_str())?)
+                }
+                None => reflect(realm, "deleteProperty", args),
+            },
+            2,
+        )?;
+        q_ctx.set_object_property(&handler_obj, "deleteProperty", &func)?;
+    }
+    if let Some(own_keys) = handler.own_keys {
+        let func = q_ctx.create_function(
+            "ownKeys",
+            move |realm, _this, args| {
+                let target = args
+                    .first()
+                    .ok_or_else(|| JsError::new_str("ownKeys trap called without target"))?;
+                let keys = own_keys(realm, target)?;
+                let arr = realm.create_array()?;
+                for key in keys {
+                    realm.push_array_element(&arr, &realm.create_string(key.as_str())?)?;
+                }
+                Ok(arr)
+            },
+            1,
+        )?;
+        q_ctx.set_object_property(&handler_obj, "ownKeys", &func)?;
+    }
+
+    unsafe {
+        let constructor = get_constructor(q_ctx.context, "Proxy")?;
+        construct_object(q_ctx.context, &constructor, &[target, &handler_obj])
+    }
+}
+
+/// create a new Proxy for a target object, like `new Proxy(target, handler)`
+/// # Safety
+/// please ensure the passed JSContext is still valid
+pub unsafe fn new_proxy(
+    ctx: *mut q::JSContext,
+    target: &QuickJsValueAdapter,
+    handler: ProxyHandler,
+) -> Result<QuickJsValueAdapter, JsError> {
+    QuickJsRealmAdapter::with_context(ctx, |q_ctx| new_proxy_q(q_ctx, target, handler))
+}
+
+/// get the property name passed to a trap, None if the property is keyed by a Symbol
+fn string_key(args: &[QuickJsValueAdapter]) -> Option<String> {
+    match args.get(1) {
+        Some(prop) if prop.is_string() => prop.to_string().ok(),
+        _ => None,
+    }
+}
+
+/// fall back to the default behaviour by calling the Reflect method with the same name as the trap
+fn reflect(
+    realm: &QuickJsRealmAdapter,
+    trap_name: &str,
+    args: &[QuickJsValueAdapter],
+) -> Result<QuickJsValueAdapter, JsError> {
+    let reflect_obj =
+        realm.get_object_property(&unsafe { get_global(realm.context) }, "Reflect")?;
+    functions::invoke_member_function_q(realm, &reflect_obj, trap_name, args)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::proxies::{new_proxy_q, ProxyHandler};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_proxy_traps() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let (res, logged) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let logged = Rc::new(RefCell::new(vec![]));
+            let logged2 = logged.clone();
+            let target = q_ctx.create_object().expect("could not create target");
+            let handler = ProxyHandler::new()
+                .get(move |realm, target, prop| {
+                    logged2.borrow_mut().push(prop.to_string());
+                    if prop == "computed" {
+                        realm.create_i32(prop.len() as i32 * 2)
+                    } else {
+                        realm.get_object_property(target, prop)
+                    }
+                })
+                .has(|_realm, _target, prop| Ok(prop.starts_with("virtual_")))
+                .own_keys(|_realm, _target| Ok(vec!["a".to_string(), "b".to_string()]));
+            let proxy = new_proxy_q(q_ctx, &target, handler).expect("could not create proxy");
+            let global = q_ctx.get_global().expect("could not get global");
+            q_ctx
+                .set_object_property(&global, "testProxy", &proxy)
+                .expect("could not set proxy");
+
+            let res = q_ctx
+                .eval(Script::new(
+                    "test_proxy_traps.es",
+                    r#"
+                testProxy.real = 'yes';
+                [
+                    testProxy.computed,
+                    testProxy.real,
+                    'virtual_one' in testProxy,
+                    'other' in testProxy,
+                    Reflect.ownKeys(testProxy).join(',')
+                ].join('|');
+            "#,
+                ))
+                .expect("script failed");
+            let logged = logged.borrow().clone();
+            (res.to_string().expect("not a string"), logged)
+        });
+        assert_eq!(res, "16|yes|true|false|a,b");
+        assert_eq!(logged, vec!["computed", "real"]);
+    }
+}
@@ -0,0 +1,128 @@
+//! RegExp utils, these can be used to run a RegExp from rust without compiling the pattern for every call
+//! see [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp) for more on RegExps
+
+use crate::jsutils::JsError;
+use crate::quickjs_utils::objects::construct_object;
+use crate::quickjs_utils::{functions, get_constructor, objects, primitives};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use libquickjs_sys as q;
+
+/// a RegExp which is compiled once and may be used to test or exec many inputs
+/// the RegExp belongs to the realm it was created in and should only be used in the worker thread of that realm
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::regexp::Cached;
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///    let q_ctx = q_js_rt.get_main_realm();
+///    let digits = Cached::new_q(q_ctx, "^[0-9]+$", "").ok().unwrap();
+///    assert!(digits.test("12345").ok().unwrap());
+///    assert!(!digits.test("123a5").ok().unwrap());
+/// });
+/// ```
+pub struct Cached {
+    regexp: QuickJsValueAdapter,
+}
+
+impl Cached {
+    /// compile a new RegExp from a pattern and flags, e.g. `Cached::new_q(q_ctx, "a+", "i")`
+    pub fn new_q(q_ctx: &QuickJsRealmAdapter, pattern: &str, flags: &str) -> Result<Self, JsError> {
+        unsafe { Self::new(q_ctx.context, pattern, flags) }
+    }
+
+    /// compile a new RegExp from a pattern and flags
+    /// # Safety
+    /// please ensure the passed JSContext is still valid
+    pub unsafe fn new(ctx: *mut q::JSContext, pattern: &str, flags: &str) -> Result<Self, JsError> {
+        let constructor = get_constructor(ctx, "RegExp")?;
+        let pattern_ref = primitives::from_string(ctx, pattern)?;
+        let flags_ref = primitives::from_string(ctx, flags)?;
+        let regexp = construct_object(ctx, &constructor, &[&pattern_ref, &flags_ref])?;
+        Ok(Self { regexp })
+    }
+
+    /// test if the input matches, like `regexp.test(input)`
+    /// unlike in script the lastIndex of global or sticky RegExps is reset first so every call starts at the beginning of the input
+    pub fn test(&self, input: &str) -> Result<bool, JsError> {
+        let res = self.invoke("test", input)?;
+        Ok(res.to_bool())
+    }
+
+    /// run the RegExp against the input, like `regexp.exec(input)`
+    /// returns None if the input does not match or the result array otherwise
+    pub fn exec(&self, input: &str) -> Result<Option<QuickJsValueAdapter>, JsError> {
+        let res = self.invoke("exec", input)?;
+        if res.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(res))
+        }
+    }
+
+    /// get the RegExp object
+    pub fn get_regexp(&self) -> &QuickJsValueAdapter {
+        &self.regexp
+    }
+
+    fn invoke(&self, function_name: &str, input: &str) -> Result<QuickJsValueAdapter, JsError> {
+        let ctx = self.regexp.context;
+        unsafe {
+            objects::set_property(ctx, &self.regexp, "lastIndex", &primitives::from_i32(0))?;
+            let input_ref = primitives::from_string(ctx, input)?;
+            functions::invoke_member_function(ctx, &self.regexp, function_name, &[input_ref])
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::regexp::Cached;
+    use crate::quickjs_utils::{arrays, primitives};
+
+    #[test]
+    fn test_cached_regexp() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let cached = Cached::new_q(q_ctx, "^item_(\\d+)$", "g").expect("could not compile");
+
+            // compiling a RegExp from here on would fail
+            q_ctx
+                .eval(Script::new(
+                    "test_cached_regexp.es",
+                    "globalThis.RegExp = function() {throw Error('recompiled');};",
+                ))
+                .expect("script failed");
+
+            for x in 0..100 {
+                let input = if x % 2 == 0 {
+                    format!("item_{x}")
+                } else {
+                    format!("other_{x}")
+                };
+                assert_eq!(
+                    cached.test(input.as_str()).expect("test failed"),
+                    x % 2 == 0
+                );
+
+                let exec_res = cached.exec(input.as_str()).expect("exec failed");
+                if x % 2 == 0 {
+                    let matched = exec_res.expect("no match");
+                    let group = unsafe { arrays::get_element(q_ctx.context, &matched, 1) }
+                        .expect("no group");
+                    assert_eq!(
+                        primitives::to_string_q(q_ctx, &group).expect("not a string"),
+                        x.to_string()
+                    );
+                } else {
+                    assert!(exec_res.is_none());
+                }
+            }
+        });
+    }
+}
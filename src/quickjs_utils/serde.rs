@@ -0,0 +1,1221 @@
+//! serde utils, these convert rust values to JavaScript values and back directly without serializing to a JSON string first
+//!
+//! the values are represented like serde_json would represent them, structs and maps become objects, sequences and tuples become arrays,
+//! `None` and `()` become null and enum variants are externally tagged, e.g. `{"Circle": {"radius": 1.0}}` or `"Empty"` for a unit variant
+
+use crate::jsutils::{JsError, JsValueType};
+use crate::quickjs_utils::objects;
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use ::serde::de::value::StringDeserializer;
+use ::serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use ::serde::ser::{
+    Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use ::serde::{forward_to_deserialize_any, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::str::FromStr;
+
+impl ::serde::ser::Error for JsError {
+    fn custom<T: Display>(msg: T) -> Self {
+        JsError::new_string(format!("could not serialize value: {msg}"))
+    }
+}
+
+/// convert a rust value to a JavaScript value
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::quickjs_utils::serde::to_js_value;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let realm = q_js_rt.get_main_realm();
+///     let value = to_js_value(realm, &Point { x: 1, y: 2 }).expect("could not convert");
+///     assert_eq!(realm.get_object_property(&value, "y").unwrap().to_i32(), 2);
+/// });
+/// ```
+pub fn to_js_value<T: Serialize + ?Sized>(
+    realm: &QuickJsRealmAdapter,
+    value: &T,
+) -> Result<QuickJsValueAdapter, JsError> {
+    value.serialize(ValueSerializer { realm })
+}
+
+struct ValueSerializer<'a> {
+    realm: &'a QuickJsRealmAdapter,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn create_u64(&self, v: u64) -> Result<QuickJsValueAdapter, JsError> {
+        if v <= i32::MAX as u64 {
+            self.realm.create_i32(v as i32)
+        } else {
+            self.realm.create_f64(v as f64)
+        }
+    }
+    /// wrap the value of a variant in an object with the name of the variant as its only property
+    fn tagged(
+        &self,
+        variant: &'static str,
+        value: &QuickJsValueAdapter,
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let obj = self.realm.create_object()?;
+        self.realm.set_object_property(&obj, variant, value)?;
+        Ok(obj)
+    }
+}
+
+impl<'a> Serializer for ValueSerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+    type SerializeSeq = ArraySerializer<'a>;
+    type SerializeTuple = ArraySerializer<'a>;
+    type SerializeTupleStruct = ArraySerializer<'a>;
+    type SerializeTupleVariant = ArraySerializer<'a>;
+    type SerializeMap = ObjectSerializer<'a>;
+    type SerializeStruct = ObjectSerializer<'a>;
+    type SerializeStructVariant = ObjectSerializer<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_boolean(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_i32(v as i32)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_i32(v as i32)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        if v >= i32::MIN as i64 && v <= i32::MAX as i64 {
+            self.realm.create_i32(v as i32)
+        } else {
+            self.realm.create_f64(v as f64)
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_i32(v as i32)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_i32(v as i32)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.create_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.create_u64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_string(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        // like serde_json bytes are an array of numbers
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            SerializeSeq::serialize_element(&mut seq, byte)?;
+        }
+        SerializeSeq::end(seq)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_null()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_null()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_null()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.realm.create_string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let inner = value.serialize(ValueSerializer { realm: self.realm })?;
+        self.tagged(variant, &inner)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ArraySerializer {
+            realm: self.realm,
+            array: self.realm.create_array()?,
+            index: 0,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(ArraySerializer {
+            realm: self.realm,
+            array: self.realm.create_array()?,
+            index: 0,
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(ObjectSerializer {
+            realm: self.realm,
+            obj: self.realm.create_object()?,
+            next_key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(ObjectSerializer {
+            realm: self.realm,
+            obj: self.realm.create_object()?,
+            next_key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+struct ArraySerializer<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    array: QuickJsValueAdapter,
+    index: u32,
+    /// the name of the variant for a tuple variant
+    variant: Option<&'static str>,
+}
+
+impl<'a> ArraySerializer<'a> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        let element = value.serialize(ValueSerializer { realm: self.realm })?;
+        self.realm
+            .set_array_element(&self.array, self.index, &element)?;
+        self.index += 1;
+        Ok(())
+    }
+    fn finish(self) -> Result<QuickJsValueAdapter, JsError> {
+        match self.variant {
+            Some(variant) => ValueSerializer { realm: self.realm }.tagged(variant, &self.array),
+            None => Ok(self.array),
+        }
+    }
+}
+
+impl<'a> SerializeSeq for ArraySerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTuple for ArraySerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleStruct for ArraySerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleVariant for ArraySerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+struct ObjectSerializer<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    obj: QuickJsValueAdapter,
+    next_key: Option<String>,
+    /// the name of the variant for a struct variant
+    variant: Option<&'static str>,
+}
+
+impl<'a> ObjectSerializer<'a> {
+    fn set<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), JsError> {
+        let prop = value.serialize(ValueSerializer { realm: self.realm })?;
+        self.realm.set_object_property(&self.obj, key, &prop)
+    }
+    fn finish(self) -> Result<QuickJsValueAdapter, JsError> {
+        match self.variant {
+            Some(variant) => ValueSerializer { realm: self.realm }.tagged(variant, &self.obj),
+            None => Ok(self.obj),
+        }
+    }
+}
+
+impl<'a> SerializeMap for ObjectSerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), JsError> {
+        self.next_key = Some(key.serialize(KeySerializer {})?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| JsError::new_str("serialize_value called before serialize_key"))?;
+        self.set(key.as_str(), value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStruct for ObjectSerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), JsError> {
+        self.set(key, value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStructVariant for ObjectSerializer<'a> {
+    type Ok = QuickJsValueAdapter;
+    type Error = JsError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), JsError> {
+        self.set(key, value)
+    }
+
+    fn end(self) -> Result<QuickJsValueAdapter, JsError> {
+        self.finish()
+    }
+}
+
+/// serializes the keys of a map, like serde_json strings, chars and numbers are accepted as keys
+struct KeySerializer {}
+
+fn key_error() -> JsError {
+    JsError::new_str("could not serialize value: a map key must be a string or a number")
+}
+
+impl Serializer for KeySerializer {
+    type Ok = String;
+    type Error = JsError;
+    type SerializeSeq = Impossible<String, JsError>;
+    type SerializeTuple = Impossible<String, JsError>;
+    type SerializeTupleStruct = Impossible<String, JsError>;
+    type SerializeTupleVariant = Impossible<String, JsError>;
+    type SerializeMap = Impossible<String, JsError>;
+    type SerializeStruct = Impossible<String, JsError>;
+    type SerializeStructVariant = Impossible<String, JsError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, JsError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_none(self) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_unit(self) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, JsError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, JsError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, JsError> {
+        Err(key_error())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, JsError> {
+        Err(key_error())
+    }
+}
+
+impl ::serde::de::Error for JsError {
+    fn custom<T: Display>(msg: T) -> Self {
+        JsError::new_string(format!("could not deserialize value: {msg}"))
+    }
+}
+
+/// convert a JavaScript value to a rust value
+///
+/// numbers which are converted to an integer type must be whole numbers within the range of that type,
+/// properties of an object which are not fields of the struct are ignored, a missing field fails unless it is an `Option`
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::serde::from_js_value;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let realm = q_js_rt.get_main_realm();
+///     let value = realm.eval(Script::new("point.js", "({x: 1, y: 2});")).expect("script failed");
+///     let point: Point = from_js_value(realm, &value).expect("could not convert");
+///     assert_eq!(point.y, 2);
+/// });
+/// ```
+pub fn from_js_value<T: DeserializeOwned>(
+    realm: &QuickJsRealmAdapter,
+    value: &QuickJsValueAdapter,
+) -> Result<T, JsError> {
+    T::deserialize(ValueDeserializer {
+        realm,
+        value: value.clone(),
+    })
+}
+
+struct ValueDeserializer<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    value: QuickJsValueAdapter,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    /// get the value as a whole number, None if the value is not a number
+    fn to_integer(&self) -> Result<Option<i64>, JsError> {
+        if self.value.is_i32() {
+            Ok(Some(self.value.to_i32() as i64))
+        } else if self.value.is_f64() {
+            let v = self.value.to_f64();
+            if v.fract() != 0.0 || v < i64::MIN as f64 || v >= i64::MAX as f64 {
+                return Err(JsError::new_string(format!(
+                    "could not deserialize value: expected an integer but got {v}"
+                )));
+            }
+            Ok(Some(v as i64))
+        } else {
+            Ok(None)
+        }
+    }
+    /// get the value as an integer of type T, values which are not a number are passed to deserialize_any
+    fn deserialize_integer<'de, T, V, F>(self, visitor: V, visit: F) -> Result<V::Value, JsError>
+    where
+        T: TryFrom<i64>,
+        V: Visitor<'de>,
+        F: FnOnce(V, T) -> Result<V::Value, JsError>,
+    {
+        match self.to_integer()? {
+            Some(v) => {
+                let converted = T::try_from(v).map_err(|_| {
+                    JsError::new_string(format!(
+                        "could not deserialize value: {v} is out of range for {}",
+                        std::any::type_name::<T>()
+                    ))
+                })?;
+                visit(visitor, converted)
+            }
+            None => self.deserialize_any(visitor),
+        }
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = JsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        if self.value.is_big_int() || self.value.is_symbol() {
+            return Err(JsError::new_str(
+                "could not deserialize value: BigInts and Symbols are not supported",
+            ));
+        }
+        match self.value.get_js_type() {
+            JsValueType::Null | JsValueType::Undefined => visitor.visit_unit(),
+            JsValueType::Boolean => visitor.visit_bool(self.value.to_bool()),
+            JsValueType::I32 => visitor.visit_i32(self.value.to_i32()),
+            JsValueType::F64 => visitor.visit_f64(self.value.to_f64()),
+            JsValueType::String => visitor.visit_string(self.value.to_string()?),
+            JsValueType::Array => {
+                let len = self.realm.get_array_length(&self.value)?;
+                visitor.visit_seq(ArrayAccess {
+                    realm: self.realm,
+                    array: self.value,
+                    index: 0,
+                    len,
+                })
+            }
+            JsValueType::Object => {
+                let keys = unsafe {
+                    objects::get_own_string_property_names(self.realm.context, &self.value)
+                }?;
+                visitor.visit_map(ObjectAccess {
+                    realm: self.realm,
+                    obj: self.value,
+                    keys: keys.into_iter(),
+                    next_value: None,
+                })
+            }
+            other => Err(JsError::new_string(format!(
+                "could not deserialize value: a value of type {other} is not supported"
+            ))),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: i8| visitor.visit_i8(v))
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: i16| visitor.visit_i16(v))
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: i32| visitor.visit_i32(v))
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: i64| visitor.visit_i64(v))
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: u8| visitor.visit_u8(v))
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: u16| visitor.visit_u16(v))
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: u32| visitor.visit_u32(v))
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_integer(visitor, |visitor, v: u64| visitor.visit_u64(v))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        if self.value.is_null_or_undefined() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, JsError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, JsError> {
+        // unit variants are a string, the other variants an object with the name of the variant as its only property
+        if self.value.is_string() {
+            return visitor.visit_enum(self.value.to_string()?.into_deserializer());
+        }
+        if self.value.get_js_type() == JsValueType::Object {
+            let keys =
+                unsafe { objects::get_own_string_property_names(self.realm.context, &self.value) }?;
+            if keys.len() == 1 {
+                let variant = keys.into_iter().next().unwrap();
+                let value = self
+                    .realm
+                    .get_object_property(&self.value, variant.as_str())?;
+                return visitor.visit_enum(ObjectEnumAccess {
+                    realm: self.realm,
+                    variant,
+                    value,
+                });
+            }
+        }
+        Err(JsError::new_str(
+            "could not deserialize value: an enum must be a string or an object with a single property",
+        ))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        // ignored values are not read so e.g. a function in a property which is not a field does not fail
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier
+    }
+}
+
+struct ArrayAccess<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    array: QuickJsValueAdapter,
+    index: u32,
+    len: u32,
+}
+
+impl<'de, 'a> SeqAccess<'de> for ArrayAccess<'a> {
+    type Error = JsError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, JsError> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let value = self.realm.get_array_element(&self.array, self.index)?;
+        self.index += 1;
+        seed.deserialize(ValueDeserializer {
+            realm: self.realm,
+            value,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+struct ObjectAccess<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    obj: QuickJsValueAdapter,
+    keys: std::vec::IntoIter<String>,
+    next_value: Option<QuickJsValueAdapter>,
+}
+
+impl<'de, 'a> MapAccess<'de> for ObjectAccess<'a> {
+    type Error = JsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, JsError> {
+        match self.keys.next() {
+            Some(key) => {
+                self.next_value = Some(self.realm.get_object_property(&self.obj, key.as_str())?);
+                seed.deserialize(KeyDeserializer { key }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, JsError> {
+        let value = self
+            .next_value
+            .take()
+            .ok_or_else(|| JsError::new_str("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(ValueDeserializer {
+            realm: self.realm,
+            value,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+struct ObjectEnumAccess<'a> {
+    realm: &'a QuickJsRealmAdapter,
+    variant: String,
+    value: QuickJsValueAdapter,
+}
+
+impl<'de, 'a> EnumAccess<'de> for ObjectEnumAccess<'a> {
+    type Error = JsError;
+    type Variant = ValueDeserializer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), JsError> {
+        let deserializer: StringDeserializer<JsError> = self.variant.into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((
+            variant,
+            ValueDeserializer {
+                realm: self.realm,
+                value: self.value,
+            },
+        ))
+    }
+}
+
+impl<'de, 'a> ::serde::de::VariantAccess<'de> for ValueDeserializer<'a> {
+    type Error = JsError;
+
+    fn unit_variant(self) -> Result<(), JsError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, JsError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, JsError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, JsError> {
+        self.deserialize_map(visitor)
+    }
+}
+
+/// deserializes the property names of an object, like serde_json numeric keys are parsed for maps with integer keys
+struct KeyDeserializer {
+    key: String,
+}
+
+impl KeyDeserializer {
+    fn parse<T: FromStr>(&self) -> Result<T, JsError> {
+        self.key.parse().map_err(|_| {
+            JsError::new_string(format!(
+                "could not deserialize value: {} is not a valid {} key",
+                self.key,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer {
+    type Error = JsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_string(self.key)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_i8(self.parse()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_i16(self.parse()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_i32(self.parse()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_i64(self.parse()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_u8(self.parse()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_u16(self.parse()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_u32(self.parse()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsError> {
+        visitor.visit_u64(self.parse()?)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, JsError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, JsError> {
+        visitor.visit_enum(self.key.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::facades::tests::init_test_rt;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::serde::{from_js_value, to_js_value};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Address {
+        street: String,
+        number: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(u8),
+        Line(i32, i32),
+        Empty,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Person {
+        name: String,
+        age: u8,
+        score: f64,
+        big: u64,
+        active: bool,
+        nickname: Option<String>,
+        address: Address,
+        tags: Vec<String>,
+        shapes: Vec<Shape>,
+        counts: BTreeMap<u32, i64>,
+    }
+
+    #[test]
+    fn test_to_js_value() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let mut counts = BTreeMap::new();
+            counts.insert(3, -1);
+            let person = Person {
+                name: "Ada".to_string(),
+                age: 36,
+                score: 1.5,
+                big: 1 << 40,
+                active: true,
+                nickname: None,
+                address: Address {
+                    street: "Main street".to_string(),
+                    number: 12,
+                },
+                tags: vec!["a".to_string(), "b".to_string()],
+                shapes: vec![
+                    Shape::Circle { radius: 2.5 },
+                    Shape::Square(4),
+                    Shape::Line(1, 2),
+                    Shape::Empty,
+                ],
+                counts,
+            };
+            let value = to_js_value(realm, &person).expect("could not convert");
+
+            assert_eq!(
+                realm
+                    .get_object_property(&value, "name")
+                    .unwrap()
+                    .to_string()
+                    .unwrap(),
+                "Ada"
+            );
+            assert_eq!(realm.get_object_property(&value, "age").unwrap().to_i32(), 36);
+            assert_eq!(
+                realm.get_object_property(&value, "score").unwrap().to_f64(),
+                1.5
+            );
+            assert_eq!(
+                realm.get_object_property(&value, "big").unwrap().to_f64(),
+                (1u64 << 40) as f64
+            );
+            assert!(realm.get_object_property(&value, "active").unwrap().to_bool());
+            assert!(realm
+                .get_object_property(&value, "nickname")
+                .unwrap()
+                .is_null());
+            let address = realm.get_object_property(&value, "address").unwrap();
+            assert_eq!(
+                realm
+                    .get_object_property(&address, "number")
+                    .unwrap()
+                    .to_i32(),
+                12
+            );
+            let tags = realm.get_object_property(&value, "tags").unwrap();
+            assert!(tags.is_array());
+            assert_eq!(
+                realm
+                    .get_array_element(&tags, 1)
+                    .unwrap()
+                    .to_string()
+                    .unwrap(),
+                "b"
+            );
+
+            // the value is a plain object, so it is stringified like serde_json would serialize the struct
+            let json = realm.json_stringify(&value, None).expect("could not stringify");
+            assert_eq!(json, serde_json::to_string(&person).unwrap());
+
+            realm
+                .set_object_property(&realm.get_global().unwrap(), "person", &value)
+                .expect("could not set person");
+            let shapes = realm
+                .eval(Script::new(
+                    "test_to_js_value.js",
+                    "person.shapes.map(s => typeof s === 'string' ? s : Object.keys(s)[0]).join(',');",
+                ))
+                .expect("script failed");
+            assert_eq!(shapes.to_string().unwrap(), "Circle,Square,Line,Empty");
+        });
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Settings {
+        name: String,
+        retries: u8,
+        ratio: f64,
+        timeout: Option<u32>,
+        point: (i32, i32),
+    }
+
+    #[test]
+    fn test_from_js_value() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let value = realm
+                .eval(Script::new(
+                    "test_from_js_value.js",
+                    r#"
+                ({
+                    name: 'Ada', age: 36, score: 1.5, big: 2 ** 40, active: true, nickname: null,
+                    address: {street: 'Main street', number: 12, extra: () => 1},
+                    tags: ['a', 'b'],
+                    shapes: [{Circle: {radius: 2.5}}, {Square: 4}, {Line: [1, 2]}, 'Empty'],
+                    counts: {3: -1}
+                });
+            "#,
+                ))
+                .expect("script failed");
+            let person: Person = from_js_value(realm, &value).expect("could not convert");
+            let mut counts = BTreeMap::new();
+            counts.insert(3, -1);
+            assert_eq!(
+                person,
+                Person {
+                    name: "Ada".to_string(),
+                    age: 36,
+                    score: 1.5,
+                    big: 1 << 40,
+                    active: true,
+                    nickname: None,
+                    address: Address {
+                        street: "Main street".to_string(),
+                        number: 12,
+                    },
+                    tags: vec!["a".to_string(), "b".to_string()],
+                    shapes: vec![
+                        Shape::Circle { radius: 2.5 },
+                        Shape::Square(4),
+                        Shape::Line(1, 2),
+                        Shape::Empty,
+                    ],
+                    counts,
+                }
+            );
+            // a value converted with to_js_value converts back to the same value
+            let round_trip: Person = from_js_value(
+                realm,
+                &to_js_value(realm, &person).expect("could not convert"),
+            )
+            .expect("could not convert");
+            assert_eq!(round_trip, person);
+
+            let eval_settings = |code: &str| {
+                let value = realm
+                    .eval(Script::new("test_from_js_value2.js", code))
+                    .expect("script failed");
+                from_js_value::<Settings>(realm, &value)
+            };
+            // extra properties are ignored and a missing Option is None
+            let settings = eval_settings(
+                "({name: 'db', retries: 3, ratio: 2, point: [1, -1], unknown: 'ignored'});",
+            )
+            .expect("could not convert");
+            assert_eq!(
+                settings,
+                Settings {
+                    name: "db".to_string(),
+                    retries: 3,
+                    ratio: 2.0,
+                    timeout: None,
+                    point: (1, -1),
+                }
+            );
+            let err = eval_settings("({name: 'db', ratio: 2, point: [1, -1]});")
+                .expect_err("missing field was accepted");
+            assert!(err.get_message().contains("missing field `retries`"));
+            let err = eval_settings("({name: 'db', retries: 1.5, ratio: 2, point: [1, -1]});")
+                .expect_err("fraction was accepted");
+            assert!(err
+                .get_message()
+                .contains("expected an integer but got 1.5"));
+            let err = eval_settings("({name: 'db', retries: 300, ratio: 2, point: [1, -1]});")
+                .expect_err("out of range value was accepted");
+            assert!(err.get_message().contains("300 is out of range for u8"));
+        });
+    }
+}
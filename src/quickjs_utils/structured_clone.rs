@@ -0,0 +1,195 @@
+//! structured clone utils, these copy a value from one realm to another realm of the same runtime
+//! like the structured clone algorithm of the web platform (e.g. postMessage)
+//!
+//! primitives, arrays, plain objects, Dates, ArrayBuffers and TypedArrays are copied, references to the same object
+//! (including cycles) are preserved in the copy, functions, symbols and BigInts can not be cloned and fail with a DataCloneError
+//!
+//! ArrayBuffers which are passed in the transfer list are moved instead of shared, after the clone they are detached
+//! in the source realm so they can no longer be used there
+
+use crate::jsutils::{JsError, JsValueType};
+use crate::quickjs_utils::typedarrays::{
+    get_array_buffer, get_array_buffer_buffer_copy, get_typed_array_info, is_array_buffer,
+    new_array_buffer_copy,
+};
+use crate::quickjs_utils::{arrays, dates, functions, get_constructor, objects, primitives};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
+use libquickjs_sys as q;
+use std::collections::HashMap;
+
+/// clone a value from the source realm to the target realm, the ArrayBuffers in transfer are detached in the source realm
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::quickjs_utils::structured_clone::structured_clone_q;
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.create_context("clone_target").expect("could not create realm");
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let source = q_js_rt.get_main_realm();
+///     let target = q_js_rt.get_context("clone_target");
+///     let value = source.eval(Script::new("clone.js", "({a: [1, 2], d: new Date(0)});")).expect("script failed");
+///     let copy = structured_clone_q(source, target, &value, &[]).expect("clone failed");
+///     let json = target.json_stringify(&copy, None).expect("stringify failed");
+///     assert_eq!(json, "{\"a\":[1,2],\"d\":\"1970-01-01T00:00:00.000Z\"}");
+/// });
+/// ```
+pub fn structured_clone_q(
+    source: &QuickJsRealmAdapter,
+    target: &QuickJsRealmAdapter,
+    value: &QuickJsValueAdapter,
+    transfer: &[QuickJsValueAdapter],
+) -> Result<QuickJsValueAdapter, JsError> {
+    unsafe { structured_clone(source.context, target.context, value, transfer) }
+}
+
+/// clone a value from the source context to the target context, see structured_clone_q
+/// # Safety
+/// When passing a context pointer please make sure the corresponding QuickJsContext is still valid, both contexts should belong to the same runtime
+pub unsafe fn structured_clone(
+    source: *mut q::JSContext,
+    target: *mut q::JSContext,
+    value: &QuickJsValueAdapter,
+    transfer: &[QuickJsValueAdapter],
+) -> Result<QuickJsValueAdapter, JsError> {
+    for (index, buffer) in transfer.iter().enumerate() {
+        if buffer.context != source || !is_array_buffer(source, buffer) {
+            return Err(data_clone_error(
+                "only ArrayBuffers of the source realm can be transferred",
+            ));
+        }
+        if transfer[..index].contains(buffer) {
+            return Err(data_clone_error(
+                "an ArrayBuffer is listed more than once in the transfer list",
+            ));
+        }
+    }
+    let mut copies = HashMap::new();
+    let res = clone_value(source, target, value, &mut copies)?;
+    // detach after cloning, views which are cloned after a detach would otherwise see an empty buffer
+    for buffer in transfer {
+        q::JS_DetachArrayBuffer(source, *buffer.borrow_value());
+    }
+    Ok(res)
+}
+
+fn data_clone_error(message: &str) -> JsError {
+    JsError::new(
+        "DataCloneError".to_string(),
+        message.to_string(),
+        "".to_string(),
+    )
+}
+
+unsafe fn clone_value(
+    source: *mut q::JSContext,
+    target: *mut q::JSContext,
+    value: &QuickJsValueAdapter,
+    copies: &mut HashMap<QuickJsValueAdapter, QuickJsValueAdapter>,
+) -> Result<QuickJsValueAdapter, JsError> {
+    if value.is_big_int() || value.is_symbol() {
+        return Err(data_clone_error("BigInts and Symbols can not be cloned"));
+    }
+    match value.get_js_type() {
+        JsValueType::Undefined => Ok(crate::quickjs_utils::new_undefined_ref()),
+        JsValueType::Null => Ok(crate::quickjs_utils::new_null_ref()),
+        JsValueType::Boolean => Ok(primitives::from_bool(value.to_bool())),
+        JsValueType::I32 => Ok(primitives::from_i32(value.to_i32())),
+        JsValueType::F64 => Ok(primitives::from_f64(value.to_f64())),
+        JsValueType::String => {
+            primitives::from_string(target, primitives::to_string(source, value)?.as_str())
+        }
+        JsValueType::Function => Err(data_clone_error("functions can not be cloned")),
+        JsValueType::Promise => Err(data_clone_error("Promises can not be cloned")),
+        _ => {
+            if let Some(copy) = copies.get(value) {
+                return Ok(copy.clone());
+            }
+            let copy = if is_array_buffer(source, value) {
+                new_array_buffer_copy(target, &get_array_buffer_buffer_copy(source, value)?)?
+            } else if let Some(info) = get_typed_array_info(source, value)? {
+                let buffer = get_array_buffer(source, value)?;
+                let buffer_copy = clone_value(source, target, &buffer, copies)?;
+                let constructor = get_constructor(target, info.kind.constructor_name())?;
+                functions::call_constructor(
+                    target,
+                    &constructor,
+                    &[
+                        buffer_copy,
+                        primitives::from_f64(info.byte_offset as f64),
+                        primitives::from_f64(info.length as f64),
+                    ],
+                )?
+            } else if dates::is_date(source, value) {
+                let date = dates::new_date(target)?;
+                dates::set_time(target, &date, dates::get_time(source, value)?)?;
+                date
+            } else if arrays::is_array(source, value) {
+                let array = arrays::create_array(target)?;
+                // register the copy before the elements so cycles resolve to it
+                copies.insert(value.clone(), array.clone());
+                let len = arrays::get_length(source, value)?;
+                for index in 0..len {
+                    let element = arrays::get_element(source, value, index)?;
+                    let element_copy = clone_value(source, target, &element, copies)?;
+                    arrays::set_element(target, &array, index, &element_copy)?;
+                }
+                array
+            } else {
+                let obj = objects::create_object(target)?;
+                copies.insert(value.clone(), obj.clone());
+                for name in objects::get_own_string_property_names(source, value)? {
+                    let prop = objects::get_property(source, value, name.as_str())?;
+                    let prop_copy = clone_value(source, target, &prop, copies)?;
+                    objects::set_property(target, &obj, name.as_str(), &prop_copy)?;
+                }
+                obj
+            };
+            copies.insert(value.clone(), copy.clone());
+            Ok(copy)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::facades::tests::init_test_rt;
+    use crate::jsutils::Script;
+    use crate::quickjs_utils::structured_clone::structured_clone_q;
+
+    #[test]
+    fn test_structured_clone() {
+        let rt = init_test_rt();
+        rt.create_context("test_structured_clone")
+            .expect("could not create realm");
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let source = q_js_rt.get_main_realm();
+            let target = q_js_rt.get_context("test_structured_clone");
+            let value = source
+                .eval(Script::new(
+                    "test_structured_clone.js",
+                    "const shared = {n: 1}; const o = {a: shared, b: shared, bytes: new Uint16Array([1, 2, 3]).subarray(1)}; o.me = o; o;",
+                ))
+                .expect("script failed");
+            let copy = structured_clone_q(source, target, &value, &[]).expect("clone failed");
+            target
+                .set_object_property(&target.get_global().unwrap(), "copy", &copy)
+                .expect("could not set copy");
+            let func = source
+                .eval(Script::new("test_structured_clone2.js", "(function() {});"))
+                .expect("script failed");
+            let err = structured_clone_q(source, target, &func, &[]).expect_err("function was cloned");
+            assert_eq!(err.get_name(), "DataCloneError");
+            target
+                .eval(Script::new(
+                    "test_structured_clone3.js",
+                    "[copy.a === copy.b, copy.me === copy, copy.bytes instanceof Uint16Array, Array.from(copy.bytes).join(' '), copy.bytes.buffer.byteLength].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "true,true,true,2 3,6");
+    }
+}
@@ -141,6 +141,128 @@ pub unsafe fn is_typed_array(ctx: *mut q::JSContext, arr: &QuickJsValueAdapter)
     }
 }
 
+/// the kind of a TypedArray
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypedArrayKind {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    BigInt64,
+    BigUint64,
+    Float32,
+    Float64,
+}
+
+impl TypedArrayKind {
+    const ALL: [TypedArrayKind; 11] = [
+        TypedArrayKind::Int8,
+        TypedArrayKind::Uint8,
+        TypedArrayKind::Uint8Clamped,
+        TypedArrayKind::Int16,
+        TypedArrayKind::Uint16,
+        TypedArrayKind::Int32,
+        TypedArrayKind::Uint32,
+        TypedArrayKind::BigInt64,
+        TypedArrayKind::BigUint64,
+        TypedArrayKind::Float32,
+        TypedArrayKind::Float64,
+    ];
+
+    /// the name of the constructor of this kind of TypedArray e.g. "Float64Array"
+    pub fn constructor_name(&self) -> &'static str {
+        match self {
+            TypedArrayKind::Int8 => "Int8Array",
+            TypedArrayKind::Uint8 => "Uint8Array",
+            TypedArrayKind::Uint8Clamped => "Uint8ClampedArray",
+            TypedArrayKind::Int16 => "Int16Array",
+            TypedArrayKind::Uint16 => "Uint16Array",
+            TypedArrayKind::Int32 => "Int32Array",
+            TypedArrayKind::Uint32 => "Uint32Array",
+            TypedArrayKind::BigInt64 => "BigInt64Array",
+            TypedArrayKind::BigUint64 => "BigUint64Array",
+            TypedArrayKind::Float32 => "Float32Array",
+            TypedArrayKind::Float64 => "Float64Array",
+        }
+    }
+}
+
+/// info about a TypedArray, lengths and offsets are as reported by the TypedArray
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedArrayInfo {
+    pub kind: TypedArrayKind,
+    /// the number of elements
+    pub length: usize,
+    /// the number of bytes
+    pub byte_length: usize,
+    /// the offset in bytes from the start of the underlying ArrayBuffer
+    pub byte_offset: usize,
+}
+
+/// get the kind, length, byte length and byte offset of a TypedArray, returns None if the value is not a TypedArray
+pub fn get_typed_array_info_q(
+    q_ctx: &QuickJsRealmAdapter,
+    arr: &QuickJsValueAdapter,
+) -> Result<Option<TypedArrayInfo>, JsError> {
+    unsafe { get_typed_array_info(q_ctx.context, arr) }
+}
+
+/// get the kind, length, byte length and byte offset of a TypedArray, returns None if the value is not a TypedArray
+/// # Safety
+/// please ensure that the relevant QuickjsRealmAdapter is not dropped while using this function or a result of this function
+pub unsafe fn get_typed_array_info(
+    ctx: *mut q::JSContext,
+    arr: &QuickJsValueAdapter,
+) -> Result<Option<TypedArrayInfo>, JsError> {
+    if !is_typed_array(ctx, arr) {
+        return Ok(None);
+    }
+    let mut kind_opt = None;
+    for kind in TypedArrayKind::ALL {
+        if is_instance_of_by_name(ctx, arr, kind.constructor_name())? {
+            kind_opt = Some(kind);
+            break;
+        }
+    }
+    let kind = match kind_opt {
+        Some(kind) => kind,
+        None => return Ok(None),
+    };
+
+    let mut byte_offset = 0;
+    let mut byte_length = 0;
+    let mut bytes_per_element = 0;
+    let raw = q::JS_GetTypedArrayBuffer(
+        ctx,
+        *arr.borrow_value(),
+        &mut byte_offset,
+        &mut byte_length,
+        &mut bytes_per_element,
+    );
+    let buffer_ref = QuickJsValueAdapter::new(
+        ctx,
+        raw,
+        false,
+        true,
+        "typedarrays::get_typed_array_info buffer",
+    );
+    if buffer_ref.is_exception() {
+        return Err(QuickJsRealmAdapter::get_exception(ctx)
+            .unwrap_or_else(|| JsError::new_str("could not get TypedArray buffer")));
+    }
+    let byte_length = byte_length as usize;
+    let bytes_per_element = std::cmp::max(bytes_per_element as usize, 1);
+    Ok(Some(TypedArrayInfo {
+        kind,
+        length: byte_length / bytes_per_element,
+        byte_length,
+        byte_offset: byte_offset as usize,
+    }))
+}
+
 /// create an array buffer with a copy of the data in a Vec
 pub fn new_array_buffer_copy_q(
     q_ctx: &QuickJsRealmAdapter,
@@ -343,7 +465,7 @@ pub mod tests {
     use crate::jsutils::Script;
     use crate::quickjs_utils::typedarrays::{
         detach_array_buffer_buffer_q, get_array_buffer_q, is_array_buffer_q, is_typed_array_q,
-        new_array_buffer_q, new_uint8_array_q,
+        new_array_buffer_q, new_uint8_array_q, TypedArrayInfo, TypedArrayKind,
     };
 
     #[test]
@@ -453,4 +575,55 @@ pub mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_array_buffer_bytes() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_array_buffer_bytes.js",
+                    "(new Uint8Array([1, 2, 3, 250])).buffer;",
+                ),
+            )
+            .expect("script failed");
+        assert_eq!(res.as_array_buffer_bytes(), Some(vec![1, 2, 3, 250]));
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new("test_array_buffer_bytes2.js", "({a: 1});"),
+            )
+            .expect("script failed");
+        assert!(res.as_array_buffer_bytes().is_none());
+    }
+
+    #[test]
+    fn test_typed_array_info() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let (info, not_typed) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let arr = realm
+                .eval(Script::new(
+                    "test_typed_array_info.js",
+                    "new Float64Array(new ArrayBuffer(64), 16, 3);",
+                ))
+                .expect("script failed");
+            let not_typed = realm
+                .eval(Script::new("test_typed_array_info2.js", "[1, 2, 3];"))
+                .expect("script failed");
+            (arr.typed_array_info(), not_typed.typed_array_info())
+        });
+        assert_eq!(
+            info,
+            Some(TypedArrayInfo {
+                kind: TypedArrayKind::Float64,
+                length: 3,
+                byte_length: 24,
+                byte_offset: 16,
+            })
+        );
+        assert!(not_typed.is_none());
+    }
 }
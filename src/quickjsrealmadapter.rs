@@ -1,17 +1,24 @@
 use crate::facades::QuickjsRuntimeFacadeInner;
+#[cfg(feature = "console")]
+use crate::features::console::ConsoleRealmState;
+use crate::quickjs_utils::allocators::EvalAllocationGuard;
 use crate::quickjs_utils::objects::construct_object;
 use crate::quickjs_utils::primitives::{from_bool, from_f64, from_i32, from_string_q};
+use crate::quickjs_utils::structured_clone::structured_clone_q;
 use crate::quickjs_utils::typedarrays::{
     detach_array_buffer_buffer_q, get_array_buffer_buffer_copy_q, get_array_buffer_q,
     new_uint8_array_copy_q, new_uint8_array_q,
 };
-use crate::quickjs_utils::{arrays, errors, functions, get_global_q, json, new_null_ref, objects};
+use crate::quickjs_utils::{
+    arrays, errors, functions, get_constructor, get_global_q, json, modules, new_null_ref, objects,
+};
 use crate::quickjsruntimeadapter::{make_cstring, QuickJsRuntimeAdapter};
 use crate::quickjsvalueadapter::{QuickJsValueAdapter, TAG_EXCEPTION};
 use crate::reflection::eventtarget::dispatch_event;
 use crate::reflection::eventtarget::dispatch_static_event;
 use crate::reflection::{new_instance, new_instance3, Proxy};
 use hirofa_utils::auto_id_map::AutoIdMap;
+use hirofa_utils::eventloop::EventLoop;
 
 use crate::jsutils::jsproxies::{JsProxy, JsProxyInstanceId};
 use crate::jsutils::{JsError, JsValueType, Script};
@@ -21,20 +28,58 @@ use crate::values::{
     TypedArrayType,
 };
 use libquickjs_sys as q;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
 use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::i32;
 use std::os::raw::c_void;
 use std::rc::Rc;
 use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::jsutils::promises::new_resolving_promise;
 use crate::jsutils::promises::new_resolving_promise_async;
+use crate::jsutils::streams::new_readable_stream;
+use futures::Stream;
 use string_cache::DefaultAtom;
 
+/// wraps a Uint8Array in a Proxy which rejects writes, used by set_global_bytes_read_only
+const READ_ONLY_BYTES_SOURCE: &str = r#"
+(function() {
+    const mutators = ['set', 'fill', 'copyWithin', 'sort', 'reverse'];
+    function readOnly(bytes) {
+        return new Proxy(bytes, {
+            get(target, prop) {
+                if (prop === 'buffer') {
+                    // a copy so the bytes can not be modified through a new view on the buffer
+                    return target.buffer.slice(0);
+                }
+                if (prop === 'subarray') {
+                    return (...args) => readOnly(target.subarray(...args));
+                }
+                if (mutators.includes(prop)) {
+                    return function() {
+                        throw new TypeError('bytes are read-only');
+                    };
+                }
+                const value = Reflect.get(target, prop);
+                return typeof value === 'function' ? value.bind(target) : value;
+            },
+            set() { return false; },
+            defineProperty() { return false; },
+            deleteProperty() { return false; },
+            setPrototypeOf() { return false; }
+        });
+    }
+    return readOnly;
+})();
+"#;
+
 type ProxyEventListenerMaps = HashMap<
     String, /*proxy_class_name*/
     HashMap<
@@ -60,6 +105,24 @@ type ProxyStaticEventListenerMaps = HashMap<
     >,
 >;
 
+/// options for QuickJsRealmAdapter::eval_with_options
+#[derive(Clone, Default)]
+pub struct EvalOptions {
+    max_result_bytes: Option<usize>,
+}
+
+impl EvalOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// fail the eval when the estimated JSON size of the result exceeds max bytes
+    /// the size is estimated before the result is converted so a huge result is rejected without being copied to rust
+    pub fn max_result_bytes(mut self, max: usize) -> Self {
+        self.max_result_bytes = Some(max);
+        self
+    }
+}
+
 pub struct QuickJsRealmAdapter {
     object_cache: RefCell<AutoIdMap<QuickJsValueAdapter>>,
     promise_cache: RefCell<AutoIdMap<QuickJsPromiseAdapter>>,
@@ -68,6 +131,37 @@ pub struct QuickJsRealmAdapter {
     pub(crate) proxy_static_event_listeners: RefCell<ProxyStaticEventListenerMaps>,
     pub id: String,
     pub context: *mut q::JSContext,
+    random_state: RefCell<Option<Rc<Cell<u64>>>>,
+    last_activity: Cell<Instant>,
+    idle_handler: RefCell<Option<IdleHandler>>,
+    // ids of the timeouts and intervals which were scheduled from this realm and did not fire or were not cleared yet
+    pending_timers: RefCell<HashSet<i32>>,
+    // the function which wraps bytes in a read-only Proxy, compiled on the first call of set_global_bytes_read_only
+    read_only_bytes_func: RefCell<Option<QuickJsValueAdapter>>,
+    eval_recorder: RefCell<Option<Vec<RecordedEval>>>,
+    per_eval_globals: RefCell<Vec<(String, Rc<dyn Fn() -> JsValueFacade>)>>,
+    // number of evals which are running in this realm, per eval globals are only recomputed for the outermost eval
+    eval_depth: Cell<usize>,
+    #[cfg(feature = "console")]
+    pub(crate) console_state: RefCell<ConsoleRealmState>,
+}
+
+/// an eval which was recorded after calling QuickJsRealmAdapter::start_recording
+#[derive(Clone, Debug)]
+pub struct RecordedEval {
+    pub script: Script,
+    /// hash of the source code, may be used to quickly compare histories
+    pub source_hash: u64,
+    pub timestamp: SystemTime,
+    /// true if the script was evaluated as module
+    pub module: bool,
+}
+
+struct IdleHandler {
+    interval_id: i32,
+    idle_duration: Duration,
+    callback: Rc<dyn Fn(&QuickJsRealmAdapter)>,
+    fired: bool,
 }
 
 thread_local! {
@@ -78,6 +172,9 @@ thread_local! {
 impl QuickJsRealmAdapter {
     pub(crate) fn free(&self) {
         log::trace!("QuickJsContext:free {}", self.id);
+        if let Some(idle_handler) = self.idle_handler.borrow_mut().take() {
+            EventLoop::clear_interval(idle_handler.interval_id);
+        }
         {
             let cache_map = &mut *self.object_cache.borrow_mut();
             log::trace!(
@@ -91,6 +188,7 @@ impl QuickJsRealmAdapter {
             let proxy_event_listeners = &mut *self.proxy_event_listeners.borrow_mut();
             proxy_event_listeners.clear();
         }
+        self.read_only_bytes_func.borrow_mut().take();
 
         unsafe { q::JS_FreeContext(self.context) };
         log::trace!("after QuickJsContext:free {}", self.id);
@@ -122,6 +220,16 @@ impl QuickJsRealmAdapter {
             proxy_registry: RefCell::new(Default::default()),
             proxy_event_listeners: RefCell::new(Default::default()),
             proxy_static_event_listeners: RefCell::new(Default::default()),
+            random_state: RefCell::new(None),
+            last_activity: Cell::new(Instant::now()),
+            idle_handler: RefCell::new(None),
+            pending_timers: RefCell::new(HashSet::new()),
+            read_only_bytes_func: RefCell::new(None),
+            eval_recorder: RefCell::new(None),
+            per_eval_globals: RefCell::new(vec![]),
+            eval_depth: Cell::new(0),
+            #[cfg(feature = "console")]
+            console_state: RefCell::new(Default::default()),
         }
     }
     /// get the id of a QuickJsContext from a JSContext
@@ -145,7 +253,43 @@ impl QuickJsRealmAdapter {
     /// evaluate a script
 
     pub fn eval(&self, script: Script) -> Result<QuickJsValueAdapter, JsError> {
-        unsafe { Self::eval_ctx(self.context, script, None) }
+        self.mark_active();
+        self.record_eval(&script, false);
+        self.with_per_eval_globals(|| unsafe { Self::eval_ctx(self.context, script, None) })
+    }
+
+    /// evaluate a script with EvalOptions
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::quickjsrealmadapter::EvalOptions;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     let options = EvalOptions::new().max_result_bytes(1024);
+    ///     let res = realm.eval_with_options(Script::new("eval_with_options.js", "new Array(10000).fill('x');"), &options);
+    ///     assert!(res.is_err());
+    /// });
+    /// ```
+    pub fn eval_with_options(
+        &self,
+        script: Script,
+        options: &EvalOptions,
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let path = script.get_path().to_string();
+        let res = self.eval(script)?;
+        if let Some(max) = options.max_result_bytes {
+            let size = json::estimate_json_size_q(self, &res, max);
+            if size > max {
+                return Err(JsError::new(
+                    "RangeError".to_string(),
+                    format!("result of {path} exceeds the maximum size of {max} bytes"),
+                    "".to_string(),
+                ));
+            }
+        }
+        Ok(res)
     }
 
     pub fn eval_this(
@@ -153,7 +297,9 @@ impl QuickJsRealmAdapter {
         script: Script,
         this: QuickJsValueAdapter,
     ) -> Result<QuickJsValueAdapter, JsError> {
-        unsafe { Self::eval_ctx(self.context, script, Some(this)) }
+        self.mark_active();
+        self.record_eval(&script, false);
+        self.with_per_eval_globals(|| unsafe { Self::eval_ctx(self.context, script, Some(this)) })
     }
 
     /// # Safety
@@ -170,6 +316,10 @@ impl QuickJsRealmAdapter {
         let filename_c = make_cstring(script.get_path())?;
         let code_c = make_cstring(script.get_code())?;
 
+        let _alloc_guard = EvalAllocationGuard::start();
+        #[cfg(feature = "console")]
+        let _output_guard = crate::features::console::EvalOutputGuard::start();
+
         let value_raw = match this_opt {
             None => q::JS_Eval(
                 context,
@@ -213,7 +363,9 @@ impl QuickJsRealmAdapter {
 
     /// evaluate a Module
     pub fn eval_module(&self, script: Script) -> Result<QuickJsValueAdapter, JsError> {
-        unsafe { Self::eval_module_ctx(self.context, script) }
+        self.mark_active();
+        self.record_eval(&script, true);
+        self.with_per_eval_globals(|| unsafe { Self::eval_module_ctx(self.context, script) })
     }
 
     /// # Safety
@@ -229,6 +381,10 @@ impl QuickJsRealmAdapter {
         let filename_c = make_cstring(script.get_path())?;
         let code_c = make_cstring(script.get_code())?;
 
+        let _alloc_guard = EvalAllocationGuard::start();
+        #[cfg(feature = "console")]
+        let _output_guard = crate::features::console::EvalOutputGuard::start();
+
         let value_raw = q::JS_Eval(
             context,
             code_c.as_ptr(),
@@ -263,6 +419,57 @@ impl QuickJsRealmAdapter {
             Ok(ret)
         }
     }
+    /// evaluate a Module and return its namespace object, this can be used to read the exports of the module
+    /// the default export is available as the "default" property of the namespace
+    pub fn eval_module_namespace(&self, script: Script) -> Result<QuickJsValueAdapter, JsError> {
+        self.mark_active();
+        self.record_eval(&script, true);
+        self.with_per_eval_globals(|| unsafe {
+            Self::eval_module_namespace_ctx(self.context, script)
+        })
+    }
+
+    /// # Safety
+    /// when passing a context ptr please be sure that the corresponding QuickJsContext is still active
+    pub unsafe fn eval_module_namespace_ctx(
+        context: *mut q::JSContext,
+        mut script: Script,
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        log::debug!("q_js_rt.eval_module_namespace file {}", script.get_path());
+
+        script = QuickJsRuntimeAdapter::pre_process(script)?;
+
+        // compile first so we keep the module record, its namespace is read from that record after evaluating
+        let compiled_module = modules::compile_module(context, script)?;
+        let module = modules::get_module_def(&compiled_module);
+
+        let _alloc_guard = EvalAllocationGuard::start();
+        #[cfg(feature = "console")]
+        let _output_guard = crate::features::console::EvalOutputGuard::start();
+
+        let value_raw = q::JS_EvalFunction(context, compiled_module.clone_value_incr_rc());
+        let ret = QuickJsValueAdapter::new(
+            context,
+            value_raw,
+            false,
+            true,
+            "eval_module_namespace result",
+        );
+        if ret.is_exception() {
+            let ex_opt = Self::get_exception(context);
+            return if let Some(ex) = ex_opt {
+                log::debug!("eval_module_namespace_ctx failed: {}", ex);
+                Err(ex)
+            } else {
+                Err(JsError::new_str(
+                    "eval_module_namespace failed and could not get exception",
+                ))
+            };
+        }
+
+        modules::get_module_namespace(context, module)
+    }
+
     /// throw an internal error to quickjs and create a new ex obj
     pub fn report_ex(&self, err: &str) -> q::JSValue {
         unsafe { Self::report_ex_ctx(self.context, err) }
@@ -380,6 +587,15 @@ impl QuickJsRealmAdapter {
         crate::quickjs_utils::get_script_or_module_name_q(self)
     }
 
+    /// get the name of the script or module which is currently executing (the top of the call stack)
+    /// native functions on the stack are skipped so this may be used in a native function to get the name of the script which called it
+    /// returns None if no script is running
+    pub fn current_script_name(&self) -> Option<String> {
+        self.get_script_or_module_name()
+            .ok()
+            .filter(|name| !name.is_empty())
+    }
+
     pub fn install_proxy(
         &self,
         proxy: JsProxy,
@@ -649,6 +865,11 @@ impl QuickJsRealmAdapter {
         objects::create_object_q(self)
     }
 
+    /// create an object without a prototype, like `Object.create(null)`
+    pub fn create_object_null_proto(&self) -> Result<QuickJsValueAdapter, JsError> {
+        objects::create_object_null_proto_q(self)
+    }
+
     pub fn construct_object(
         &self,
         constructor: &QuickJsValueAdapter,
@@ -790,6 +1011,26 @@ impl QuickJsRealmAdapter {
         crate::quickjs_utils::promises::new_promise_q(self)
     }
 
+    /// create a new Promise which is already resolved with a value, like `Promise.resolve(value)`
+    /// this is useful for native functions which compute their result synchronously but need to return a Promise
+    pub fn new_resolved_promise(
+        &self,
+        value: &QuickJsValueAdapter,
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let promise = self.create_promise()?;
+        promise.resolve_q(self, value.clone())?;
+        Ok(promise.get_promise_obj_ref())
+    }
+
+    /// create a new Promise which is already rejected with an Error, like `Promise.reject(new Error(...))`
+    pub fn new_rejected_promise(&self, error: JsError) -> Result<QuickJsValueAdapter, JsError> {
+        let promise = self.create_promise()?;
+        let err_ref =
+            self.create_error(error.get_name(), error.get_message(), error.get_stack())?;
+        promise.reject_q(self, err_ref)?;
+        Ok(promise.get_promise_obj_ref())
+    }
+
     pub fn add_promise_reactions(
         &self,
         promise: &QuickJsValueAdapter,
@@ -810,6 +1051,11 @@ impl QuickJsRealmAdapter {
         map.remove_opt(&id)
     }
 
+    /// get the number of cached promises, these are the promises which are waiting for a native producer
+    pub fn pending_promise_count(&self) -> usize {
+        self.promise_cache.borrow().len()
+    }
+
     pub fn dispose_cached_object(&self, id: i32) {
         let _ = self.consume_cached_obj(id);
     }
@@ -853,6 +1099,32 @@ impl QuickJsRealmAdapter {
         json::parse_q(self, json_string)
     }
 
+    /// serialize the script defined state of this realm as a JSON object
+    /// this contains the enumerable properties of the global object (e.g. `var` declarations and `globalThis.x = ...` assignments) which can be serialized to JSON
+    /// functions, built-ins (which are not enumerable) and values which fail to serialize (e.g. circular objects or BigInts) are skipped
+    /// note that `let` and `const` declarations are not properties of the global object and are thus not included
+    pub fn dump_globals_json(&self) -> Result<String, JsError> {
+        let global = get_global_q(self);
+        let snapshot = self.create_object()?;
+        let names = unsafe { objects::get_own_string_property_names(self.context, &global)? };
+        for name in names {
+            let value = match objects::get_property_q(self, &global, name.as_str()) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            if value.is_function() || value.is_undefined() {
+                continue;
+            }
+            match json::stringify_q(self, &value, None) {
+                Ok(json_ref) if json_ref.is_string() => {
+                    objects::set_property_q(self, &snapshot, name.as_str(), &value)?;
+                }
+                _ => {}
+            }
+        }
+        self.json_stringify(&snapshot, None)
+    }
+
     pub fn create_typed_array_uint8(
         &self,
         buffer: Vec<u8>,
@@ -867,6 +1139,66 @@ impl QuickJsRealmAdapter {
         new_uint8_array_copy_q(self, buffer)
     }
 
+    /// expose a copy of bytes to script as a Uint8Array global
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     realm.set_global_bytes("input", &[1, 2, 3]).expect("could not set bytes");
+    ///     let res = realm.eval(Script::new("bytes.js", "input.reduce((a, b) => a + b, 0);")).expect("script failed");
+    ///     assert_eq!(res.to_i32(), 6);
+    /// });
+    /// ```
+    pub fn set_global_bytes(
+        &self,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let array = self.create_typed_array_uint8_copy(bytes)?;
+        objects::set_property_q(self, &get_global_q(self), name, &array)?;
+        Ok(array)
+    }
+
+    /// expose a copy of bytes to script as a read-only Uint8Array global
+    /// a TypedArray can not be frozen so the array is wrapped in a Proxy, writes to its elements are ignored
+    /// (or throw a TypeError in strict mode) and methods which would modify it throw a TypeError
+    /// the global itself is not writable
+    ///
+    /// the global is a Proxy and not a real Uint8Array, `instanceof Uint8Array` is true and the array methods work but
+    /// ArrayBuffer.isView returns false and APIs which need a real TypedArray reject it, like TextDecoder.decode or
+    /// TypedArray.prototype.set when it is passed as source, script can use `roData.slice()` to get a real (writable) copy
+    pub fn set_global_bytes_read_only(
+        &self,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let array = self.create_typed_array_uint8_copy(bytes)?;
+        let read_only_func = self.read_only_bytes_func()?;
+        let read_only = self.invoke_function(None, &read_only_func, &[&array])?;
+        objects::set_property2_q(self, &get_global_q(self), name, &read_only, 0)?;
+        Ok(read_only)
+    }
+
+    /// get the function which wraps bytes in a read-only Proxy
+    /// this is evaluated once per realm with eval_ctx so it is not recorded and does not count as an eval of the realm
+    fn read_only_bytes_func(&self) -> Result<QuickJsValueAdapter, JsError> {
+        if let Some(func) = &*self.read_only_bytes_func.borrow() {
+            return Ok(func.clone());
+        }
+        let func = unsafe {
+            Self::eval_ctx(
+                self.context,
+                Script::new("read_only_bytes.js", READ_ONLY_BYTES_SOURCE),
+                None,
+            )
+        }?;
+        self.read_only_bytes_func.replace(Some(func.clone()));
+        Ok(func)
+    }
+
     pub fn detach_typed_array_buffer(
         &self,
         array: &QuickJsValueAdapter,
@@ -967,6 +1299,59 @@ impl QuickJsRealmAdapter {
         Ok(res)
     }
 
+    /// receive an object or array which is cached in another realm of the same runtime as a structured clone, see [structured_clone_q]
+    /// the ArrayBuffers in transfer are moved, after this they are detached in the realm the value came from
+    ///
+    /// values which are not cached in a realm (e.g. a facade which was created in rust) are converted with from_js_value_facade
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.create_context("worker").expect("could not create realm");
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let main = q_js_rt.get_main_realm();
+    ///     let worker = q_js_rt.get_context("worker");
+    ///     let msg = main.eval(Script::new("msg.js", "({bytes: new Uint8Array([1, 2]).buffer});")).expect("script failed");
+    ///     let buffer = main.get_object_property(&msg, "bytes").expect("no bytes");
+    ///     let facade = main.to_js_value_facade(&msg).expect("could not convert");
+    ///     let received = worker.receive_transfer(facade, &[buffer.clone()]).expect("transfer failed");
+    ///     let received_bytes = worker.get_object_property(&received, "bytes").expect("no bytes");
+    ///     assert_eq!(worker.get_object_property(&received_bytes, "byteLength").unwrap().to_i32(), 2);
+    ///     // the buffer was moved, it is detached in the main realm
+    ///     assert_eq!(main.get_object_property(&buffer, "byteLength").unwrap().to_i32(), 0);
+    /// });
+    /// ```
+    pub fn receive_transfer(
+        &self,
+        value_facade: JsValueFacade,
+        transfer: &[QuickJsValueAdapter],
+    ) -> Result<QuickJsValueAdapter, JsError> {
+        let cached_object = match &value_facade {
+            JsValueFacade::JsObject { cached_object } => Some(cached_object),
+            JsValueFacade::JsArray { cached_array } => Some(&cached_array.cached_object),
+            _ => None,
+        };
+        match cached_object {
+            Some(cached_object) => QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+                let source = q_js_rt
+                    .get_realm(cached_object.get_realm_id())
+                    .ok_or_else(|| JsError::new_str("no such realm"))?;
+                source.with_cached_object(cached_object.id, |obj| {
+                    structured_clone_q(source, self, obj, transfer)
+                })
+            }),
+            None => {
+                if !transfer.is_empty() {
+                    return Err(JsError::new_str(
+                        "a transfer list requires an object or array of another realm",
+                    ));
+                }
+                self.from_js_value_facade(value_facade)
+            }
+        }
+    }
+
     /// convert a JSValueFacade into a JSValueAdapter
     /// you need this to move values into the worker thread from a different thread (JSValueAdapter cannot leave the worker thread)
     #[allow(clippy::wrong_self_convention)]
@@ -1104,6 +1489,66 @@ impl QuickJsRealmAdapter {
         }
     }
 
+    /// convert an object to a rust enum, the variant is selected by the string value of the tag_field property
+    /// and the other properties are deserialized as the fields of that variant
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// #[serde(rename_all = "lowercase")]
+    /// enum Event {
+    ///     Click { x: i32, y: i32 },
+    ///     Close,
+    /// }
+    ///
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     let value = realm.eval(Script::new("tagged.js", "({kind: 'click', x: 1, y: 2});")).expect("script failed");
+    ///     let event: Event = realm.from_js_tagged(&value, "kind").expect("could not convert");
+    ///     assert_eq!(event, Event::Click { x: 1, y: 2 });
+    /// });
+    /// ```
+    pub fn from_js_tagged<T: DeserializeOwned>(
+        &self,
+        value: &QuickJsValueAdapter,
+        tag_field: &str,
+    ) -> Result<T, JsError> {
+        if value.get_js_type() != JsValueType::Object {
+            return Err(JsError::new_str("value is not an object"));
+        }
+        let mut fields = match self.value_adapter_to_serde_value(value)? {
+            Value::Object(fields) => fields,
+            _ => return Err(JsError::new_str("value is not an object")),
+        };
+        let tag = match fields.remove(tag_field) {
+            Some(Value::String(tag)) => tag,
+            Some(_) => {
+                return Err(JsError::new_string(format!(
+                    "discriminant {tag_field} is not a string"
+                )))
+            }
+            None => {
+                return Err(JsError::new_string(format!(
+                    "missing discriminant {tag_field}"
+                )))
+            }
+        };
+        // a unit variant is represented by just its name
+        if fields.is_empty() {
+            if let Ok(unit) = serde_json::from_value(Value::String(tag.clone())) {
+                return Ok(unit);
+            }
+        }
+        let mut tagged = serde_json::Map::new();
+        tagged.insert(tag.clone(), Value::Object(fields));
+        serde_json::from_value(Value::Object(tagged))
+            .map_err(|e| JsError::new_string(format!("could not convert variant {tag}: {e}")))
+    }
+
     pub fn serde_value_to_value_adapter(
         &self,
         value: Value,
@@ -1165,6 +1610,230 @@ impl QuickJsRealmAdapter {
     {
         new_resolving_promise_async(self, producer, mapper)
     }
+    /// create a minimal ReadableStream object which reads its chunks from a rust Stream
+    /// from script the chunks can be read by using getReader().read() which returns a Promise of {value, done}
+    pub fn create_readable_stream<S>(&self, stream: S) -> Result<QuickJsValueAdapter, JsError>
+    where
+        S: Stream<Item = Result<JsValueFacade, JsError>> + Send + 'static,
+    {
+        new_readable_stream(self, stream)
+    }
+    /// get the counters of console.count() in this realm by label
+    #[cfg(feature = "console")]
+    pub fn console_counters(&self) -> HashMap<String, u64> {
+        self.console_state.borrow().counters()
+    }
+    /// get the running timers of console.time() in this realm with the time elapsed since they were started, ordered by label
+    #[cfg(feature = "console")]
+    pub fn console_timers(&self) -> Vec<(String, Duration)> {
+        self.console_state.borrow().timers()
+    }
+    /// mark this realm as active, this postpones the idle callback registered with on_idle
+    /// this is done automatically when evaluating script or invoking functions in this realm
+    pub fn mark_active(&self) {
+        self.last_activity.set(Instant::now());
+        if let Some(idle_handler) = &mut *self.idle_handler.borrow_mut() {
+            idle_handler.fired = false;
+        }
+    }
+    /// register a timeout or interval which was scheduled from this realm, the realm is not idle while it is pending
+    #[cfg(any(feature = "settimeout", feature = "setinterval"))]
+    pub(crate) fn add_pending_timer(&self, id: i32) {
+        self.pending_timers.borrow_mut().insert(id);
+    }
+    /// unregister a timeout which fired or a timeout or interval which was cleared
+    #[cfg(any(feature = "settimeout", feature = "setinterval"))]
+    pub(crate) fn remove_pending_timer(&self, id: i32) {
+        self.pending_timers.borrow_mut().remove(&id);
+    }
+    /// register a callback which is invoked when this realm has not evaluated script or invoked functions for the idle_duration and there are no pending jobs, timeouts or intervals
+    /// the callback is invoked once per idle period, after the realm was active again it will be invoked again when the realm becomes idle again
+    /// timers which fire also mark the realm as active, registering a new callback replaces the previous one
+    pub fn on_idle<C: Fn(&QuickJsRealmAdapter) + 'static>(
+        &self,
+        idle_duration: Duration,
+        callback: C,
+    ) {
+        if let Some(old_handler) = self.idle_handler.borrow_mut().take() {
+            EventLoop::clear_interval(old_handler.interval_id);
+        }
+        let check_interval = std::cmp::max(idle_duration / 4, Duration::from_millis(1));
+        let realm_id = self.id.clone();
+        let interval_id = EventLoop::add_interval(
+            move || {
+                QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+                    if let Some(realm) = q_js_rt.opt_context(realm_id.as_str()) {
+                        realm.check_idle(q_js_rt);
+                    }
+                })
+            },
+            check_interval,
+            check_interval,
+        );
+        self.idle_handler.replace(Some(IdleHandler {
+            interval_id,
+            idle_duration,
+            callback: Rc::new(callback),
+            fired: false,
+        }));
+    }
+    fn check_idle(&self, q_js_rt: &QuickJsRuntimeAdapter) {
+        let callback = {
+            let idle_handler_opt = &mut *self.idle_handler.borrow_mut();
+            let idle_handler = match idle_handler_opt {
+                Some(idle_handler) => idle_handler,
+                None => return,
+            };
+            if idle_handler.fired
+                || self.last_activity.get().elapsed() < idle_handler.idle_duration
+                || q_js_rt.has_pending_jobs()
+                || !self.pending_timers.borrow().is_empty()
+            {
+                return;
+            }
+            idle_handler.fired = true;
+            idle_handler.callback.clone()
+        };
+        log::debug!("realm {} is idle", self.id);
+        callback(self);
+    }
+    /// start recording all scripts which are evaluated in this realm, the history may be used to replay the evals in a fresh realm to reproduce a bug
+    /// starting a recording clears the previously recorded history
+    pub fn start_recording(&self) {
+        self.eval_recorder.replace(Some(vec![]));
+    }
+    /// stop recording and return the recorded history
+    pub fn stop_recording(&self) -> Vec<RecordedEval> {
+        self.eval_recorder.replace(None).unwrap_or_default()
+    }
+    /// get a copy of the history recorded so far
+    pub fn recorded_evals(&self) -> Vec<RecordedEval> {
+        self.eval_recorder.borrow().clone().unwrap_or_default()
+    }
+    /// evaluate a recorded history in this realm in the same order as it was recorded
+    /// failing evals are logged but do not stop the replay because they may also have failed while recording
+    pub fn replay(&self, history: &[RecordedEval]) {
+        for recorded in history {
+            log::debug!(
+                "replaying {} (hash {}) in realm {}",
+                recorded.script.get_path(),
+                recorded.source_hash,
+                self.id
+            );
+            let res = if recorded.module {
+                self.eval_module(recorded.script.clone())
+            } else {
+                self.eval(recorded.script.clone())
+            };
+            if let Err(e) = res {
+                log::debug!("replay of {} failed: {}", recorded.script.get_path(), e);
+            }
+        }
+    }
+    fn record_eval(&self, script: &Script, module: bool) {
+        if let Some(history) = &mut *self.eval_recorder.borrow_mut() {
+            let mut hasher = DefaultHasher::new();
+            script.get_code().hash(&mut hasher);
+            history.push(RecordedEval {
+                script: script.clone(),
+                source_hash: hasher.finish(),
+                timestamp: SystemTime::now(),
+                module,
+            });
+        }
+    }
+    /// reseed Math.random of this realm, after reseeding with the same seed Math.random will return the same sequence of numbers
+    /// QuickJS seeds Math.random from the current time and offers no way to set the seed, so the first call replaces Math.random with
+    /// an implementation of the same xorshift64* generator which uses a seed we control
+    /// this may be used to make scripts reproducible, e.g. by reseeding before every request
+    pub fn reseed_random(&self, seed: u64) -> Result<(), JsError> {
+        // xorshift64* can not use 0 as state, quickjs uses 1 in that case
+        let seed = if seed == 0 { 1 } else { seed };
+        if let Some(state) = &*self.random_state.borrow() {
+            state.set(seed);
+            return Ok(());
+        }
+        let state = Rc::new(Cell::new(seed));
+        let func_state = state.clone();
+        let random_func = self.create_function(
+            "random",
+            move |realm, _this, _args| realm.create_f64(next_random(&func_state)),
+            0,
+        )?;
+        let math = self.get_object_property(&self.get_global()?, "Math")?;
+        self.set_object_property(&math, "random", &random_func)?;
+        self.random_state.replace(Some(state));
+        Ok(())
+    }
+    /// define a global which scripts read like a variable but which calls a rust closure on every read
+    /// e.g. a global `__now` which returns the current time
+    /// the global is defined as a configurable accessor on globalThis without a setter, so assigning to it throws in strict mode
+    pub fn define_global_getter(
+        &self,
+        name: &str,
+        getter: Box<dyn Fn() -> JsValueFacade>,
+    ) -> Result<(), JsError> {
+        let getter_func = self.create_function(
+            name,
+            move |realm, _this, _args| realm.from_js_value_facade(getter()),
+            0,
+        )?;
+        let descriptor = self.create_object()?;
+        self.set_object_property(&descriptor, "get", &getter_func)?;
+        self.set_object_property(&descriptor, "configurable", &self.create_boolean(true)?)?;
+        self.set_object_property(&descriptor, "enumerable", &self.create_boolean(true)?)?;
+        let object_constructor = unsafe { get_constructor(self.context, "Object") }?;
+        self.invoke_function_on_object_by_name(
+            &object_constructor,
+            "defineProperty",
+            &[self.get_global()?, self.create_string(name)?, descriptor],
+        )?;
+        Ok(())
+    }
+    /// define a global which is recomputed at the start of every eval, e.g. a request-scoped context
+    /// the value is computed once per top-level eval so it is stable while the script runs, evals which are started
+    /// while another eval of this realm is running (e.g. from a native function) see the value of the outer eval
+    /// setting a per eval global with the same name replaces the previous one
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::values::JsValueFacade;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     realm.set_per_eval_global("requestStart", Box::new(|| JsValueFacade::new_f64(1700000000000.0)));
+    ///     let res = realm.eval(Script::new("per_eval_global.js", "requestStart;")).expect("script failed");
+    ///     assert_eq!(res.to_f64(), 1700000000000.0);
+    /// });
+    /// ```
+    pub fn set_per_eval_global(&self, name: &str, compute: Box<dyn Fn() -> JsValueFacade>) {
+        let globals = &mut *self.per_eval_globals.borrow_mut();
+        globals.retain(|(global_name, _)| global_name != name);
+        globals.push((name.to_string(), Rc::from(compute)));
+    }
+    /// run an eval, the per eval globals are recomputed first if this is the outermost eval
+    fn with_per_eval_globals<C>(&self, eval: C) -> Result<QuickJsValueAdapter, JsError>
+    where
+        C: FnOnce() -> Result<QuickJsValueAdapter, JsError>,
+    {
+        let depth = self.eval_depth.get();
+        if depth == 0 {
+            // clone the list so the closures may set per eval globals themselves
+            let globals = self.per_eval_globals.borrow().clone();
+            if !globals.is_empty() {
+                let global = self.get_global()?;
+                for (name, compute) in globals {
+                    let value = self.from_js_value_facade(compute())?;
+                    self.set_object_property(&global, name.as_str(), &value)?;
+                }
+            }
+        }
+        self.eval_depth.set(depth + 1);
+        let res = eval();
+        self.eval_depth.set(depth);
+        res
+    }
     /// create a new Promise with a FnOnce producer which will run async and then resolve or reject the promise
     /// the mapper is used to convert the result of the future into a JSValueAdapter
     ///
@@ -1182,14 +1851,525 @@ impl QuickJsRealmAdapter {
     }
 }
 
+/// the xorshift64* generator as used by Math.random in QuickJS, returns a number in [0, 1)
+fn next_random(state: &Cell<u64>) -> f64 {
+    let mut x = state.get();
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    state.set(x);
+    let v = x.wrapping_mul(0x2545F4914F6CDD1D);
+    f64::from_bits((0x3ff << 52) | (v >> 12)) - 1.0
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::builder::QuickJsRuntimeBuilder;
     use crate::facades::tests::init_test_rt;
-    use crate::jsutils::Script;
+    use crate::jsutils::{JsError, Script};
     use crate::quickjs_utils;
     use crate::quickjs_utils::primitives::to_i32;
     use crate::quickjs_utils::{functions, get_global_q, objects};
+    use crate::values::JsValueFacade;
+    use serde::Deserialize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Shape {
+        Circle { r: f64 },
+        Rect { w: f64, h: f64 },
+        Empty,
+    }
+
+    #[test]
+    fn test_set_global_bytes() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .set_global_bytes("data", &[10, 20, 30])
+                .expect("could not set bytes");
+            realm.start_recording();
+            realm
+                .set_global_bytes_read_only("roData", &[1, 2, 3, 4])
+                .expect("could not set read-only bytes");
+            realm
+                .set_global_bytes_read_only("roData2", &[5])
+                .expect("could not set read-only bytes");
+            // the helper is not evaluated as a script of the realm
+            assert!(realm.stop_recording().is_empty());
+            realm
+                .eval(Script::new(
+                    "test_set_global_bytes.js",
+                    r#"
+                data[1] = 21;
+                roData[0] = 9;
+                function throwsTypeError(f) {
+                    try {
+                        f();
+                        return false;
+                    } catch (err) {
+                        return err instanceof TypeError;
+                    }
+                }
+                [
+                    data.length, data[1], data instanceof Uint8Array,
+                    roData.length, roData[0], roData[3], roData instanceof Uint8Array, roData.slice(1, 3).join('|'),
+                    throwsTypeError(() => { 'use strict'; roData[1] = 9; }),
+                    throwsTypeError(() => roData.fill(0)),
+                    throwsTypeError(() => roData.subarray(1).set([9])),
+                    new Uint8Array(roData.buffer).fill(9) && roData[2]
+                ].join(',');
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "3,21,true,4,1,4,true,2|3,true,true,true,3");
+    }
+
+    #[cfg(feature = "textencoding")]
+    #[test]
+    fn test_decode_global_bytes_read_only() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            realm
+                .set_global_bytes_read_only("roText", "héllo".as_bytes())
+                .expect("could not set read-only bytes");
+            realm
+                .eval(Script::new(
+                    "test_decode_global_bytes_read_only.js",
+                    r#"
+                let rejected = false;
+                try {
+                    new TextDecoder().decode(roText);
+                } catch (err) {
+                    rejected = true;
+                }
+                [ArrayBuffer.isView(roText), rejected, new TextDecoder().decode(roText.slice())].join(',');
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "false,true,héllo");
+    }
+
+    #[test]
+    fn test_from_js_tagged() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            let shapes = realm
+                .eval(Script::new(
+                    "test_from_js_tagged.js",
+                    "[{type: 'circle', r: 2}, {type: 'rect', w: 1, h: 2.5}, {type: 'empty'}, {type: 'hexagon'}, {r: 1}];",
+                ))
+                .expect("script failed");
+            let shape = |index| realm.get_array_element(&shapes, index).unwrap();
+            assert_eq!(
+                realm.from_js_tagged::<Shape>(&shape(0), "type").unwrap(),
+                Shape::Circle { r: 2.0 }
+            );
+            assert_eq!(
+                realm.from_js_tagged::<Shape>(&shape(1), "type").unwrap(),
+                Shape::Rect { w: 1.0, h: 2.5 }
+            );
+            assert_eq!(
+                realm.from_js_tagged::<Shape>(&shape(2), "type").unwrap(),
+                Shape::Empty
+            );
+            assert!(realm.from_js_tagged::<Shape>(&shape(3), "type").is_err());
+            let err = realm.from_js_tagged::<Shape>(&shape(4), "type").unwrap_err();
+            assert_eq!(err.get_message(), "missing discriminant type");
+        });
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let rt = init_test_rt();
+        rt.create_context("record_src")
+            .expect("could not create realm");
+        rt.create_context("record_dst")
+            .expect("could not create realm");
+        let (src_state, dst_state, history_len) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let src = q_js_rt.get_context("record_src");
+            src.start_recording();
+            src.eval(Script::new(
+                "record1.es",
+                "globalThis.counter = {count: 1}; globalThis.incr = (by) => {counter.count += by;};",
+            ))
+            .expect("script failed");
+            src.eval(Script::new("record2.es", "incr(5); incr(3);"))
+                .expect("script failed");
+            let history = src.stop_recording();
+
+            let state_script = || Script::new("state.es", "JSON.stringify(counter);");
+            let src_state = src.eval(state_script()).expect("script failed");
+            // not recorded
+            src.eval(Script::new("record3.es", "incr(100);"))
+                .expect("script failed");
+
+            let dst = q_js_rt.get_context("record_dst");
+            dst.replay(&history);
+            let dst_state = dst.eval(state_script()).expect("script failed");
+            (
+                src_state.to_string().expect("not a string"),
+                dst_state.to_string().expect("not a string"),
+                history.len(),
+            )
+        });
+        assert_eq!(history_len, 2);
+        assert_eq!(src_state, r#"{"count":9}"#);
+        assert_eq!(dst_state, src_state);
+    }
+
+    #[test]
+    fn test_on_idle() {
+        let rt = init_test_rt();
+        let idle_ct = Arc::new(AtomicUsize::new(0));
+        let idle_ct2 = idle_ct.clone();
+        rt.exe_rt_task_in_event_loop(move |q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            q_ctx.on_idle(Duration::from_millis(50), move |realm| {
+                assert_eq!(realm.id, "__main__");
+                idle_ct2.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+        assert_eq!(idle_ct.load(Ordering::SeqCst), 0);
+        std::thread::sleep(Duration::from_millis(300));
+        // only fired once while staying idle
+        assert_eq!(idle_ct.load(Ordering::SeqCst), 1);
+
+        rt.eval_sync(None, Script::new("test_on_idle.es", "1 + 1;"))
+            .expect("script failed");
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(idle_ct.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_on_idle_pending_timer() {
+        let rt = init_test_rt();
+        let idle_ct = Arc::new(AtomicUsize::new(0));
+        let idle_ct2 = idle_ct.clone();
+        rt.exe_rt_task_in_event_loop(move |q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            q_ctx.on_idle(Duration::from_millis(50), move |_realm| {
+                idle_ct2.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+        rt.eval_sync(
+            None,
+            Script::new(
+                "test_on_idle_pending_timer.es",
+                "const idleTimer = setTimeout(() => {}, 60000); const idleInterval = setInterval(() => {}, 60000);",
+            ),
+        )
+        .expect("script failed");
+        std::thread::sleep(Duration::from_millis(300));
+        // not idle while a timeout or interval is pending
+        assert_eq!(idle_ct.load(Ordering::SeqCst), 0);
+
+        rt.eval_sync(
+            None,
+            Script::new(
+                "test_on_idle_pending_timer2.es",
+                "clearTimeout(idleTimer); clearInterval(idleInterval);",
+            ),
+        )
+        .expect("script failed");
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(idle_ct.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_resolved_and_rejected_promise() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let resolving = q_ctx
+                .create_function(
+                    "resolving",
+                    |realm, _this, _args| realm.new_resolved_promise(&realm.create_i32(42)?),
+                    0,
+                )
+                .expect("could not create function");
+            let rejecting = q_ctx
+                .create_function(
+                    "rejecting",
+                    |realm, _this, _args| {
+                        realm.new_rejected_promise(JsError::new_str("computation failed"))
+                    },
+                    0,
+                )
+                .expect("could not create function");
+            let global = q_ctx.get_global().expect("could not get global");
+            q_ctx
+                .set_object_property(&global, "testResolving", &resolving)
+                .expect("could not set function");
+            q_ctx
+                .set_object_property(&global, "testRejecting", &rejecting)
+                .expect("could not set function");
+        });
+
+        let res = rt
+            .eval_sync(
+                None,
+                Script::new(
+                    "test_resolved_and_rejected_promise.es",
+                    r#"
+            (async () => {
+                let value = await testResolving();
+                try {
+                    await testRejecting();
+                    return 'not rejected';
+                } catch (err) {
+                    return value + '|' + err.message;
+                }
+            })();
+        "#,
+                ),
+            )
+            .expect("script failed");
+        if let JsValueFacade::JsPromise { cached_promise } = res {
+            let res = cached_promise
+                .get_promise_result_sync()
+                .expect("promise timed out")
+                .expect("promise was rejected");
+            assert_eq!(res.get_str(), "42|computation failed");
+        } else {
+            panic!("script did not return a promise");
+        }
+    }
+
+    #[test]
+    fn test_define_global_getter() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let counter = std::cell::Cell::new(0);
+            q_ctx
+                .define_global_getter(
+                    "__counter",
+                    Box::new(move || {
+                        counter.set(counter.get() + 1);
+                        JsValueFacade::new_i32(counter.get())
+                    }),
+                )
+                .expect("could not define getter");
+            q_ctx
+                .eval(Script::new(
+                    "test_define_global_getter.es",
+                    "[__counter, __counter, globalThis.__counter].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "1,2,3");
+    }
+
+    #[test]
+    fn test_per_eval_global() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let next_id = std::cell::Cell::new(0);
+            q_ctx.set_per_eval_global(
+                "requestId",
+                Box::new(move || {
+                    next_id.set(next_id.get() + 1);
+                    JsValueFacade::new_i32(next_id.get())
+                }),
+            );
+            let nested_eval = q_ctx
+                .create_function(
+                    "nestedEval",
+                    |realm, _this, _args| {
+                        realm.eval(Script::new("test_per_eval_global_nested.es", "requestId;"))
+                    },
+                    0,
+                )
+                .expect("could not create function");
+            q_ctx
+                .set_object_property(&q_ctx.get_global().unwrap(), "nestedEval", &nested_eval)
+                .expect("could not set nestedEval");
+            let eval = |name: &str| {
+                q_ctx
+                    .eval(Script::new(
+                        name,
+                        "[requestId, requestId, nestedEval()].join(',');",
+                    ))
+                    .expect("script failed")
+                    .to_string()
+                    .expect("not a string")
+            };
+            format!(
+                "{}|{}",
+                eval("test_per_eval_global.es"),
+                eval("test_per_eval_global2.es")
+            )
+        });
+        assert_eq!(res, "1,1,1|2,2,2");
+    }
+
+    #[test]
+    fn test_current_script_name() {
+        let rt = init_test_rt();
+        let (during, after) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let report_func = q_ctx
+                .create_function(
+                    "reportScriptName",
+                    |realm, _this, _args| {
+                        let name = realm.current_script_name().unwrap_or_default();
+                        realm.create_string(name.as_str())
+                    },
+                    0,
+                )
+                .expect("could not create function");
+            q_ctx
+                .set_object_property(
+                    &q_ctx.get_global().unwrap(),
+                    "reportScriptName",
+                    &report_func,
+                )
+                .expect("could not set function");
+            let during = q_ctx
+                .eval(Script::new(
+                    "https://scripts/reporter.js",
+                    "(function inner() {return reportScriptName();})();",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string");
+            (during, q_ctx.current_script_name())
+        });
+        assert_eq!(during, "https://scripts/reporter.js");
+        assert_eq!(after, None);
+    }
+
+    #[test]
+    fn test_dump_globals_json() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            q_ctx
+                .eval(Script::new(
+                    "test_dump_globals_json.es",
+                    r#"
+                var counter = 3;
+                globalThis.settings = {debug: true, tags: ['a', 'b']};
+                globalThis.greet = function() {};
+                globalThis.big = 1n;
+                var circular = {};
+                circular.self = circular;
+                let notGlobal = 1;
+            "#,
+                ))
+                .expect("script failed");
+            q_ctx.dump_globals_json().expect("dump failed")
+        });
+        let dump: serde_json::Value = serde_json::from_str(res.as_str()).expect("invalid json");
+        assert_eq!(dump["counter"], 3);
+        assert_eq!(dump["settings"]["tags"][1], "b");
+        assert!(dump.get("console").is_none());
+        assert!(dump.get("greet").is_none());
+        assert!(dump.get("big").is_none());
+        assert!(dump.get("circular").is_none());
+        assert!(dump.get("notGlobal").is_none());
+    }
+
+    #[test]
+    fn test_reseed_random() {
+        let rt = init_test_rt();
+        let (first, second, third) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let read = |q_ctx: &crate::quickjsrealmadapter::QuickJsRealmAdapter| {
+                let arr = q_ctx
+                    .eval(Script::new(
+                        "test_reseed_random.es",
+                        "[Math.random(), Math.random()];",
+                    ))
+                    .expect("script failed");
+                let a = q_ctx
+                    .get_array_element(&arr, 0)
+                    .expect("no element")
+                    .to_f64();
+                let b = q_ctx
+                    .get_array_element(&arr, 1)
+                    .expect("no element")
+                    .to_f64();
+                (a, b)
+            };
+            q_ctx.reseed_random(1234).expect("reseed failed");
+            let first = read(q_ctx);
+            q_ctx.reseed_random(1234).expect("reseed failed");
+            let second = read(q_ctx);
+            q_ctx.reseed_random(4321).expect("reseed failed");
+            let third = read(q_ctx);
+            (first, second, third)
+        });
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+        assert_ne!(first.0, first.1);
+        assert!(first.0 >= 0.0 && first.0 < 1.0);
+    }
+
+    #[test]
+    fn test_receive_transfer() {
+        let rt = init_test_rt();
+        rt.create_context("transfer_a")
+            .expect("could not create ctx a");
+        rt.create_context("transfer_b")
+            .expect("could not create ctx b");
+        let (a_res, b_res) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm_a = q_js_rt.get_context("transfer_a");
+            let realm_b = q_js_rt.get_context("transfer_b");
+            let msg = realm_a
+                .eval(Script::new(
+                    "test_receive_transfer.js",
+                    "globalThis.buf = new Uint8Array([7, 8, 9]).buffer; globalThis.view = new Uint8Array(buf, 1); ({buf, view, kept: new Uint8Array([1]).buffer});",
+                ))
+                .expect("script failed");
+            let buf = realm_a
+                .get_object_property(&msg, "buf")
+                .expect("no buf");
+            let facade = realm_a.to_js_value_facade(&msg).expect("could not convert");
+            let received = realm_b
+                .receive_transfer(facade, &[buf])
+                .expect("transfer failed");
+            realm_b
+                .set_object_property(&realm_b.get_global().unwrap(), "msg", &received)
+                .expect("could not set msg");
+            let a_res = realm_a
+                .eval(Script::new(
+                    "test_receive_transfer2.js",
+                    "[buf.byteLength, view.length].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string");
+            let b_res = realm_b
+                .eval(Script::new(
+                    "test_receive_transfer3.js",
+                    "[Array.from(new Uint8Array(msg.buf)).join(' '), Array.from(msg.view).join(' '), msg.view.buffer === msg.buf, msg.kept.byteLength].join(',');",
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string");
+            (a_res, b_res)
+        });
+        // the transferred buffer is detached in realm a
+        assert_eq!(a_res, "0,0");
+        assert_eq!(b_res, "7 8 9,8 9,true,1");
+    }
 
     #[test]
     fn test_eval() {
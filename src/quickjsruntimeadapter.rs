@@ -1,24 +1,31 @@
 // store in thread_local
 
 use crate::facades::QuickjsRuntimeFacadeInner;
+use crate::jsutils::identifiers::free_identifiers;
 use crate::jsutils::modules::{CompiledModuleLoader, NativeModuleLoader, ScriptModuleLoader};
 use crate::jsutils::{JsError, Script, ScriptPreProcessor};
+use crate::quickjs_utils::allocators::BoxedAllocator;
 use crate::quickjs_utils::compile::from_bytecode;
 use crate::quickjs_utils::modules::{
     add_module_export, compile_module, get_module_def, get_module_name, new_module,
     set_module_export,
 };
-use crate::quickjs_utils::{gc, interrupthandler, modules, promises};
+use crate::quickjs_utils::promises::{RejectionAction, UnhandledRejection};
+use crate::quickjs_utils::{gc, interrupthandler, modules, objects, promises};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use libquickjs_sys as q;
 use serde::Serialize;
-use std::cell::RefCell;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::fmt::{Debug, Formatter};
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_void};
 use std::panic;
+use std::rc::Rc;
 use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 /// this is the internal abstract loader which is used to actually load the modules
 pub trait ModuleLoader {
@@ -194,7 +201,14 @@ impl ModuleLoader for NativeModuleLoaderAdapter {
     ) -> Result<(), JsError> {
         let module_name = get_module_name(q_ctx.context, module)?;
 
+        let frozen_names = self
+            .inner
+            .get_frozen_export_names(q_ctx, module_name.as_str());
+
         for (name, val) in self.inner.get_module_exports(q_ctx, module_name.as_str()) {
+            if frozen_names.contains(&name) {
+                objects::freeze(q_ctx.context, &val)?;
+            }
             set_module_export(q_ctx.context, module, name, val)?;
         }
         Ok(())
@@ -264,12 +278,29 @@ pub struct QuickJsRuntimeAdapter {
     pub(crate) script_pre_processors: Vec<Box<dyn ScriptPreProcessor + Send>>,
     #[allow(clippy::type_complexity)]
     pub(crate) interrupt_handler: Option<Box<dyn Fn(&QuickJsRuntimeAdapter) -> bool>>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) unhandled_rejection_handler:
+        Option<Box<dyn Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> RejectionAction>>,
+    pub(crate) unhandled_rejections: RefCell<Vec<UnhandledRejection>>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) atom_count_threshold: Option<(i64, Box<dyn Fn(&QuickJsRuntimeAdapter, i64)>)>,
+    atom_count_threshold_exceeded: Cell<bool>,
+    atom_count_check_interval: Duration,
+    atom_count_last_check: Cell<Option<Instant>>,
+    pub(crate) max_pending_promises: Option<usize>,
+    // owns the data the runtime opaque points to
+    runtime_data: RefCell<Option<Box<Rc<dyn Any>>>>,
+    // owns the custom allocator the malloc state points to, fields are dropped after drop() so this outlives the runtime
+    pub(crate) custom_allocator: Option<Box<BoxedAllocator>>,
 }
 
 thread_local! {
     static NESTED: RefCell<bool> = RefCell::new(false);
 }
 
+// the atom count is computed by walking the whole heap, so by default it is checked at most once a second
+const DEFAULT_ATOM_COUNT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Serialize)]
 pub struct MemoryUsage {
     pub realm_ct: usize,
@@ -337,6 +368,7 @@ impl Debug for MemoryUsage {
 
 impl QuickJsRuntimeAdapter {
     pub(crate) fn init_rt_for_current_thread(rt: QuickJsRuntimeAdapter) {
+        interrupthandler::init(&rt);
         QJS_RT.with(|rc| {
             let opt = &mut *rc.borrow_mut();
             opt.replace(rt);
@@ -378,6 +410,42 @@ impl QuickJsRuntimeAdapter {
         }
     }
 
+    /// get a best-effort summary of the live objects in this runtime grouped by class, sorted by count (highest first)
+    /// QuickJS offers no way to walk the heap, so only instances of Proxy classes (see [reflection::Proxy](crate::reflection::Proxy)) are counted per class,
+    /// all other objects are approximated from the memory usage and reported as "Array", "Function" or "Object"
+    /// comparing two histograms can help to find which classes leak instances
+    pub fn object_histogram(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for realm in self.contexts.values() {
+            for (class_name, proxy) in realm.proxy_registry.borrow().iter() {
+                let instance_ct = proxy.proxy_instance_id_mappings.borrow().len();
+                if instance_ct > 0 {
+                    *counts.entry(class_name.clone()).or_insert(0) += instance_ct;
+                }
+            }
+        }
+        let proxy_instance_ct: usize = counts.values().sum();
+
+        let mu = self.memory_usage();
+        let array_ct = mu.array_count.max(0) as usize;
+        let function_ct = (mu.js_func_count + mu.c_func_count).max(0) as usize;
+        let object_ct = (mu.obj_count.max(0) as usize)
+            .saturating_sub(array_ct + function_ct + proxy_instance_ct);
+        for &(name, ct) in &[
+            ("Array", array_ct),
+            ("Function", function_ct),
+            ("Object", object_ct),
+        ] {
+            if ct > 0 {
+                *counts.entry(name.to_string()).or_insert(0) += ct;
+            }
+        }
+
+        let mut histogram: Vec<(String, usize)> = counts.into_iter().collect();
+        histogram.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        histogram
+    }
+
     pub(crate) fn pre_process(mut script: Script) -> Result<Script, JsError> {
         Self::do_with(|q_js_rt| {
             for pp in &q_js_rt.script_pre_processors {
@@ -434,6 +502,9 @@ impl QuickJsRuntimeAdapter {
         log::debug!("QuickJsRuntime::drop_context: {}", id);
 
         QuickJsRuntimeAdapter::do_with(|rt| {
+            rt.unhandled_rejections
+                .borrow_mut()
+                .retain(|rejection| rejection.realm_id != id);
             let q_ctx = rt.get_context(id);
             log::trace!("QuickJsRuntime::q_ctx.free: {}", id);
             q_ctx.free();
@@ -508,6 +579,15 @@ impl QuickJsRuntimeAdapter {
             compiled_module_loaders: vec![],
             script_pre_processors: vec![],
             interrupt_handler: None,
+            unhandled_rejection_handler: None,
+            unhandled_rejections: RefCell::new(vec![]),
+            atom_count_threshold: None,
+            atom_count_threshold_exceeded: Cell::new(false),
+            atom_count_check_interval: DEFAULT_ATOM_COUNT_CHECK_INTERVAL,
+            atom_count_last_check: Cell::new(None),
+            max_pending_promises: None,
+            runtime_data: RefCell::new(None),
+            custom_allocator: None,
         };
 
         modules::set_module_loader(&q_rt);
@@ -524,10 +604,134 @@ impl QuickJsRuntimeAdapter {
         interrupt_handler: I,
     ) -> &mut Self {
         self.interrupt_handler = Some(Box::new(interrupt_handler));
-        interrupthandler::init(self);
         self
     }
 
+    /// set a handler which decides what to do with promises which were rejected without a rejection handler
+    /// the handler is called after pending jobs have been run, so rejections which get a handler in the mean time are not reported
+    pub fn set_unhandled_rejection_handler<
+        H: Fn(&QuickJsRealmAdapter, &QuickJsValueAdapter) -> RejectionAction + 'static,
+    >(
+        &mut self,
+        handler: H,
+    ) -> &mut Self {
+        self.unhandled_rejection_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// set a callback which is invoked when the number of atoms (interned strings like property names) exceeds a threshold
+    /// the callback is called once every time the threshold is crossed, it is called again after the atom count has dropped below the threshold and exceeds it again
+    /// the atom count is checked after pending jobs have been run but at most once per check interval (1 second by default, see set_atom_count_check_interval)
+    /// because computing it walks the whole heap
+    pub fn set_atom_count_threshold<C: Fn(&QuickJsRuntimeAdapter, i64) + 'static>(
+        &mut self,
+        threshold: i64,
+        callback: C,
+    ) -> &mut Self {
+        self.atom_count_threshold = Some((threshold, Box::new(callback)));
+        self.atom_count_threshold_exceeded.set(false);
+        self.atom_count_last_check.set(None);
+        self
+    }
+
+    /// set the minimal time between two checks of the atom count threshold, a zero interval checks after every run of the pending jobs
+    pub fn set_atom_count_check_interval(&mut self, interval: Duration) -> &mut Self {
+        self.atom_count_check_interval = interval;
+        self
+    }
+
+    /// limit the number of promises which are waiting for a native producer, see QuickJsRuntimeBuilder::max_pending_promises
+    pub fn set_max_pending_promises(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_pending_promises = max;
+        self
+    }
+
+    /// get the number of promises in all realms which are waiting for a native producer
+    pub fn pending_promise_count(&self) -> usize {
+        self.contexts
+            .values()
+            .map(|realm| realm.pending_promise_count())
+            .sum()
+    }
+
+    /// get the current number of atoms (interned strings like property names) in this runtime
+    pub fn atom_count(&self) -> i64 {
+        self.memory_usage().atom_count
+    }
+
+    fn check_atom_count_threshold(&self) {
+        if let Some((threshold, callback)) = &self.atom_count_threshold {
+            let now = Instant::now();
+            if let Some(last_check) = self.atom_count_last_check.get() {
+                if now.duration_since(last_check) < self.atom_count_check_interval {
+                    return;
+                }
+            }
+            self.atom_count_last_check.set(Some(now));
+
+            let atom_count = self.atom_count();
+            if atom_count > *threshold {
+                if !self.atom_count_threshold_exceeded.replace(true) {
+                    log::debug!("atom count {} exceeded threshold {}", atom_count, threshold);
+                    callback(self, atom_count);
+                }
+            } else {
+                self.atom_count_threshold_exceeded.set(false);
+            }
+        }
+    }
+
+    /// check a script before running it by making sure it does not reference any globals which are not in the allowed list
+    /// this is a best-effort static analysis (see [free_identifiers](crate::jsutils::identifiers::free_identifiers)), standard globals like Math or JSON need to be allowed explicitly
+    ///
+    /// `this` and `globalThis` are never allowed, even when they are in the allowed list, as they give access to every global e.g. `this['fe' + 'tch']`
+    ///
+    /// this is NOT a security boundary, it catches accidental use of unavailable globals but a hostile script can still reach
+    /// globals without naming them, e.g. through `[].constructor.constructor('return fetch')()`, untrusted scripts should run
+    /// in a realm which only contains the globals they may use
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let script = Script::new("check.js", "console.log(JSON.stringify({a: 1}));");
+    ///     assert!(q_js_rt.check_globals(&script, &["console", "JSON"]).is_ok());
+    ///     assert!(q_js_rt.check_globals(&script, &["console"]).is_err());
+    /// });
+    /// ```
+    pub fn check_globals(&self, script: &Script, allowed: &[&str]) -> Result<(), JsError> {
+        let not_allowed: Vec<String> = free_identifiers(script.get_code())
+            .into_iter()
+            .filter(|name| {
+                name == "this" || name == "globalThis" || !allowed.contains(&name.as_str())
+            })
+            .collect();
+        if not_allowed.is_empty() {
+            Ok(())
+        } else {
+            Err(JsError::new_string(format!(
+                "script {} references globals which are not allowed: {}",
+                script.get_path(),
+                not_allowed.join(", ")
+            )))
+        }
+    }
+
+    /// associate a single rust object with this runtime, this replaces any previously set data
+    /// the data is stored as the runtime opaque of the JSRuntime so it can be retrieved from any native callback with [get_runtime_data]
+    pub fn set_runtime_data<T: 'static>(&self, data: T) {
+        let boxed: Box<Rc<dyn Any>> = Box::new(Rc::new(data));
+        let data_ptr = &*boxed as *const Rc<dyn Any> as *mut c_void;
+        unsafe { q::JS_SetRuntimeOpaque(self.runtime, data_ptr) };
+        self.runtime_data.replace(Some(boxed));
+    }
+
+    /// get the rust object associated with this runtime, None if no data was set or if the data is not a T
+    pub fn get_runtime_data<T: 'static>(&self) -> Option<Rc<T>> {
+        unsafe { get_runtime_data(self.runtime) }
+    }
+
     pub fn add_script_module_loader(&mut self, sml: ScriptModuleLoaderAdapter) {
         self.script_module_loaders.push(sml);
     }
@@ -646,15 +850,53 @@ impl QuickJsRuntimeAdapter {
     /// move this to a quickjs_utils::pending_jobs so it can be used without doing QuickjsRuntime.do_with()
     pub fn run_pending_jobs_if_any(&self) {
         log::trace!("quick_js_rt.run_pending_jobs_if_any");
+        self.run_pending_jobs_with(|res| match res {
+            Ok(_) => {
+                log::trace!("run_pending_job OK!");
+            }
+            Err(e) => {
+                log::error!("run_pending_job failed: {}", e);
+            }
+        });
+        self.check_atom_count_threshold();
+    }
+
+    /// run all pending jobs (microtasks), the callback is invoked after every job with the result of that job
+    /// unhandled promise rejections for which the handler returned RejectionAction::Throw are passed to the callback as errors after all jobs have run
+    pub fn run_pending_jobs_with<C>(&self, mut callback: C)
+    where
+        C: FnMut(Result<(), JsError>),
+    {
         while self.has_pending_jobs() {
             log::trace!("quick_js_rt.has_pending_jobs!");
-            let res = self.run_pending_job();
-            match res {
-                Ok(_) => {
-                    log::trace!("run_pending_job OK!");
-                }
-                Err(e) => {
-                    log::error!("run_pending_job failed: {}", e);
+            callback(self.run_pending_job());
+        }
+        self.process_unhandled_rejections(&mut callback);
+    }
+
+    fn process_unhandled_rejections<C>(&self, callback: &mut C)
+    where
+        C: FnMut(Result<(), JsError>),
+    {
+        let handler = match &self.unhandled_rejection_handler {
+            Some(handler) => handler,
+            None => return,
+        };
+        let rejections: Vec<UnhandledRejection> =
+            self.unhandled_rejections.borrow_mut().drain(..).collect();
+        for rejection in rejections {
+            if let Some(realm) = self.get_realm(rejection.realm_id.as_str()) {
+                match handler(realm, &rejection.reason) {
+                    RejectionAction::Ignore => {}
+                    RejectionAction::Log => unsafe {
+                        promises::log_unhandled_rejection(realm.context, &rejection.reason);
+                    },
+                    RejectionAction::Throw => {
+                        callback(Err(promises::rejection_to_js_error(
+                            realm,
+                            &rejection.reason,
+                        )));
+                    }
                 }
             }
         }
@@ -703,11 +945,16 @@ impl Drop for QuickJsRuntimeAdapter {
         // drop contexts first, should be done when Dropping EsRuntime?
         log::trace!("drop QuickJsRuntime, dropping contexts");
 
+        self.unhandled_rejections.borrow_mut().clear();
+
         self.contexts.clear();
         log::trace!("drop QuickJsRuntime, after dropping contexts");
 
         log::trace!("before JS_FreeRuntime");
-        unsafe { q::JS_FreeRuntime(self.runtime) };
+        unsafe {
+            q::JS_SetRuntimeOpaque(self.runtime, std::ptr::null_mut());
+            q::JS_FreeRuntime(self.runtime)
+        };
         log::trace!("after JS_FreeRuntime");
     }
 }
@@ -762,6 +1009,19 @@ impl QuickJsRuntimeAdapter {
     }
 }
 
+/// get the rust object associated with a runtime by calling [QuickJsRuntimeAdapter::set_runtime_data]
+/// None if no data was set or if the data is not a T
+/// # Safety
+/// please ensure the passed JSRuntime is still valid
+pub unsafe fn get_runtime_data<T: 'static>(runtime: *mut q::JSRuntime) -> Option<Rc<T>> {
+    let data_ptr = q::JS_GetRuntimeOpaque(runtime) as *const Rc<dyn Any>;
+    if data_ptr.is_null() {
+        None
+    } else {
+        (*data_ptr).clone().downcast::<T>().ok()
+    }
+}
+
 /// Helper for creating CStrings.
 pub(crate) fn make_cstring(value: &str) -> Result<CString, JsError> {
     let res = CString::new(value);
@@ -777,12 +1037,20 @@ pub(crate) fn make_cstring(value: &str) -> Result<CString, JsError> {
 pub mod tests {
     use crate::builder::QuickJsRuntimeBuilder;
     use crate::quickjsrealmadapter::QuickJsRealmAdapter;
-    use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+    use crate::quickjsruntimeadapter::{get_runtime_data, QuickJsRuntimeAdapter};
+    use crate::reflection::Proxy;
 
     use std::panic;
 
     use crate::jsutils::modules::ScriptModuleLoader;
     use crate::jsutils::Script;
+    use crate::quickjs_utils;
+    use crate::quickjs_utils::errors;
+    use libquickjs_sys as q;
+    use std::os::raw::c_int;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
 
     struct FooScriptModuleLoader {}
     impl ScriptModuleLoader for FooScriptModuleLoader {
@@ -801,6 +1069,232 @@ pub mod tests {
         }
     }
 
+    unsafe extern "C" fn ok_job(
+        _ctx: *mut q::JSContext,
+        _argc: c_int,
+        _argv: *mut q::JSValue,
+    ) -> q::JSValue {
+        quickjs_utils::new_undefined()
+    }
+
+    unsafe extern "C" fn failing_job(
+        ctx: *mut q::JSContext,
+        _argc: c_int,
+        _argv: *mut q::JSValue,
+    ) -> q::JSValue {
+        let err = errors::new_error(ctx, "Error", "job failed", "").expect("could not create err");
+        errors::throw(ctx, err)
+    }
+
+    #[test]
+    fn test_run_pending_jobs_with() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let results = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            unsafe {
+                q::JS_EnqueueJob(realm.context, Some(failing_job), 0, std::ptr::null_mut());
+                q::JS_EnqueueJob(realm.context, Some(ok_job), 0, std::ptr::null_mut());
+            }
+            let mut results = vec![];
+            q_js_rt.run_pending_jobs_with(|res| {
+                results.push(res.map_err(|e| e.get_message().to_string()));
+            });
+            results
+        });
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], Err("job failed".to_string()));
+        assert_eq!(results[1], Ok(()));
+    }
+
+    #[test]
+    fn test_check_globals() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let allowed = Script::new(
+                "test_check_globals_allowed.js",
+                "let msg = 'hi'; console.log(msg);",
+            );
+            assert!(q_js_rt.check_globals(&allowed, &["console"]).is_ok());
+
+            let rejected = Script::new(
+                "test_check_globals_rejected.js",
+                "console.log('hi'); fetch('https://example.com');",
+            );
+            let err = q_js_rt
+                .check_globals(&rejected, &["console"])
+                .expect_err("fetch was allowed");
+            assert!(err.get_message().ends_with("not allowed: fetch"));
+
+            let shadowed = Script::new(
+                "test_check_globals_shadowed.js",
+                "function f(fetch) { return fetch; } fetch('https://example.com');",
+            );
+            let err = q_js_rt
+                .check_globals(&shadowed, &["console"])
+                .expect_err("fetch was allowed");
+            assert!(err.get_message().ends_with("not allowed: fetch"));
+
+            let global_object = Script::new(
+                "test_check_globals_this.js",
+                "this['fe' + 'tch']('https://example.com'); globalThis.fetch('https://example.com');",
+            );
+            let err = q_js_rt
+                .check_globals(&global_object, &["console", "this", "globalThis"])
+                .expect_err("the global object was allowed");
+            assert!(err.get_message().ends_with("not allowed: globalThis, this"));
+        });
+    }
+
+    #[test]
+    fn test_runtime_data() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            q_js_rt.set_runtime_data(std::cell::Cell::new(0));
+            let realm = q_js_rt.get_main_realm();
+            let increment_by_one = realm
+                .create_function(
+                    "incrementByOne",
+                    |realm, _this, _args| {
+                        let counter = unsafe {
+                            get_runtime_data::<std::cell::Cell<i32>>(q::JS_GetRuntime(
+                                realm.context,
+                            ))
+                        }
+                        .expect("no runtime data");
+                        counter.set(counter.get() + 1);
+                        realm.create_undefined()
+                    },
+                    0,
+                )
+                .expect("could not create function");
+            let increment_by_ten = realm
+                .create_function(
+                    "incrementByTen",
+                    |realm, _this, _args| {
+                        let counter = QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+                            q_js_rt.get_runtime_data::<std::cell::Cell<i32>>()
+                        })
+                        .expect("no runtime data");
+                        counter.set(counter.get() + 10);
+                        realm.create_undefined()
+                    },
+                    0,
+                )
+                .expect("could not create function");
+            let global = realm.get_global().expect("could not get global");
+            realm
+                .set_object_property(&global, "incrementByOne", &increment_by_one)
+                .expect("could not set function");
+            realm
+                .set_object_property(&global, "incrementByTen", &increment_by_ten)
+                .expect("could not set function");
+            realm
+                .eval(Script::new(
+                    "test_runtime_data.js",
+                    "incrementByOne(); incrementByTen(); incrementByOne();",
+                ))
+                .expect("script failed");
+
+            assert!(q_js_rt.get_runtime_data::<String>().is_none());
+            q_js_rt
+                .get_runtime_data::<std::cell::Cell<i32>>()
+                .expect("no runtime data")
+                .get()
+        });
+        assert_eq!(res, 12);
+    }
+
+    #[test]
+    fn test_object_histogram() {
+        let rt = QuickJsRuntimeBuilder::new().build();
+        let (before, after) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("HistogramTest")
+                .constructor(|_rt, _realm, _id, _args| Ok(()))
+                .install(realm, true)
+                .expect("could not install proxy");
+            let count_of = |q_js_rt: &QuickJsRuntimeAdapter| {
+                q_js_rt
+                    .object_histogram()
+                    .into_iter()
+                    .find(|(class_name, _ct)| class_name == "HistogramTest")
+                    .map(|(_class_name, ct)| ct)
+                    .unwrap_or(0)
+            };
+            let before = count_of(q_js_rt);
+            realm
+                .eval(Script::new(
+                    "test_object_histogram.js",
+                    "globalThis.histogramInstances = []; for (let x = 0; x < 250; x++) {histogramInstances.push(new HistogramTest());}",
+                ))
+                .expect("script failed");
+            let after = count_of(q_js_rt);
+            let histogram = q_js_rt.object_histogram();
+            assert!(histogram.iter().any(|(class_name, _ct)| class_name == "Object"));
+            (before, after)
+        });
+        assert_eq!(before, 0);
+        assert_eq!(after, 250);
+    }
+
+    #[test]
+    fn test_atom_count_threshold() {
+        let fired = Arc::new(AtomicI64::new(0));
+        let fired_clone = fired.clone();
+        let rt = QuickJsRuntimeBuilder::new()
+            .atom_count_threshold(5000, move |_q_js_rt, atom_count| {
+                fired_clone.store(atom_count, Ordering::SeqCst);
+            })
+            .atom_count_check_interval(Duration::ZERO)
+            .build();
+
+        let before = rt.exe_rt_task_in_event_loop(|q_js_rt| q_js_rt.atom_count());
+        assert!(before < 5000);
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+
+        rt.eval_sync(
+            None,
+            Script::new(
+                "atoms.js",
+                "globalThis.atomHolder = {}; for (let x = 0; x < 10000; x++) {atomHolder['unique_key_' + x] = x;}",
+            ),
+        )
+        .expect("script failed");
+
+        let after = rt.exe_rt_task_in_event_loop(|q_js_rt| q_js_rt.atom_count());
+        assert!(after >= before + 10000);
+        assert!(fired.load(Ordering::SeqCst) > 5000);
+    }
+
+    #[test]
+    fn test_atom_count_check_interval() {
+        let fired = Arc::new(AtomicI64::new(0));
+        let fired_clone = fired.clone();
+        let rt = QuickJsRuntimeBuilder::new()
+            .atom_count_threshold(5000, move |_q_js_rt, atom_count| {
+                fired_clone.store(atom_count, Ordering::SeqCst);
+            })
+            .atom_count_check_interval(Duration::from_secs(3600))
+            .build();
+
+        // the first run of the pending jobs checks the atom count, later runs are skipped until the interval has passed
+        rt.eval_sync(None, Script::new("no_atoms.js", "1 + 1;"))
+            .expect("script failed");
+        rt.eval_sync(
+            None,
+            Script::new(
+                "atoms.js",
+                "globalThis.atomHolder = {}; for (let x = 0; x < 10000; x++) {atomHolder['unique_key_' + x] = x;}",
+            ),
+        )
+        .expect("script failed");
+
+        let atom_count = rt.exe_rt_task_in_event_loop(|q_js_rt| q_js_rt.atom_count());
+        assert!(atom_count > 5000);
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+    }
+
     #[test]
     fn test_mem_usage() {
         let rt = QuickJsRuntimeBuilder::new()
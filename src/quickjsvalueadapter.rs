@@ -1,8 +1,9 @@
 //! JSValueRef is a wrapper for quickjs's JSValue. it provides automatic reference counting making it safer to use  
 
 use crate::jsutils::{JsError, JsValueType};
-use crate::quickjs_utils::typedarrays::is_typed_array;
-use crate::quickjs_utils::{arrays, errors, functions, primitives, promises};
+use crate::quickjs_utils::typedarrays::{get_typed_array_info, is_typed_array, TypedArrayInfo};
+use crate::quickjs_utils::{arrays, errors, functions, objects, primitives, promises};
+use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::reflection::is_proxy_instance;
 use libquickjs_sys as q;
 use std::hash::{Hash, Hasher};
@@ -108,6 +109,7 @@ impl std::fmt::Debug for QuickJsValueAdapter {
             TAG_INT => write!(f, "Int(?)"),
             TAG_FLOAT64 => write!(f, "Float(?)"),
             TAG_STRING => write!(f, "String(?)"),
+            TAG_SYMBOL => write!(f, "Symbol(?)"),
             TAG_OBJECT => write!(f, "Object(?)"),
             TAG_MODULE => write!(f, "Module(?)"),
             _ => write!(f, "?"),
@@ -248,11 +250,16 @@ impl QuickJsValueAdapter {
     pub fn is_string(&self) -> bool {
         unsafe { q::JS_IsString(self.value) }
     }
+
+    /// return true if the wrapped value represents a JS Symbol value
+    pub fn is_symbol(&self) -> bool {
+        self.borrow_value().tag == TAG_SYMBOL
+    }
 }
 
 pub(crate) const TAG_BIG_INT: i64 = -10;
 //pub(crate) const TAG_BIG_FLOAT: i64 = -9;
-//pub(crate) const TAG_SYMBOL: i64 = -8;
+pub(crate) const TAG_SYMBOL: i64 = -8;
 pub(crate) const TAG_STRING: i64 = -7;
 pub(crate) const TAG_MODULE: i64 = -3;
 pub(crate) const TAG_FUNCTION_BYTECODE: i64 = -2;
@@ -317,6 +324,7 @@ impl QuickJsValueAdapter {
     pub fn type_of(&self) -> &'static str {
         match self.get_tag() {
             TAG_BIG_INT => "bigint",
+            TAG_SYMBOL => "symbol",
             TAG_STRING => "string",
             TAG_MODULE => "module",
             TAG_FUNCTION_BYTECODE => "function",
@@ -345,6 +353,12 @@ impl QuickJsValueAdapter {
         }
     }
 
+    /// check if this value is truthy according to JS semantics, e.g. 0, "", null, undefined, NaN and false are falsy
+    pub fn is_truthy(&self) -> bool {
+        // values without a context are never refcounted so JS_ToBool does not need the context for them
+        unsafe { q::JS_ToBool(self.context, self.value) != 0 }
+    }
+
     pub fn to_i32(&self) -> i32 {
         if self.get_js_type() == JsValueType::I32 {
             primitives::to_i32(self).expect("could not convert to i32")
@@ -381,6 +395,48 @@ impl QuickJsValueAdapter {
         }
     }
 
+    /// get the enumerable own string keyed properties of this object as key/value pairs, like `Object.entries(obj)`
+    pub fn object_entries(&self) -> Result<Vec<(String, QuickJsValueAdapter)>, JsError> {
+        if self.is_object() {
+            unsafe { objects::get_entries(self.context, self) }
+        } else {
+            Err(JsError::new_str("this value is not an object"))
+        }
+    }
+
+    /// get the file name and line number where this function was defined
+    /// returns None if this is not a function, if it is a native function or if the function was compiled without debug info
+    pub fn function_source_location(&self) -> Option<(String, u32)> {
+        if !self.is_function() {
+            return None;
+        }
+        let file_name = unsafe { objects::get_property(self.context, self, "fileName") }.ok()?;
+        let line_number =
+            unsafe { objects::get_property(self.context, self, "lineNumber") }.ok()?;
+        if !file_name.is_string() || !line_number.is_i32() {
+            return None;
+        }
+        Some((file_name.to_string().ok()?, line_number.to_i32() as u32))
+    }
+
+    /// get the kind, length, byte length and byte offset of this value if it is a TypedArray
+    pub fn typed_array_info(&self) -> Option<TypedArrayInfo> {
+        unsafe { get_typed_array_info(self.context, self) }
+            .ok()
+            .flatten()
+    }
+
+    /// create an object graph from a serde_json Value and freeze it recursively, e.g. to pass configuration to scripts
+    /// which can not be altered by those scripts, in strict mode every attempt to mutate the value throws a TypeError
+    pub fn new_frozen_from_serde(
+        realm: &QuickJsRealmAdapter,
+        value: &serde_json::Value,
+    ) -> Result<Self, JsError> {
+        let res = realm.serde_value_to_value_adapter(value.clone())?;
+        objects::deep_freeze_q(realm, &res)?;
+        Ok(res)
+    }
+
     pub fn to_str(&self) -> Result<&str, JsError> {
         if self.get_js_type() == JsValueType::String {
             unsafe { primitives::to_str(self.context, self) }
@@ -394,6 +450,7 @@ impl QuickJsValueAdapter {
 pub mod tests {
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::{JsValueType, Script};
+    use crate::quickjsvalueadapter::QuickJsValueAdapter;
 
     #[test]
     fn test_to_str() {
@@ -415,4 +472,74 @@ pub mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_function_source_location() {
+        let rt = init_test_rt();
+        rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let func = q_ctx
+                .eval(Script::new(
+                    "test_function_source_location.es",
+                    "let locateMeValue = 1;\n\nfunction locateMe() {\n    return locateMeValue;\n}\nlocateMe;",
+                ))
+                .expect("script failed");
+            assert_eq!(
+                func.function_source_location(),
+                Some(("test_function_source_location.es".to_string(), 3))
+            );
+
+            let native_func = q_ctx
+                .eval(Script::new("test_function_source_location2.es", "Math.max;"))
+                .expect("script failed");
+            assert_eq!(native_func.function_source_location(), None);
+
+            let not_a_func = q_ctx.create_i32(1).expect("could not create i32");
+            assert_eq!(not_a_func.function_source_location(), None);
+        });
+    }
+
+    #[test]
+    fn test_new_frozen_from_serde() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let config = serde_json::json!({"name": "app", "db": {"host": "localhost", "ports": [5432, 5433]}});
+            let frozen = QuickJsValueAdapter::new_frozen_from_serde(q_ctx, &config)
+                .expect("could not create config");
+            q_ctx
+                .set_object_property(&q_ctx.get_global().unwrap(), "config", &frozen)
+                .expect("could not set config");
+            q_ctx
+                .eval(Script::new(
+                    "test_new_frozen_from_serde.js",
+                    r#"
+                'use strict';
+                const mutations = [
+                    () => {config.name = 'other';},
+                    () => {config.db.host = 'remote';},
+                    () => {config.db.extra = true;},
+                    () => {delete config.db;},
+                    () => {config.db.ports[0] = 1;},
+                    () => {config.db.ports.push(1);}
+                ];
+                mutations.map((mutate) => {
+                    try {
+                        mutate();
+                        return 'mutated';
+                    } catch (e) {
+                        return e.name;
+                    }
+                }).join(',') + '|' + JSON.stringify(config);
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(
+            res,
+            "TypeError,TypeError,TypeError,TypeError,TypeError,TypeError|{\"name\":\"app\",\"db\":{\"host\":\"localhost\",\"ports\":[5432,5433]}}"
+        );
+    }
 }
@@ -175,19 +175,16 @@ pub fn dispatch_event(
         instance_id,
         event_id,
         |listeners| -> Result<(), JsError> {
-            let func_args = [event];
+            let func_args = [event.clone()];
             for entry in listeners {
                 let listener = entry.0;
                 let _res = functions::call_function_q(q_ctx, listener, &func_args, None)?;
-
-                // todo chekc if _res is bool, for cancel and such
-                // and if event is cancelabble and preventDefault was called and such
             }
             Ok(())
         },
     )?;
 
-    Ok(true)
+    is_not_prevented(q_ctx, &event)
 }
 
 /// dispatch an Event on a Proxy class
@@ -203,19 +200,45 @@ pub fn dispatch_static_event(
         proxy_class_name,
         event_id,
         |listeners| -> Result<(), JsError> {
-            let func_args = [event];
+            let func_args = [event.clone()];
             for entry in listeners {
                 let listener = entry.0;
                 let _res = functions::call_function_q(q_ctx, listener, &func_args, None)?;
-
-                // todo chekc if _res is bool, for cancel and such
-                // and if event is cancelabble and preventDefault was called and such
             }
             Ok(())
         },
     )?;
 
-    Ok(true)
+    is_not_prevented(q_ctx, &event)
+}
+
+/// false if a listener called preventDefault() on the event, the defaultPrevented member of an Event is only set for cancelable events
+fn is_not_prevented(
+    q_ctx: &QuickJsRealmAdapter,
+    event: &QuickJsValueAdapter,
+) -> Result<bool, JsError> {
+    if !event.is_object() {
+        return Ok(true);
+    }
+    let default_prevented = objects::get_property_q(q_ctx, event, "defaultPrevented")?;
+    Ok(!default_prevented.is_truthy())
+}
+
+/// the event id and event of the args of dispatchEvent, this is either an Event (which has a type) or an eventId and an eventObj
+fn get_dispatch_args(
+    q_ctx: &QuickJsRealmAdapter,
+    args: &[QuickJsValueAdapter],
+) -> Result<(String, QuickJsValueAdapter), JsError> {
+    if args.len() == 1 && args[0].is_object() {
+        let type_ref = objects::get_property_q(q_ctx, &args[0], "type")?;
+        Ok((primitives::to_string_q(q_ctx, &type_ref)?, args[0].clone()))
+    } else if args.len() == 2 && args[0].is_string() {
+        Ok((primitives::to_string_q(q_ctx, &args[0])?, args[1].clone()))
+    } else {
+        Err(JsError::new_str(
+            "dispatchEvent requires an Event or 2 arguments (eventId: String and eventObj: Object)",
+        ))
+    }
 }
 
 pub fn _set_event_bubble_target() {
@@ -364,20 +387,11 @@ unsafe extern "C" fn ext_dispatch_event(
 
         let proxy_info = get_proxy_instance_info(this_ref.borrow_value());
 
-        if args.len() != 2 || !args[0].is_string() {
-            Err(JsError::new_str(
-                "dispatchEvent requires at least 2 arguments (eventId: String and eventObj: Object)",
-            ))
-        } else {
-            let event_id = primitives::to_string_q(q_ctx, &args[0])?;
-            let evt_obj = args[1].clone();
+        let (event_id, evt_obj) = get_dispatch_args(q_ctx, &args)?;
 
-            let proxy = get_proxy(q_ctx, proxy_info.class_name.as_str()).unwrap();
+        let proxy = get_proxy(q_ctx, proxy_info.class_name.as_str()).unwrap();
 
-            let res = dispatch_event(q_ctx, &proxy, proxy_info.id, event_id.as_str(), evt_obj)?;
-
-            Ok(res)
-        }
+        dispatch_event(q_ctx, &proxy, proxy_info.id, event_id.as_str(), evt_obj)
     });
     match res {
         Ok(res) => {
@@ -498,19 +512,9 @@ unsafe extern "C" fn ext_dispatch_static_event(
 
         let proxy_name = get_static_proxy_class_name(q_ctx, &this_ref);
 
-        if args.len() != 2 || !args[0].is_string() {
-            Err(JsError::new_str(
-                "dispatchEvent requires at least 2 arguments (eventId: String and eventObj: Object)",
-            ))
-        } else {
-            let event_id = primitives::to_string_q(q_ctx, &args[0])?;
-            let evt_obj = args[1].clone();
+        let (event_id, evt_obj) = get_dispatch_args(q_ctx, &args)?;
 
-            let res =
-                dispatch_static_event(q_ctx, proxy_name.as_str(), event_id.as_str(), evt_obj)?;
-
-            Ok(res)
-        }
+        dispatch_static_event(q_ctx, proxy_name.as_str(), event_id.as_str(), evt_obj)
     });
     match res {
         Ok(res) => {
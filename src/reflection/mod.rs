@@ -12,10 +12,11 @@ use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use libquickjs_sys as q;
 use log::trace;
 use rand::{thread_rng, Rng};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::os::raw::{c_char, c_void};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 pub type JsProxyInstanceId = usize;
 
@@ -29,19 +30,19 @@ pub type ProxyConstructor = dyn Fn(
     ) -> Result<(), JsError>
     + 'static;
 pub type ProxyFinalizer = dyn Fn(&QuickJsRuntimeAdapter, &QuickJsRealmAdapter, usize) + 'static;
+/// the result of a native method, an Err is thrown in script as an Error with the same name (e.g. TypeError) and message
+/// so method bodies can simply use the ? operator to fail
+pub type NativeMethodResult = Result<QuickJsValueAdapter, JsError>;
 pub type ProxyMethod = dyn Fn(
         &QuickJsRuntimeAdapter,
         &QuickJsRealmAdapter,
         &usize,
         &[QuickJsValueAdapter],
-    ) -> Result<QuickJsValueAdapter, JsError>
+    ) -> NativeMethodResult
     + 'static;
+pub type ProxyMethodFactory = dyn Fn() -> Box<ProxyMethod> + 'static;
 pub type ProxyNativeMethod = q::JSCFunction;
-pub type ProxyStaticMethod = dyn Fn(
-        &QuickJsRuntimeAdapter,
-        &QuickJsRealmAdapter,
-        &[QuickJsValueAdapter],
-    ) -> Result<QuickJsValueAdapter, JsError>
+pub type ProxyStaticMethod = dyn Fn(&QuickJsRuntimeAdapter, &QuickJsRealmAdapter, &[QuickJsValueAdapter]) -> NativeMethodResult
     + 'static;
 pub type ProxyStaticNativeMethod = q::JSCFunction;
 pub type ProxyStaticGetter = dyn Fn(&QuickJsRuntimeAdapter, &QuickJsRealmAdapter) -> Result<QuickJsValueAdapter, JsError>
@@ -81,6 +82,23 @@ pub type ProxyCatchAllSetter = dyn Fn(
         QuickJsValueAdapter,
     ) -> Result<(), JsError>
     + 'static;
+pub type ProxyCatchAllHas =
+    dyn Fn(&QuickJsRuntimeAdapter, &QuickJsRealmAdapter, &usize, &str) -> bool + 'static;
+pub type ProxyIndexedGetter = dyn Fn(
+        &QuickJsRuntimeAdapter,
+        &QuickJsRealmAdapter,
+        &usize,
+        u32,
+    ) -> Result<QuickJsValueAdapter, JsError>
+    + 'static;
+pub type ProxyIndexedSetter = dyn Fn(
+        &QuickJsRuntimeAdapter,
+        &QuickJsRealmAdapter,
+        &usize,
+        u32,
+        QuickJsValueAdapter,
+    ) -> Result<(), JsError>
+    + 'static;
 
 static CNAME: &str = "ProxyInstanceClass\0";
 static SCNAME: &str = "ProxyStaticClass\0";
@@ -175,6 +193,51 @@ pub(crate) fn init_statics() {
     });
 }
 
+thread_local! {
+    static METHOD_STATS_ENABLED: Cell<bool> = Cell::new(false);
+    static METHOD_STATS: RefCell<HashMap<String, MethodStats>> = RefCell::new(HashMap::new());
+}
+
+/// timing stats for a Proxy method, see method_stats()
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MethodStats {
+    pub call_count: u64,
+    pub total_duration: Duration,
+}
+
+/// enable or disable recording of MethodStats for Proxy methods in the current thread
+pub(crate) fn set_method_stats_enabled(enabled: bool) {
+    METHOD_STATS_ENABLED.with(|rc| rc.set(enabled));
+}
+
+/// get the recorded stats of Proxy methods which were called in the current thread (the worker thread of a runtime)
+/// instance methods are keyed as `ClassName.prototype.method` and static methods as `ClassName.method`
+/// stats are only recorded when enabled with QuickJsRuntimeBuilder::proxy_method_stats
+pub fn method_stats() -> HashMap<String, MethodStats> {
+    METHOD_STATS.with(|rc| rc.borrow().clone())
+}
+
+/// clear the recorded stats of Proxy methods in the current thread
+pub fn reset_method_stats() {
+    METHOD_STATS.with(|rc| rc.borrow_mut().clear());
+}
+
+fn timed_method_call<R, K: FnOnce() -> String, C: FnOnce() -> R>(key: K, call: C) -> R {
+    if !METHOD_STATS_ENABLED.with(|rc| rc.get()) {
+        return call();
+    }
+    let start = Instant::now();
+    let res = call();
+    let elapsed = start.elapsed();
+    METHOD_STATS.with(|rc| {
+        let stats_map = &mut *rc.borrow_mut();
+        let stats = stats_map.entry(key()).or_default();
+        stats.call_count += 1;
+        stats.total_duration += elapsed;
+    });
+    res
+}
+
 fn next_id(proxy: &Proxy) -> usize {
     let mappings = &*proxy.proxy_instance_id_mappings.borrow();
     if mappings.len() == MAX_INSTANCE_NUM {
@@ -279,18 +342,25 @@ pub struct Proxy {
     pub(crate) constructor: Option<Box<ProxyConstructor>>,
     finalizers: Vec<Box<ProxyFinalizer>>,
     methods: HashMap<String, Box<ProxyMethod>>,
+    lazy_methods: HashMap<String, Box<ProxyMethodFactory>>,
+    materialized_lazy_methods: RefCell<HashMap<String, Rc<ProxyMethod>>>,
     native_methods: HashMap<String, ProxyNativeMethod>,
     static_methods: HashMap<String, Box<ProxyStaticMethod>>,
     static_native_methods: HashMap<String, ProxyStaticNativeMethod>,
     static_getters_setters: HashMap<String, (Box<ProxyStaticGetter>, Box<ProxyStaticSetter>)>,
     getters_setters: HashMap<String, (Box<ProxyGetter>, Box<ProxySetter>)>,
+    read_only_getters: HashMap<String, Box<ProxyGetter>>,
     catch_all: Option<(Box<ProxyCatchAllGetter>, Box<ProxyCatchAllSetter>)>,
+    catch_all_has: Option<Box<ProxyCatchAllHas>>,
+    indexed_getter: Option<Box<ProxyIndexedGetter>>,
+    indexed_setter: Option<Box<ProxyIndexedSetter>>,
     static_catch_all: Option<(
         Box<ProxyStaticCatchAllGetter>,
         Box<ProxyStaticCatchAllSetter>,
     )>,
     is_event_target: bool,
     is_static_event_target: bool,
+    extends: Option<String>,
     pub(crate) proxy_instance_id_mappings: RefCell<HashMap<usize, Box<ProxyInstanceInfo>>>,
 }
 
@@ -306,6 +376,60 @@ pub fn get_proxy(q_ctx: &QuickJsRealmAdapter, class_name: &str) -> Option<Rc<Pro
     registry.get(class_name).cloned()
 }
 
+/// the value of a variant of an enum installed with install_enum
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnumValue<'a> {
+    Int(i32),
+    F64(f64),
+    Str(&'a str),
+}
+
+impl From<i32> for EnumValue<'_> {
+    fn from(val: i32) -> Self {
+        EnumValue::Int(val)
+    }
+}
+
+impl<'a> From<&'a str> for EnumValue<'a> {
+    fn from(val: &'a str) -> Self {
+        EnumValue::Str(val)
+    }
+}
+
+/// expose a rust enum to script as a frozen object on globalThis which maps the variant names to their values
+/// # Example
+/// ```rust
+/// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+/// use quickjs_runtime::jsutils::Script;
+/// use quickjs_runtime::reflection::{install_enum, EnumValue};
+/// let rt = QuickJsRuntimeBuilder::new().build();
+/// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+///     let realm = q_js_rt.get_main_realm();
+///     install_enum(realm, "Color", &[("Red", EnumValue::Int(0)), ("Green", EnumValue::Int(1))]).expect("install failed");
+/// });
+/// let res = rt.eval_sync(None, Script::new("enum.js", "Color.Green;")).expect("script failed");
+/// assert_eq!(res.get_i32(), 1);
+/// ```
+pub fn install_enum(
+    realm: &QuickJsRealmAdapter,
+    name: &str,
+    variants: &[(&str, EnumValue)],
+) -> Result<QuickJsValueAdapter, JsError> {
+    let enum_obj = realm.create_object()?;
+    for (variant_name, value) in variants {
+        let value_ref = match value {
+            EnumValue::Int(val) => realm.create_i32(*val)?,
+            EnumValue::F64(val) => realm.create_f64(*val)?,
+            EnumValue::Str(val) => realm.create_string(val)?,
+        };
+        realm.set_object_property(&enum_obj, variant_name, &value_ref)?;
+    }
+    objects::freeze_q(realm, &enum_obj)?;
+    let global = realm.get_global()?;
+    set_property2_q(realm, &global, name, &enum_obj, 0)?;
+    Ok(enum_obj)
+}
+
 impl Proxy {
     #[allow(dead_code)]
     pub fn new() -> Self {
@@ -315,15 +439,22 @@ impl Proxy {
             constructor: None,
             finalizers: Default::default(),
             methods: Default::default(),
+            lazy_methods: Default::default(),
+            materialized_lazy_methods: RefCell::new(Default::default()),
             native_methods: Default::default(),
             static_methods: Default::default(),
             static_native_methods: Default::default(),
             static_getters_setters: Default::default(),
             getters_setters: Default::default(),
+            read_only_getters: Default::default(),
             catch_all: None,
+            catch_all_has: None,
+            indexed_getter: None,
+            indexed_setter: None,
             static_catch_all: None,
             is_event_target: false,
             is_static_event_target: false,
+            extends: None,
             proxy_instance_id_mappings: RefCell::new(Default::default()),
         }
     }
@@ -404,12 +535,35 @@ impl Proxy {
                 &QuickJsRealmAdapter,
                 &usize,
                 &[QuickJsValueAdapter],
-            ) -> Result<QuickJsValueAdapter, JsError>
+            ) -> NativeMethodResult
             + 'static,
     {
         self.methods.insert(name.to_string(), Box::new(method));
         self
     }
+    /// add a lazily created method to the Proxy class, this method will be available as a member of instances of the Proxy class
+    /// the factory is only called when the method is accessed for the first time, which keeps installing Proxy classes with many rarely used methods cheap
+    pub fn lazy_method<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn() -> Box<ProxyMethod> + 'static,
+    {
+        self.lazy_methods
+            .insert(name.to_string(), Box::new(factory));
+        self
+    }
+    /// get a lazy method, creating it with its factory if it was not accessed before
+    fn get_lazy_method(&self, name: &str) -> Option<Rc<ProxyMethod>> {
+        if let Some(method) = self.materialized_lazy_methods.borrow().get(name) {
+            return Some(method.clone());
+        }
+        let factory = self.lazy_methods.get(name)?;
+        log::trace!("materializing lazy method {}", name);
+        let method: Rc<ProxyMethod> = Rc::from(factory());
+        self.materialized_lazy_methods
+            .borrow_mut()
+            .insert(name.to_string(), method.clone());
+        Some(method)
+    }
     /// add a method to the Proxy class, this method will be available as a member of instances of the Proxy class
     pub fn native_method(mut self, name: &str, method: ProxyNativeMethod) -> Self {
         self.native_methods.insert(name.to_string(), method);
@@ -422,7 +576,7 @@ impl Proxy {
                 &QuickJsRuntimeAdapter,
                 &QuickJsRealmAdapter,
                 &[QuickJsValueAdapter],
-            ) -> Result<QuickJsValueAdapter, JsError>
+            ) -> NativeMethodResult
             + 'static,
     {
         self.static_methods
@@ -504,6 +658,23 @@ impl Proxy {
     {
         self.getter_setter(name, getter, |_rt, _realm, _id, _val| Ok(()))
     }
+    /// add a read-only getter to the Proxy class, this may be used for members which are computed from the state of an instance
+    ///
+    /// unlike [Proxy::getter] assigning the member is rejected, script assignments throw a TypeError and Reflect.set returns false
+    /// (a native class can not see whether the assigning code is strict so sloppy mode assignments throw as well)
+    pub fn read_only_getter<G>(mut self, name: &str, getter: G) -> Self
+    where
+        G: Fn(
+                &QuickJsRuntimeAdapter,
+                &QuickJsRealmAdapter,
+                &usize,
+            ) -> Result<QuickJsValueAdapter, JsError>
+            + 'static,
+    {
+        self.read_only_getters
+            .insert(name.to_string(), Box::new(getter));
+        self
+    }
     /// add a catchall getter and setter to the Proxy class, these will be used for properties which are not specifically defined as getter, setter or method in this Proxy
     pub fn catch_all_getter_setter<G, S>(mut self, getter: G, setter: S) -> Self
     where
@@ -527,6 +698,64 @@ impl Proxy {
 
         self
     }
+    /// add a check for the properties which are handled by the catch_all getter and setter, this is used by the `in` operator e.g. `'key' in obj`
+    /// without it `in` only finds the methods, getters and setters of this Proxy class and the own and inherited properties of the instance
+    pub fn catch_all_has<H>(mut self, has: H) -> Self
+    where
+        H: Fn(&QuickJsRuntimeAdapter, &QuickJsRealmAdapter, &usize, &str) -> bool + 'static,
+    {
+        self.catch_all_has = Some(Box::new(has));
+        self
+    }
+    /// add a getter for indexed members of instances of this Proxy class, e.g. `obj[0]`, this lets a Proxy class act like an array
+    /// the getter is called with the instance id and the index and takes precedence over the catch_all getter
+    /// a `length` member can be added with [Proxy::read_only_getter] so scripts can iterate the instance
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::reflection::Proxy;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     Proxy::new().name("Squares")
+    ///         .constructor(|_rt, _realm, _id, _args| Ok(()))
+    ///         .indexed_getter(|_rt, realm, _id, index| realm.create_i32((index * index) as i32))
+    ///         .install(realm, true).expect("could not install Squares");
+    /// });
+    /// let res = rt.eval_sync(None, Script::new("indexed.js", "new Squares()[3];")).expect("script failed");
+    /// assert_eq!(res.get_i32(), 9);
+    /// ```
+    pub fn indexed_getter<G>(mut self, getter: G) -> Self
+    where
+        G: Fn(
+                &QuickJsRuntimeAdapter,
+                &QuickJsRealmAdapter,
+                &usize,
+                u32,
+            ) -> Result<QuickJsValueAdapter, JsError>
+            + 'static,
+    {
+        self.indexed_getter = Some(Box::new(getter));
+        self
+    }
+    /// add a setter for indexed members of instances of this Proxy class, e.g. `obj[0] = 1`
+    /// the setter is called with the instance id, the index and the value and takes precedence over the catch_all setter
+    /// when a Proxy class has an indexed getter but no indexed setter assigning an indexed member is rejected like for [Proxy::read_only_getter]
+    pub fn indexed_setter<S>(mut self, setter: S) -> Self
+    where
+        S: Fn(
+                &QuickJsRuntimeAdapter,
+                &QuickJsRealmAdapter,
+                &usize,
+                u32,
+                QuickJsValueAdapter,
+            ) -> Result<(), JsError>
+            + 'static,
+    {
+        self.indexed_setter = Some(Box::new(setter));
+        self
+    }
     /// indicate the Proxy class should implement the EventTarget interface, this will result in the addEventListener, removeEventListener and dispatchEvent methods to be available on instances of the Proxy class
     pub fn event_target(mut self) -> Self {
         self.is_event_target = true;
@@ -537,6 +766,43 @@ impl Proxy {
         self.is_static_event_target = true;
         self
     }
+    /// let this Proxy class extend an installed Proxy class (namespace.ClassName)
+    /// instances of this class will inherit the methods, native methods, getters, setters, indexed getters and setters and catch_all of the base class (and its base classes)
+    /// and `instance instanceof Base` will be true, inherited members are called with the instance_id of this class
+    ///
+    /// script classes may extend a Proxy class as well (`class Puppy extends Dog {}`), members of the Proxy class take precedence
+    /// over members of the script class with the same name and are called with `this.method()` rather than `super.method()`
+    /// # Example
+    /// ```rust
+    /// use quickjs_runtime::builder::QuickJsRuntimeBuilder;
+    /// use quickjs_runtime::jsutils::Script;
+    /// use quickjs_runtime::reflection::Proxy;
+    /// let rt = QuickJsRuntimeBuilder::new().build();
+    /// rt.exe_rt_task_in_event_loop(|q_js_rt| {
+    ///     let realm = q_js_rt.get_main_realm();
+    ///     Proxy::new().name("Animal")
+    ///         .constructor(|_rt, _realm, _id, _args| Ok(()))
+    ///         .method("describe", |_rt, realm, _id, _args| realm.create_string("an animal"))
+    ///         .install(realm, true).expect("could not install Animal");
+    ///     Proxy::new().name("Dog").extends("Animal")
+    ///         .constructor(|_rt, _realm, _id, _args| Ok(()))
+    ///         .install(realm, true).expect("could not install Dog");
+    /// });
+    /// let res = rt.eval_sync(None, Script::new("extends.js", "let d = new Dog(); (d instanceof Animal) + ':' + d.describe();")).expect("script failed");
+    /// assert_eq!(res.get_str(), "true:an animal");
+    /// ```
+    pub fn extends(mut self, base_class_name: &str) -> Self {
+        self.extends = Some(base_class_name.to_string());
+        self
+    }
+    /// check if this Proxy class itself defines an instance method, native method or getter/setter with a certain name
+    fn has_instance_member(&self, name: &str) -> bool {
+        self.methods.contains_key(name)
+            || self.lazy_methods.contains_key(name)
+            || self.native_methods.contains_key(name)
+            || self.getters_setters.contains_key(name)
+            || self.read_only_getters.contains_key(name)
+    }
     /// install the Proxy class in a QuickJsContext, this is always needed as a final step to actually make the Proxy class work
     pub fn install(
         mut self,
@@ -546,6 +812,13 @@ impl Proxy {
         if self.name.is_none() {
             return Err(JsError::new_str("Proxy needs a name"));
         }
+        if let Some(base_class_name) = &self.extends {
+            if get_proxy(q_ctx, base_class_name).is_none() {
+                return Err(JsError::new_string(format!(
+                    "base Proxy {base_class_name} is not installed"
+                )));
+            }
+        }
 
         let prim_cn = self.get_class_name();
         let prim_cn2 = prim_cn.clone();
@@ -564,8 +837,14 @@ impl Proxy {
                 let instance = &args[0];
                 if instance.is_proxy_instance() {
                     let info = realm.get_proxy_instance_info(instance)?;
-                    if info.0.eq(prim_cn2.as_str()) {
-                        return realm.create_boolean(true);
+                    // walk up the extends chain of the instance's class
+                    let mut class_name_opt = Some(info.0);
+                    while let Some(class_name) = class_name_opt {
+                        if class_name.eq(prim_cn2.as_str()) {
+                            return realm.create_boolean(true);
+                        }
+                        class_name_opt =
+                            get_proxy(realm, &class_name).and_then(|p| p.extends.clone());
                     }
                 }
             }
@@ -668,6 +947,15 @@ impl Proxy {
             0,
         )?;
 
+        // script classes can only extend a constructor which has a prototype object
+        objects::set_property2_q(
+            q_ctx,
+            &constructor_ref,
+            "prototype",
+            &objects::create_object_q(q_ctx)?,
+            0,
+        )?;
+
         // todo impl namespace here
         if add_variable_to_global {
             log::trace!("reflection::Proxy::install_class_prop / 8");
@@ -859,7 +1147,12 @@ unsafe extern "C" fn constructor(
         let q_ctx = q_js_rt.get_quickjs_context(context);
 
         let registry = &*q_ctx.proxy_registry.borrow();
-        if let Some(proxy) = registry.get(&class_name) {
+        // a script class which extends a Proxy class is constructed with its own constructor as this_val
+        let (proxy_opt, is_script_subclass) = match registry.get(&class_name) {
+            Some(proxy) => (Some(proxy), false),
+            None => (find_script_base_proxy(context, registry, &this_ref), true),
+        };
+        if let Some(proxy) = proxy_opt {
             if let Some(constructor) = &proxy.constructor {
                 // construct
 
@@ -869,7 +1162,17 @@ unsafe extern "C" fn constructor(
 
                 match constructor_res {
                     Ok(()) => {
-                        let instance_ref_res = new_instance3(proxy, instance_id, q_ctx);
+                        let instance_ref_res =
+                            new_instance3(proxy, instance_id, q_ctx).and_then(|instance_ref| {
+                                if is_script_subclass {
+                                    set_script_subclass_prototype(
+                                        context,
+                                        &instance_ref,
+                                        &this_ref,
+                                    )?;
+                                }
+                                Ok(instance_ref)
+                            });
 
                         match instance_ref_res {
                             Ok(instance_ref) => instance_ref.clone_value_incr_rc(),
@@ -894,6 +1197,92 @@ unsafe extern "C" fn constructor(
     })
 }
 
+/// find the Proxy class a script class extends (e.g. `class Door extends EventTarget {}`) by walking up the prototype chain of its constructor
+unsafe fn find_script_base_proxy<'a>(
+    context: *mut q::JSContext,
+    registry: &'a HashMap<String, Rc<Proxy>>,
+    script_class: &QuickJsValueAdapter,
+) -> Option<&'a Rc<Proxy>> {
+    let mut constructor_ref = objects::get_prototype_of(context, script_class).ok()?;
+    while constructor_ref.is_object() {
+        let name_ref = objects::get_property(context, &constructor_ref, "name").ok()?;
+        if name_ref.is_string() {
+            let class_name = primitives::to_string(context, &name_ref).ok()?;
+            if let Some(proxy) = registry.get(&class_name) {
+                return Some(proxy);
+            }
+        }
+        constructor_ref = objects::get_prototype_of(context, &constructor_ref).ok()?;
+    }
+    None
+}
+
+/// make the prototype of the script class the prototype of a Proxy instance it constructed so the methods of the script class are found
+unsafe fn set_script_subclass_prototype(
+    context: *mut q::JSContext,
+    instance_ref: &QuickJsValueAdapter,
+    script_class: &QuickJsValueAdapter,
+) -> Result<(), JsError> {
+    let prototype_ref = objects::get_property(context, script_class, "prototype")?;
+    let res = q::JS_SetPrototype(
+        context,
+        *instance_ref.borrow_value(),
+        *prototype_ref.borrow_value(),
+    );
+    if res < 0 {
+        return if let Some(err) = QuickJsRealmAdapter::get_exception(context) {
+            Err(err)
+        } else {
+            Err(JsError::new_str("could not set instance proto"))
+        };
+    }
+    Ok(())
+}
+
+/// get a property which is not a member of the Proxy class from the prototype of a Proxy instance
+/// only instances which were constructed by a script class extending the Proxy class have a prototype
+unsafe fn get_prototype_prop(
+    context: *mut q::JSContext,
+    obj_ref: &QuickJsValueAdapter,
+    atom: q::JSAtom,
+    receiver: q::JSValue,
+) -> q::JSValue {
+    match objects::get_prototype_of(context, obj_ref) {
+        Ok(prototype_ref) if prototype_ref.is_object() => {
+            q::JS_GetPropertyInternal(context, *prototype_ref.borrow_value(), atom, receiver, 0)
+        }
+        _ => quickjs_utils::new_undefined(),
+    }
+}
+
+/// the array index a property name represents, e.g. 3 for "3" but not for "03" or "-1"
+fn parse_index(prop_name: &str) -> Option<u32> {
+    let index = prop_name.parse::<u32>().ok()?;
+    if index.to_string() == prop_name {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+/// find the first Proxy in the extends chain of class_name (starting with class_name itself) which matches a predicate
+fn find_in_chain<'a, P>(
+    registry: &'a HashMap<String, Rc<Proxy>>,
+    class_name: &str,
+    predicate: P,
+) -> Option<&'a Rc<Proxy>>
+where
+    P: Fn(&Proxy) -> bool,
+{
+    let mut proxy = registry.get(class_name)?;
+    loop {
+        if predicate(proxy) {
+            return Some(proxy);
+        }
+        proxy = registry.get(proxy.extends.as_ref()?)?;
+    }
+}
+
 pub(crate) struct ProxyInstanceInfo {
     id: usize,
     class_name: String, // todo, store all proxies in an autoidmap with a usize as key and store proxy_class_id here instead of string
@@ -978,7 +1367,11 @@ unsafe extern "C" fn proxy_static_get_prop(
         trace!("proxy_static_get_prop: prop: {}", prop_name);
 
         let registry = &*q_ctx.proxy_registry.borrow();
-        if let Some(proxy) = registry.get(proxy_name.as_str()) {
+        // statics of a script class which extends a Proxy class are looked up in that Proxy class
+        if let Some(proxy) = registry
+            .get(proxy_name.as_str())
+            .or_else(|| find_script_base_proxy(context, registry, &receiver_ref))
+        {
             if proxy.static_methods.contains_key(prop_name) {
                 trace!("found method for {}", prop_name);
 
@@ -1054,7 +1447,7 @@ unsafe extern "C" fn proxy_instance_get_prop(
 ) -> q::JSValue {
     trace!("proxy_instance_get_prop");
 
-    let _obj_ref = QuickJsValueAdapter::new(
+    let obj_ref = QuickJsValueAdapter::new(
         context,
         obj,
         false,
@@ -1079,11 +1472,48 @@ unsafe extern "C" fn proxy_instance_get_prop(
 
         trace!("obj_ref.classname = {}", info.class_name);
 
-        // see if we have a matching method
+        // see if we have a matching method, in this class or one of its base classes
 
         let registry = &*q_ctx.proxy_registry.borrow();
-        let proxy = registry.get(&info.class_name).unwrap();
-        if proxy.methods.contains_key(prop_name) {
+
+        if let Some(index) = parse_index(prop_name) {
+            if let Some(getter) =
+                find_in_chain(registry, &info.class_name, |p| p.indexed_getter.is_some())
+                    .and_then(|p| p.indexed_getter.as_ref())
+            {
+                let res: Result<QuickJsValueAdapter, JsError> =
+                    getter(q_js_rt, q_ctx, &info.id, index);
+                return match res {
+                    Ok(g_val) => g_val.clone_value_incr_rc(),
+                    Err(e) => {
+                        let msg = format!("proxy_instance_indexed_get failed: {}", e.get_message());
+                        let nat_stack = format!(
+                            "    at Proxy instance indexed getter [{}]\n{}",
+                            index,
+                            e.get_stack()
+                        );
+                        let err = errors::new_error(
+                            context,
+                            e.get_name(),
+                            msg.as_str(),
+                            nat_stack.as_str(),
+                        )
+                        .expect("create error failed");
+                        errors::throw(context, err)
+                    }
+                };
+            }
+        }
+
+        let proxy_opt = find_in_chain(registry, &info.class_name, |p| {
+            p.has_instance_member(prop_name)
+        })
+        .or_else(|| find_in_chain(registry, &info.class_name, |p| p.catch_all.is_some()));
+        let proxy = match proxy_opt {
+            Some(proxy) => proxy,
+            None => return get_prototype_prop(context, &obj_ref, atom, receiver),
+        };
+        if proxy.methods.contains_key(prop_name) || proxy.get_lazy_method(prop_name).is_some() {
             trace!("found method for {}", prop_name);
 
             let function_data_ref =
@@ -1113,9 +1543,13 @@ unsafe extern "C" fn proxy_instance_get_prop(
                 .expect("set_property 49671 failed"); // todo report ex
 
             func_ref.clone_value_incr_rc()
-        } else if let Some(getter_setter) = proxy.getters_setters.get(prop_name) {
+        } else if let Some(getter) = proxy
+            .getters_setters
+            .get(prop_name)
+            .map(|getter_setter| &getter_setter.0)
+            .or_else(|| proxy.read_only_getters.get(prop_name))
+        {
             // call the getter
-            let getter = &getter_setter.0;
             let res: Result<QuickJsValueAdapter, JsError> = getter(q_js_rt, q_ctx, &info.id);
             match res {
                 Ok(g_val) => g_val.clone_value_incr_rc(),
@@ -1163,13 +1597,63 @@ unsafe extern "C" fn proxy_instance_get_prop(
     // get method or getter or setter
     // return native func (cache those?)
 }
-#[allow(dead_code)]
 unsafe extern "C" fn proxy_instance_has_prop(
-    _context: *mut q::JSContext,
-    _obj: q::JSValue,
-    _atom: q::JSAtom,
+    context: *mut q::JSContext,
+    obj: q::JSValue,
+    atom: q::JSAtom,
 ) -> ::std::os::raw::c_int {
-    todo!()
+    trace!("proxy_instance_has_prop");
+
+    let obj_ref = QuickJsValueAdapter::new(
+        context,
+        obj,
+        false,
+        false,
+        "reflection::proxy_instance_has_prop obj",
+    );
+
+    QuickJsRuntimeAdapter::do_with(|q_js_rt| {
+        let q_ctx = q_js_rt.get_quickjs_context(context);
+
+        let prop_name = atoms::to_str(context, &atom).expect("could not get name");
+        trace!("proxy_instance_has_prop: {}", prop_name);
+
+        let info = get_proxy_instance_info(&obj);
+
+        let registry = &*q_ctx.proxy_registry.borrow();
+        if find_in_chain(registry, &info.class_name, |p| {
+            p.has_instance_member(prop_name)
+        })
+        .is_some()
+        {
+            return 1;
+        }
+        if let Some(has) = find_in_chain(registry, &info.class_name, |p| p.catch_all_has.is_some())
+            .and_then(|p| p.catch_all_has.as_ref())
+        {
+            if has(q_js_rt, q_ctx, &info.id, prop_name) {
+                return 1;
+            }
+        }
+
+        // own properties (e.g. the cached method functions) and the properties of the prototype chain
+        let own = q::JS_GetOwnProperty(context, std::ptr::null_mut(), obj, atom);
+        if own != 0 {
+            return own;
+        }
+        match objects::get_prototype_of(context, &obj_ref) {
+            Ok(proto) if proto.is_object() => {
+                q::JS_HasProperty(context, *proto.borrow_value(), atom)
+            }
+            Ok(_) => 0,
+            Err(e) => {
+                let err = format!("proxy_instance_has_prop failed: {e}");
+                log::error!("{}", err);
+                let _ = q_ctx.report_ex(err.as_str());
+                -1
+            }
+        }
+    })
 }
 #[allow(dead_code)]
 unsafe extern "C" fn proxy_static_has_prop(
@@ -1209,14 +1693,27 @@ unsafe extern "C" fn proxy_instance_method(
         trace!("proxy_instance_method: {}", func_name);
 
         let registry = &*q_ctx.proxy_registry.borrow();
-        let proxy = registry
-            .get(proxy_instance_info.class_name.as_str())
-            .unwrap();
-        if let Some(method) = proxy.methods.get(func_name.as_str()) {
+        let proxy = find_in_chain(registry, &proxy_instance_info.class_name, |p| {
+            p.methods.contains_key(func_name.as_str())
+                || p.lazy_methods.contains_key(func_name.as_str())
+        })
+        .or_else(|| registry.get(proxy_instance_info.class_name.as_str()))
+        .unwrap();
+        let stats_key = || format!("{}.prototype.{}", proxy_instance_info.class_name, func_name);
+        let m_res_opt: Option<NativeMethodResult> =
+            if let Some(method) = proxy.methods.get(func_name.as_str()) {
+                Some(timed_method_call(stats_key, || {
+                    method(q_js_rt, q_ctx, &proxy_instance_info.id, &args_vec)
+                }))
+            } else {
+                proxy.get_lazy_method(func_name.as_str()).map(|method| {
+                    timed_method_call(stats_key, || {
+                        method(q_js_rt, q_ctx, &proxy_instance_info.id, &args_vec)
+                    })
+                })
+            };
+        if let Some(m_res) = m_res_opt {
             // todo report ex
-            let m_res: Result<QuickJsValueAdapter, JsError> =
-                method(q_js_rt, q_ctx, &proxy_instance_info.id, &args_vec);
-
             match m_res {
                 Ok(m_res_ref) => m_res_ref.clone_value_incr_rc(),
                 Err(e) => {
@@ -1282,7 +1779,10 @@ unsafe extern "C" fn proxy_static_method(
         let registry = &*q_ctx.proxy_registry.borrow();
         let proxy = registry.get(proxy_name.as_str()).unwrap();
         if let Some(method) = proxy.static_methods.get(func_name.as_str()) {
-            let m_res: Result<QuickJsValueAdapter, JsError> = method(q_js_rt, q_ctx, &args_vec);
+            let m_res: NativeMethodResult = timed_method_call(
+                || format!("{}.{}", proxy_name, func_name),
+                || method(q_js_rt, q_ctx, &args_vec),
+            );
             match m_res {
                 Ok(m_res_ref) => m_res_ref.clone_value_incr_rc(),
                 Err(e) => {
@@ -1347,7 +1847,10 @@ unsafe extern "C" fn proxy_static_set_prop(
         trace!("proxy_static_set_prop: {}", proxy_name);
 
         let registry = &*realm.proxy_registry.borrow();
-        if let Some(proxy) = registry.get(proxy_name.as_str()) {
+        if let Some(proxy) = registry
+            .get(proxy_name.as_str())
+            .or_else(|| find_script_base_proxy(context, registry, &receiver_ref))
+        {
             if let Some(getter_setter) = proxy.static_getters_setters.get(prop_name) {
                 // call the setter
                 let setter = &getter_setter.1;
@@ -1417,7 +1920,7 @@ unsafe extern "C" fn proxy_instance_set_prop(
     atom: q::JSAtom,
     value: q::JSValue,
     receiver: q::JSValue,
-    _flags: ::std::os::raw::c_int,
+    flags: ::std::os::raw::c_int,
 ) -> ::std::os::raw::c_int {
     trace!("proxy_instance_set_prop");
 
@@ -1439,12 +1942,41 @@ unsafe extern "C" fn proxy_instance_set_prop(
 
         trace!("obj_ref.classname = {}", info.class_name);
 
-        // see if we have a matching gettersetter
+        // see if we have a matching gettersetter, in this class or one of its base classes
 
         let registry = &*realm.proxy_registry.borrow();
-        let proxy = registry.get(&info.class_name).unwrap();
 
-        if let Some(getter_setter) = proxy.getters_setters.get(prop_name) {
+        if let Some(index) = parse_index(prop_name) {
+            if let Some(setter) =
+                find_in_chain(registry, &info.class_name, |p| p.indexed_setter.is_some())
+                    .and_then(|p| p.indexed_setter.as_ref())
+            {
+                let res: Result<(), JsError> = setter(rt, realm, &info.id, index, value_ref);
+                return match res {
+                    Ok(_) => 0,
+                    Err(e) => {
+                        let err = format!("proxy_instance_set_prop failed: {e}");
+                        log::error!("{}", err);
+                        let _ = realm.report_ex(err.as_str());
+                        -1
+                    }
+                };
+            } else if find_in_chain(registry, &info.class_name, |p| p.indexed_getter.is_some())
+                .is_some()
+            {
+                return reject_read_only_assignment(context, flags, prop_name, &info.class_name);
+            }
+        }
+
+        let proxy_opt = find_in_chain(registry, &info.class_name, |p| {
+            p.getters_setters.contains_key(prop_name) || p.read_only_getters.contains_key(prop_name)
+        })
+        .or_else(|| find_in_chain(registry, &info.class_name, |p| p.catch_all.is_some()));
+
+        if proxy_opt.map_or(false, |p| p.read_only_getters.contains_key(prop_name)) {
+            reject_read_only_assignment(context, flags, prop_name, &info.class_name)
+        } else if let Some(getter_setter) = proxy_opt.and_then(|p| p.getters_setters.get(prop_name))
+        {
             // call the setter
             let setter = &getter_setter.1;
             let res: Result<(), JsError> = setter(rt, realm, &info.id, value_ref);
@@ -1458,7 +1990,7 @@ unsafe extern "C" fn proxy_instance_set_prop(
                     -1
                 }
             }
-        } else if let Some(catch_all_getter_setter) = &proxy.catch_all {
+        } else if let Some(catch_all_getter_setter) = proxy_opt.and_then(|p| p.catch_all.as_ref()) {
             // call the setter
             let setter = &catch_all_getter_setter.1;
             let res: Result<(), JsError> = setter(rt, realm, &info.id, prop_name, value_ref);
@@ -1506,21 +2038,44 @@ unsafe extern "C" fn proxy_instance_set_prop(
     })
 }
 
+/// reject an assignment to a member without a setter, this throws a TypeError for assignments from script
+unsafe fn reject_read_only_assignment(
+    context: *mut q::JSContext,
+    flags: ::std::os::raw::c_int,
+    prop_name: &str,
+    class_name: &str,
+) -> ::std::os::raw::c_int {
+    // assignments from script pass a throw flag, Reflect.set does not and just gets false
+    if flags & (q::JS_PROP_THROW | q::JS_PROP_THROW_STRICT) as i32 == 0 {
+        return 0;
+    }
+    let msg = format!("Cannot assign to read only property '{prop_name}' of {class_name}");
+    let err =
+        errors::new_error(context, "TypeError", msg.as_str(), "").expect("create error failed");
+    errors::throw(context, err);
+    -1
+}
+
 #[cfg(test)]
 pub mod tests {
+    use crate::builder::QuickJsRuntimeBuilder;
     use crate::facades::tests::init_test_rt;
     use crate::jsutils::JsError;
     use crate::jsutils::Script;
     use crate::quickjs_utils::objects::create_object_q;
     use crate::quickjs_utils::{functions, primitives};
+    use crate::quickjsrealmadapter::QuickJsRealmAdapter;
+    use crate::quickjsruntimeadapter::QuickJsRuntimeAdapter;
+    use crate::quickjsvalueadapter::QuickJsValueAdapter;
     use crate::reflection::{
-        get_proxy_instance_proxy_and_instance_id_q, is_proxy_instance_q, Proxy,
-        PROXY_INSTANCE_CLASS_ID,
+        get_proxy_instance_proxy_and_instance_id_q, install_enum, is_proxy_instance_q, EnumValue,
+        NativeMethodResult, Proxy, PROXY_INSTANCE_CLASS_ID,
     };
     use libquickjs_sys as q;
     use log::trace;
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
     use std::collections::HashMap;
+    use std::rc::Rc;
     use std::time::Duration;
 
     thread_local! {
@@ -1572,6 +2127,175 @@ pub mod tests {
         assert!(err.contains("cant run"));
     }
 
+    fn parse_arg(args: &[QuickJsValueAdapter]) -> Result<i32, JsError> {
+        match args.first() {
+            Some(arg) if arg.is_i32() => Ok(arg.to_i32()),
+            Some(arg) => Err(JsError::new(
+                "TypeError".to_string(),
+                format!("expected an integer but got {}", arg.get_js_type()),
+                "".to_string(),
+            )),
+            None => Err(JsError::new(
+                "TypeError".to_string(),
+                "expected an integer".to_string(),
+                "".to_string(),
+            )),
+        }
+    }
+
+    #[test]
+    pub fn test_native_method_result() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("TestNativeMethodResult")
+                .static_method("double", |_rt, realm, args| -> NativeMethodResult {
+                    let n = parse_arg(args)?;
+                    realm.create_i32(n * 2)
+                })
+                .install(q_ctx, true)
+                .expect("could not install proxy");
+            q_ctx
+                .eval(Script::new(
+                    "test_native_method_result.es",
+                    r#"
+                let ok = TestNativeMethodResult.double(21);
+                let failed;
+                try {
+                    TestNativeMethodResult.double('21');
+                } catch (err) {
+                    failed = (err instanceof TypeError) + '|' + err.name + '|' + err.message;
+                }
+                ok + '|' + failed;
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(
+            res,
+            "42|true|TypeError|proxy_static_method failed: expected an integer but got String"
+        );
+    }
+
+    #[test]
+    pub fn test_proxy_lazy_method() {
+        let rt = init_test_rt();
+        let (before, after, res) = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            let materialized = Rc::new(Cell::new(0));
+            let materialized2 = materialized.clone();
+            Proxy::new()
+                .constructor(|_q_js_rt, _q_ctx, _id, _args| Ok(()))
+                .lazy_method("calc", move || {
+                    materialized2.set(materialized2.get() + 1);
+                    Box::new(
+                        |_rt: &QuickJsRuntimeAdapter,
+                         realm: &QuickJsRealmAdapter,
+                         _instance_id: &usize,
+                         args: &[QuickJsValueAdapter]| {
+                            realm.create_i32(args[0].to_i32() * 2)
+                        },
+                    )
+                })
+                .name("LazyTest")
+                .install(q_ctx, true)
+                .expect("install failed");
+            q_ctx
+                .eval(Script::new(
+                    "test_proxy_lazy_method.es",
+                    "globalThis.lazyTestInstance = new LazyTest();",
+                ))
+                .expect("script failed");
+            let before = materialized.get();
+            let res = q_ctx
+                .eval(Script::new(
+                    "test_proxy_lazy_method2.es",
+                    "lazyTestInstance.calc(4) + new LazyTest().calc(5);",
+                ))
+                .expect("script failed");
+            (before, materialized.get(), res.to_i32())
+        });
+        assert_eq!(before, 0);
+        assert_eq!(after, 1);
+        assert_eq!(res, 18);
+    }
+
+    #[test]
+    pub fn test_proxy_method_stats() {
+        let rt = QuickJsRuntimeBuilder::new()
+            .proxy_method_stats(true)
+            .build();
+        let stats = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            Proxy::new()
+                .constructor(|_q_js_rt, _q_ctx, _id, _args| Ok(()))
+                .method("work", |_rt, realm, _instance_id, _args| {
+                    std::thread::sleep(Duration::from_millis(1));
+                    realm.create_null()
+                })
+                .static_method("staticWork", |_rt, realm, _args| realm.create_null())
+                .name("StatsTest")
+                .install(q_ctx, true)
+                .expect("install failed");
+            q_ctx
+                .eval(Script::new(
+                    "test_proxy_method_stats.es",
+                    "let statsTest = new StatsTest(); for (let x = 0; x < 10; x++) {statsTest.work();} StatsTest.staticWork();",
+                ))
+                .expect("script failed");
+            crate::reflection::method_stats()
+        });
+        let work_stats = stats
+            .get("StatsTest.prototype.work")
+            .expect("no stats for work");
+        assert_eq!(work_stats.call_count, 10);
+        assert!(work_stats.total_duration >= Duration::from_millis(10));
+        let static_stats = stats
+            .get("StatsTest.staticWork")
+            .expect("no stats for staticWork");
+        assert_eq!(static_stats.call_count, 1);
+    }
+
+    #[test]
+    pub fn test_install_enum() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let q_ctx = q_js_rt.get_main_realm();
+            install_enum(
+                q_ctx,
+                "TestEnum",
+                &[
+                    ("First", EnumValue::Int(1)),
+                    ("Second", "second".into()),
+                    ("Third", EnumValue::F64(3.5)),
+                ],
+            )
+            .expect("install failed");
+            let res = q_ctx
+                .eval(Script::new(
+                    "test_install_enum.es",
+                    r#"
+                TestEnum.First = 100;
+                TestEnum.Fourth = 4;
+                let strictFailed = false;
+                try {
+                    (function() {'use strict'; TestEnum.Second = 'changed';})();
+                } catch (ex) {
+                    strictFailed = ex instanceof TypeError;
+                }
+                TestEnum = null;
+                [TestEnum.First, TestEnum.Second, TestEnum.Third, TestEnum.Fourth, strictFailed].join(',');
+            "#,
+                ))
+                .expect("script failed");
+            res.to_string().expect("not a string")
+        });
+        assert_eq!(res, "1,second,3.5,,true");
+    }
+
     #[test]
     pub fn test_proxy_instanceof() {
         log::info!("> test_proxy_instanceof");
@@ -1613,6 +2337,324 @@ pub mod tests {
         });
     }
 
+    #[test]
+    pub fn test_proxy_extends() {
+        log::info!("> test_proxy_extends");
+
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .constructor(|_rt, _realm, _id, _args| Ok(()))
+                .namespace(&["com", "company"])
+                .name("Base")
+                .method("greet", |_rt, realm, _id, args| {
+                    let name = args[0].to_string()?;
+                    realm.create_string(format!("hello {name}").as_str())
+                })
+                .getter("kind", |_rt, realm, _id| realm.create_string("base"))
+                .install(realm, true)
+                .expect("could not install Base");
+            Proxy::new()
+                .constructor(|_rt, _realm, _id, _args| Ok(()))
+                .namespace(&["com", "company"])
+                .name("Derived")
+                .extends("com.company.Base")
+                .method("shout", |_rt, realm, _id, _args| realm.create_string("HEY"))
+                .install(realm, true)
+                .expect("could not install Derived");
+            assert!(Proxy::new()
+                .name("Orphan")
+                .extends("com.company.Missing")
+                .install(realm, true)
+                .is_err());
+            let res = realm
+                .eval(Script::new(
+                    "test_proxy_extends.js",
+                    r#"
+                const d = new com.company.Derived();
+                const b = new com.company.Base();
+                [d instanceof com.company.Derived, d instanceof com.company.Base, b instanceof com.company.Derived, d.greet('world'), d.shout(), d.kind].join(',');
+            "#,
+                ))
+                .expect("script failed");
+            res.to_string().expect("not a string")
+        });
+        assert_eq!(res, "true,true,false,hello world,HEY,base");
+    }
+
+    #[test]
+    pub fn test_script_class_extends_proxy() {
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .constructor(|_rt, _realm, _id, _args| Ok(()))
+                .name("Vehicle")
+                .method("wheels", |_rt, realm, _id, _args| realm.create_i32(4))
+                .install(realm, true)
+                .expect("could not install Vehicle");
+            let res = realm
+                .eval(Script::new(
+                    "test_script_class_extends_proxy.js",
+                    r#"
+                class Bike extends Vehicle {
+                    constructor(color) {
+                        super();
+                        this.color = color;
+                    }
+                    describe() {
+                        return this.color + ' with ' + this.wheels() + ' wheels';
+                    }
+                }
+                class Trike extends Bike {}
+                const t = new Trike('red');
+                [t instanceof Vehicle, 'describe' in t, t.describe(), new Vehicle().describe].join(',');
+            "#,
+                ))
+                .expect("script failed");
+            res.to_string().expect("not a string")
+        });
+        assert_eq!(res, "true,true,red with 4 wheels,");
+    }
+
+    #[test]
+    pub fn test_read_only_getter() {
+        log::info!("> test_read_only_getter");
+
+        thread_local! {
+            static PERSONS: RefCell<HashMap<usize, (String, String)>> = RefCell::new(HashMap::new())
+        }
+
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("Person")
+                .constructor(|_rt, _realm, id, args| {
+                    let first = args[0].to_string()?;
+                    let last = args[1].to_string()?;
+                    PERSONS.with(|rc| rc.borrow_mut().insert(id, (first, last)));
+                    Ok(())
+                })
+                .getter_setter(
+                    "first",
+                    |_rt, realm, id| {
+                        let first = PERSONS.with(|rc| rc.borrow()[id].0.clone());
+                        realm.create_string(first.as_str())
+                    },
+                    |_rt, _realm, id, val| {
+                        let first = val.to_string()?;
+                        PERSONS.with(|rc| rc.borrow_mut().get_mut(id).unwrap().0 = first);
+                        Ok(())
+                    },
+                )
+                .read_only_getter("fullName", |_rt, realm, id| {
+                    let full_name = PERSONS.with(|rc| {
+                        let persons = &*rc.borrow();
+                        let (first, last) = &persons[id];
+                        format!("{first} {last}")
+                    });
+                    realm.create_string(full_name.as_str())
+                })
+                .finalizer(|_rt, _realm, id| {
+                    PERSONS.with(|rc| rc.borrow_mut().remove(&id));
+                })
+                .install(realm, true)
+                .expect("could not install Person");
+            let res = realm
+                .eval(Script::new(
+                    "test_read_only_getter.js",
+                    r#"
+                const p = new Person('Ada', 'Byron');
+                const before = p.fullName;
+                p.first = 'Augusta';
+                let strictError;
+                try {
+                    (function() { 'use strict'; p.fullName = 'someone else'; })();
+                } catch (err) {
+                    strictError = err instanceof TypeError;
+                }
+                [before, p.fullName, strictError, Reflect.set(p, 'fullName', 'x'), p.fullName].join(',');
+            "#,
+                ))
+                .expect("script failed");
+            res.to_string().expect("not a string")
+        });
+        assert_eq!(res, "Ada Byron,Augusta Byron,true,false,Augusta Byron");
+    }
+
+    #[test]
+    pub fn test_getter_setter() {
+        thread_local! {
+            static VALUES: RefCell<HashMap<usize, i32>> = RefCell::new(HashMap::new())
+        }
+
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("Cell")
+                .constructor(|_rt, _realm, id, args| {
+                    VALUES.with(|rc| rc.borrow_mut().insert(id, args[0].to_i32()));
+                    Ok(())
+                })
+                .getter_setter(
+                    "value",
+                    |_rt, realm, id| realm.create_i32(VALUES.with(|rc| rc.borrow()[id])),
+                    |_rt, _realm, id, val| {
+                        VALUES.with(|rc| rc.borrow_mut().insert(*id, val.to_i32()));
+                        Ok(())
+                    },
+                )
+                .finalizer(|_rt, _realm, id| {
+                    VALUES.with(|rc| rc.borrow_mut().remove(&id));
+                })
+                .install(realm, true)
+                .expect("could not install Cell");
+            realm
+                .eval(Script::new(
+                    "test_getter_setter.js",
+                    r#"
+                const a = new Cell(1);
+                const b = new Cell(2);
+                a.value = 10;
+                b.value += 5;
+                [a.value, b.value].join(',');
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "10,7");
+    }
+
+    #[test]
+    pub fn test_indexed_getter_setter() {
+        thread_local! {
+            static LISTS: RefCell<HashMap<usize, Vec<i32>>> = RefCell::new(HashMap::new())
+        }
+
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("IntList")
+                .constructor(|_rt, _realm, id, args| {
+                    let len = args[0].to_i32() as usize;
+                    LISTS.with(|rc| rc.borrow_mut().insert(id, vec![0; len]));
+                    Ok(())
+                })
+                .read_only_getter("length", |_rt, realm, id| {
+                    realm.create_i32(LISTS.with(|rc| rc.borrow()[id].len()) as i32)
+                })
+                .indexed_getter(|_rt, realm, id, index| {
+                    match LISTS.with(|rc| rc.borrow()[id].get(index as usize).copied()) {
+                        Some(val) => realm.create_i32(val),
+                        None => realm.create_undefined(),
+                    }
+                })
+                .indexed_setter(|_rt, _realm, id, index, val| {
+                    LISTS.with(|rc| {
+                        match rc.borrow_mut().get_mut(id).unwrap().get_mut(index as usize) {
+                            Some(elem) => {
+                                *elem = val.to_i32();
+                                Ok(())
+                            }
+                            None => Err(JsError::new_string(format!(
+                                "index {index} is out of bounds"
+                            ))),
+                        }
+                    })
+                })
+                .finalizer(|_rt, _realm, id| {
+                    LISTS.with(|rc| rc.borrow_mut().remove(&id));
+                })
+                .install(realm, true)
+                .expect("could not install IntList");
+            realm
+                .eval(Script::new(
+                    "test_indexed_getter_setter.js",
+                    r#"
+                const list = new IntList(3);
+                for (let i = 0; i < list.length; i++) {
+                    list[i] = i * 10;
+                }
+                const values = [];
+                for (let i = 0; i < list.length; i++) {
+                    values.push(list[i]);
+                }
+                [values.join(' '), typeof list[3], list.length].join(',');
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "0 10 20,undefined,3");
+    }
+
+    #[test]
+    pub fn test_catch_all() {
+        thread_local! {
+            static STORES: RefCell<HashMap<usize, HashMap<String, String>>> = RefCell::new(HashMap::new())
+        }
+
+        let rt = init_test_rt();
+        let res = rt.exe_rt_task_in_event_loop(|q_js_rt| {
+            let realm = q_js_rt.get_main_realm();
+            Proxy::new()
+                .name("Store")
+                .constructor(|_rt, _realm, id, _args| {
+                    STORES.with(|rc| rc.borrow_mut().insert(id, HashMap::new()));
+                    Ok(())
+                })
+                .read_only_getter("size", |_rt, realm, id| {
+                    realm.create_i32(STORES.with(|rc| rc.borrow()[id].len()) as i32)
+                })
+                .catch_all_getter_setter(
+                    |_rt, realm, id, name| {
+                        let val = STORES.with(|rc| rc.borrow()[id].get(name).cloned());
+                        realm.create_string(
+                            val.unwrap_or_else(|| format!("val_{name}")).as_str(),
+                        )
+                    },
+                    |_rt, _realm, id, name, val| {
+                        let val = val.to_string()?;
+                        STORES.with(|rc| {
+                            rc.borrow_mut()
+                                .get_mut(id)
+                                .unwrap()
+                                .insert(name.to_string(), val)
+                        });
+                        Ok(())
+                    },
+                )
+                .catch_all_has(|_rt, _realm, id, name| {
+                    STORES.with(|rc| rc.borrow()[id].contains_key(name))
+                })
+                .finalizer(|_rt, _realm, id| {
+                    STORES.with(|rc| rc.borrow_mut().remove(&id));
+                })
+                .install(realm, true)
+                .expect("could not install Store");
+            realm
+                .eval(Script::new(
+                    "test_catch_all.js",
+                    r#"
+                const store = new Store();
+                store.color = 'red';
+                [store.anything, store.other_key, store.color, store.size, 'color' in store, 'missing' in store, 'size' in store].join(',');
+            "#,
+                ))
+                .expect("script failed")
+                .to_string()
+                .expect("not a string")
+        });
+        assert_eq!(res, "val_anything,val_other_key,red,1,true,false,true");
+    }
+
     #[test]
     pub fn test_rest_props() {
         log::info!("> test_rest_props");
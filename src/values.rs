@@ -1,5 +1,6 @@
 use crate::facades::QuickjsRuntimeFacadeInner;
 use crate::jsutils::{JsError, JsValueType};
+use crate::quickjs_utils::{promises, typedarrays};
 use crate::quickjsrealmadapter::QuickJsRealmAdapter;
 use crate::quickjsvalueadapter::QuickJsValueAdapter;
 use crate::reflection::JsProxyInstanceId;
@@ -70,6 +71,10 @@ impl CachedJsObjectRef {
             drop_action: Mutex::new(Some(Box::new(drop_action))),
         }
     }
+    /// the id of the realm the object is cached in
+    pub(crate) fn get_realm_id(&self) -> &str {
+        self.realm_id.as_str()
+    }
     pub async fn to_json_string(&self) -> Result<String, JsError> {
         let id = self.id;
         let realm_name = self.realm_id.clone();
@@ -199,6 +204,25 @@ impl CachedJsPromiseRef {
         self.cached_object.to_json_string().await
     }
 
+    /// get the reason this promise was rejected with without adding a then or catch reaction
+    /// returns None if the promise is pending or fulfilled
+    /// this needs to be called from the worker thread of the runtime with the realm the promise was created in
+    pub fn rejection_reason(
+        &self,
+        realm: &QuickJsRealmAdapter,
+        mark_handled: bool,
+    ) -> Result<Option<JsValueFacade>, JsError> {
+        if realm.get_realm_id() != self.cached_object.realm_id {
+            return Err(JsError::new_str("promise was created in another realm"));
+        }
+        realm.with_cached_object(self.cached_object.id, |obj| {
+            match promises::get_rejection_reason_q(realm, obj, mark_handled)? {
+                Some(reason) => Ok(Some(realm.to_js_value_facade(&reason)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
     pub fn get_promise_result_sync(&self) -> Result<Result<JsValueFacade, JsValueFacade>, JsError> {
         block_on(self.get_promise_result())
     }
@@ -532,6 +556,24 @@ impl JsValueFacade {
             }
         }
     }
+    /// get a copy of the bytes of an ArrayBuffer or TypedArray, returns None for other values
+    /// for an ArrayBuffer the bytes are copied in the worker thread of the runtime, this method will block until that is done
+    pub fn as_array_buffer_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            JsValueFacade::TypedArray { buffer, .. } => Some(buffer.clone()),
+            JsValueFacade::JsObject { cached_object } => cached_object
+                .with_obj_sync(|realm, obj| {
+                    if typedarrays::is_array_buffer_q(realm, obj) {
+                        typedarrays::get_array_buffer_buffer_copy_q(realm, obj).ok()
+                    } else {
+                        None
+                    }
+                })
+                .ok()
+                .flatten(),
+            _ => None,
+        }
+    }
     pub fn is_null_or_undefined(&self) -> bool {
         matches!(self, JsValueFacade::Null | JsValueFacade::Undefined)
     }